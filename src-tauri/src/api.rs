@@ -0,0 +1,370 @@
+use crate::models::HistoryFilter;
+use crate::state::AppState;
+use anyhow::Result;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use std::net::{Ipv4Addr, SocketAddr};
+use tokio::net::TcpListener;
+
+/// GET /history 单次最多返回的条目数
+const MAX_HISTORY_LIMIT: i32 = 500;
+
+/// 返回给调用方的错误，响应体为 {"error": "..."}
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn internal(error: anyhow::Error) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": self.message }));
+        (self.status, body).into_response()
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct HistoryQuery {
+    limit: Option<i32>,
+    offset: Option<i32>,
+    search: Option<String>,
+    subtype: Option<String>,
+    source_app: Option<String>,
+    start_ms: Option<i64>,
+    end_ms: Option<i64>,
+    search_metadata: Option<bool>,
+    favorites_only: Option<bool>,
+    preview_only: Option<bool>,
+}
+
+/// POST /copy 的请求体，content 和 id 二选一
+#[derive(Debug, Deserialize)]
+struct CopyRequest {
+    content: Option<String>,
+    id: Option<String>,
+}
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/history", get(history))
+        .route("/entry/:id", get(entry))
+        .route("/copy", post(copy))
+        .with_state(state)
+}
+
+/// 在 127.0.0.1 上启动 API，直到监听出错才返回
+pub async fn serve(state: AppState, port: u16) -> Result<()> {
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port))).await?;
+    log::info!(
+        "[HttpApi] 本地 API 已启动: http://{}",
+        listener.local_addr()?
+    );
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
+
+/// 配置中启用了 API 时在后台启动服务
+pub async fn spawn_if_enabled(state: &AppState) {
+    let api = match state.get_config().await {
+        Ok(config) => config.api,
+        Err(e) => {
+            log::error!("[HttpApi] 读取配置失败: {}", e);
+            return;
+        }
+    };
+    if !api.enabled {
+        return;
+    }
+
+    let state = state.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = serve(state, api.port).await {
+            log::error!("[HttpApi] 本地 API 启动失败 (端口 {}): {}", api.port, e);
+        }
+    });
+}
+
+async fn history(
+    State(state): State<AppState>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, MAX_HISTORY_LIMIT);
+    let filter = HistoryFilter {
+        subtype: query.subtype,
+        start_ms: query.start_ms,
+        end_ms: query.end_ms,
+        source_app: query.source_app,
+        search_metadata: query.search_metadata.unwrap_or(false),
+        favorites_only: query.favorites_only.unwrap_or(false),
+        preview_only: query.preview_only.unwrap_or(false),
+    };
+
+    let entries = state
+        .get_clipboard_history(Some(limit), query.offset, query.search, filter)
+        .await
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e.to_string()))?;
+    Ok(Json(entries))
+}
+
+async fn entry(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    match state.get_entry(id).await.map_err(ApiError::internal)? {
+        Some(entry) => Ok(Json(entry)),
+        None => Err(ApiError::new(StatusCode::NOT_FOUND, "Entry not found")),
+    }
+}
+
+async fn copy(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CopyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    authorize(&state, &headers).await?;
+
+    let result = match (request.content, request.id) {
+        // 新内容直接写入剪贴板，由监听器像普通复制一样记录
+        (Some(content), None) => state.set_plain_text(content).await,
+        (None, Some(id)) => {
+            let entry = state
+                .get_entry(id)
+                .await
+                .map_err(ApiError::internal)?
+                .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, "Entry not found"))?;
+            let content = match (entry.content_type.as_str(), entry.content_data) {
+                ("text", Some(content)) => content,
+                _ => {
+                    return Err(ApiError::new(
+                        StatusCode::BAD_REQUEST,
+                        "Only text entries can be copied",
+                    ))
+                }
+            };
+            state.copy_to_clipboard(content).await
+        }
+        _ => {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "Exactly one of `content` or `id` is required",
+            ))
+        }
+    };
+    result.map_err(ApiError::internal)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 写接口需要 `Authorization: Bearer <token>`，未配置 token 时写接口不可用
+async fn authorize(state: &AppState, headers: &HeaderMap) -> Result<(), ApiError> {
+    let expected = state
+        .get_config()
+        .await
+        .map_err(ApiError::internal)?
+        .api
+        .token
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| {
+            ApiError::new(
+                StatusCode::FORBIDDEN,
+                "Write endpoints are disabled until api.token is configured",
+            )
+        })?;
+
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match provided {
+        Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => Ok(()),
+        _ => Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "Missing or invalid API token",
+        )),
+    }
+}
+
+/// 比较时不因第一个不同的字节提前返回，避免通过响应时间猜测 token
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ClipboardEntry, ContentType};
+    use tempfile::TempDir;
+
+    const TOKEN: &str = "test-token";
+
+    async fn create_test_state(token: Option<&str>) -> (AppState, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let state = AppState::for_test(temp_dir.path()).await.unwrap();
+        state.config_manager.lock().await.config.api.token = token.map(str::to_string);
+
+        (state, temp_dir)
+    }
+
+    /// 在随机端口上启动 API，返回基础地址
+    async fn spawn_server(state: AppState) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, router(state)).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    async fn insert_text(state: &AppState, content: &str, created_at: i64) -> ClipboardEntry {
+        let mut entry = ClipboardEntry::new(
+            ContentType::Text,
+            Some(content.to_string()),
+            format!("hash_{}", content),
+            Some("TestApp".to_string()),
+            None,
+        );
+        entry.created_at = created_at;
+        state.save_entry(entry).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_history_and_entry() {
+        let (state, _temp_dir) = create_test_state(None).await;
+        let first = insert_text(&state, "first entry", 1_000).await;
+        insert_text(&state, "second entry", 2_000).await;
+        let base = spawn_server(state).await;
+        let client = reqwest::Client::new();
+
+        let history: Vec<ClipboardEntry> = client
+            .get(format!("{}/history", base))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let contents: Vec<_> = history
+            .iter()
+            .map(|entry| entry.content_data.as_deref().unwrap())
+            .collect();
+        assert_eq!(contents, vec!["second entry", "first entry"]);
+
+        let searched: Vec<ClipboardEntry> = client
+            .get(format!("{}/history?search=first&limit=10", base))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(searched.len(), 1);
+        assert_eq!(searched[0].id, first.id);
+
+        let entry: ClipboardEntry = client
+            .get(format!("{}/entry/{}", base, first.id))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(entry.content_data.as_deref(), Some("first entry"));
+
+        let missing = client
+            .get(format!("{}/entry/does-not-exist", base))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+
+        let bad_filter = client
+            .get(format!("{}/history?subtype=not_a_subtype", base))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(bad_filter.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_copy_requires_token() {
+        let (state, _temp_dir) = create_test_state(Some(TOKEN)).await;
+        let base = spawn_server(state).await;
+        let client = reqwest::Client::new();
+        let body = serde_json::json!({ "id": "does-not-exist" });
+
+        let missing = client
+            .post(format!("{}/copy", base))
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let wrong = client
+            .post(format!("{}/copy", base))
+            .bearer_auth("wrong-token")
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(wrong.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        // With a valid token the request reaches the handler (the entry doesn't exist)
+        let authorized = client
+            .post(format!("{}/copy", base))
+            .bearer_auth(TOKEN)
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(authorized.status(), reqwest::StatusCode::NOT_FOUND);
+
+        let invalid = client
+            .post(format!("{}/copy", base))
+            .bearer_auth(TOKEN)
+            .json(&serde_json::json!({ "content": "a", "id": "b" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(invalid.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_copy_disabled_without_configured_token() {
+        let (state, _temp_dir) = create_test_state(None).await;
+        let base = spawn_server(state).await;
+
+        let response = reqwest::Client::new()
+            .post(format!("{}/copy", base))
+            .bearer_auth("anything")
+            .json(&serde_json::json!({ "content": "hello" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret-longer"));
+    }
+}
@@ -0,0 +1,418 @@
+use crate::error::{CommandError, ErrorCode};
+use crate::models::RichText;
+use anyhow::Result;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// 打开系统剪贴板，测试中可以替换为模拟实现
+pub trait ClipboardFactory {
+    type Clipboard;
+
+    fn open(&self) -> std::result::Result<Self::Clipboard, arboard::Error>;
+}
+
+/// 通过 arboard 访问系统剪贴板
+pub struct SystemClipboard;
+
+impl ClipboardFactory for SystemClipboard {
+    type Clipboard = arboard::Clipboard;
+
+    fn open(&self) -> std::result::Result<arboard::Clipboard, arboard::Error> {
+        arboard::Clipboard::new()
+    }
+}
+
+/// 应用写入和读取剪贴板的入口，AppState 通过它访问剪贴板，测试中注入 MockClipboard
+pub trait ClipboardBackend: Send + Sync {
+    /// 清空剪贴板后只写入纯文本，不残留其他格式
+    fn set_text(&self, text: &str) -> Result<()>;
+
+    fn set_image(&self, image: arboard::ImageData<'_>) -> Result<()>;
+
+    /// 以文件引用的形式写入文件列表，粘贴时得到文件本身
+    fn set_file_list(&self, paths: &[PathBuf]) -> Result<()>;
+
+    /// 写入富文本，同时写入纯文本供不支持富文本的应用粘贴
+    fn set_rich_text(&self, rich_text: &RichText, plain_text: &str) -> Result<()>;
+
+    /// 剪贴板中没有文本时返回 None
+    fn get_text(&self) -> Result<Option<String>>;
+
+    /// 剪贴板中没有图片时返回 None
+    fn get_image(&self) -> Result<Option<arboard::ImageData<'static>>>;
+
+    /// 读取文本附带的 HTML 和 RTF，没有对应格式时字段为 None
+    fn get_rich_text(&self) -> Result<RichText>;
+
+    /// 读取复制的文件列表（Finder / 资源管理器中复制的文件），没有文件时返回空列表
+    fn get_file_list(&self) -> Result<Vec<PathBuf>>;
+
+    /// 剪贴板的变化计数，每次写入剪贴板（包括再次复制相同内容）都会改变；
+    /// 平台不提供时返回 None
+    fn change_count(&self) -> Option<u64>;
+}
+
+/// 基于 arboard 的系统剪贴板，剪贴板被占用时按 retry 重试
+#[derive(Default)]
+pub struct ArboardBackend {
+    pub retry: RetryPolicy,
+}
+
+impl ClipboardBackend for ArboardBackend {
+    fn set_text(&self, text: &str) -> Result<()> {
+        with_clipboard(&SystemClipboard, &self.retry, |clipboard| {
+            clipboard.clear()?;
+            clipboard.set_text(text)
+        })
+    }
+
+    fn set_image(&self, image: arboard::ImageData<'_>) -> Result<()> {
+        with_clipboard(&SystemClipboard, &self.retry, |clipboard| {
+            clipboard.set_image(image.clone())
+        })
+    }
+
+    fn set_file_list(&self, paths: &[PathBuf]) -> Result<()> {
+        crate::clipboard::file_reference::set_file_references(paths)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn set_rich_text(&self, rich_text: &RichText, plain_text: &str) -> Result<()> {
+        crate::clipboard::rich_text::write(rich_text, plain_text)
+    }
+
+    /// 其他平台只支持写入 HTML（没有 HTML 时只写入纯文本）
+    #[cfg(not(target_os = "macos"))]
+    fn set_rich_text(&self, rich_text: &RichText, plain_text: &str) -> Result<()> {
+        with_clipboard(&SystemClipboard, &self.retry, |clipboard| {
+            clipboard.clear()?;
+            match &rich_text.html {
+                Some(html) => clipboard.set_html(html.as_str(), Some(plain_text)),
+                None => clipboard.set_text(plain_text),
+            }
+        })
+    }
+
+    fn get_text(&self) -> Result<Option<String>> {
+        with_clipboard(&SystemClipboard, &self.retry, |clipboard| {
+            optional(clipboard.get_text())
+        })
+    }
+
+    fn get_image(&self) -> Result<Option<arboard::ImageData<'static>>> {
+        with_clipboard(&SystemClipboard, &self.retry, |clipboard| {
+            optional(clipboard.get_image())
+        })
+    }
+
+    fn get_rich_text(&self) -> Result<RichText> {
+        let html = with_clipboard(&SystemClipboard, &self.retry, |clipboard| {
+            optional(clipboard.get().html())
+        })?;
+        Ok(crate::clipboard::rich_text::with_rtf(html))
+    }
+
+    #[cfg(target_os = "macos")]
+    fn get_file_list(&self) -> Result<Vec<PathBuf>> {
+        let paths = with_clipboard(&SystemClipboard, &self.retry, |clipboard| {
+            optional(clipboard.get().file_list())
+        })?;
+        Ok(paths.unwrap_or_default())
+    }
+
+    /// 其他平台无法以文件引用的形式写回剪贴板（set_file_list 不支持），
+    /// 不记录文件列表，复制的文件按文件名文本记录
+    #[cfg(not(target_os = "macos"))]
+    fn get_file_list(&self) -> Result<Vec<PathBuf>> {
+        Ok(Vec::new())
+    }
+
+    #[cfg(target_os = "macos")]
+    fn change_count(&self) -> Option<u64> {
+        use cocoa::appkit::NSPasteboard;
+        use cocoa::base::nil;
+
+        let count = unsafe { NSPasteboard::generalPasteboard(nil).changeCount() };
+        Some(count as u64)
+    }
+
+    #[cfg(target_os = "windows")]
+    fn change_count(&self) -> Option<u64> {
+        // 没有访问剪贴板的权限时返回 0
+        match unsafe { winapi::um::winuser::GetClipboardSequenceNumber() } {
+            0 => None,
+            count => Some(count as u64),
+        }
+    }
+
+    /// X11 / Wayland 没有通用的变化计数
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    fn change_count(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// 剪贴板中没有对应格式（ContentNotAvailable）时返回 None
+fn optional<T>(
+    result: std::result::Result<T, arboard::Error>,
+) -> std::result::Result<Option<T>, arboard::Error> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(arboard::Error::ContentNotAvailable) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// 内存中的剪贴板，供测试检查写入的内容
+#[cfg(test)]
+#[derive(Default)]
+pub struct MockClipboard {
+    text: std::sync::Mutex<Option<String>>,
+    image: std::sync::Mutex<Option<arboard::ImageData<'static>>>,
+    files: std::sync::Mutex<Option<Vec<PathBuf>>>,
+    rich_text: std::sync::Mutex<RichText>,
+    writes: std::sync::atomic::AtomicUsize,
+}
+
+#[cfg(test)]
+impl MockClipboard {
+    pub fn text(&self) -> Option<String> {
+        self.text.lock().unwrap().clone()
+    }
+
+    pub fn image_size(&self) -> Option<(usize, usize)> {
+        self.image
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|image| (image.width, image.height))
+    }
+
+    pub fn rich_text(&self) -> RichText {
+        self.rich_text.lock().unwrap().clone()
+    }
+
+    pub fn files(&self) -> Option<Vec<PathBuf>> {
+        self.files.lock().unwrap().clone()
+    }
+
+    pub fn writes(&self) -> usize {
+        self.writes.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// 清空所有格式并记录一次写入
+    fn clear(&self) {
+        *self.text.lock().unwrap() = None;
+        *self.image.lock().unwrap() = None;
+        *self.files.lock().unwrap() = None;
+        *self.rich_text.lock().unwrap() = RichText::default();
+        self.writes
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+impl ClipboardBackend for MockClipboard {
+    fn set_text(&self, text: &str) -> Result<()> {
+        self.clear();
+        *self.text.lock().unwrap() = Some(text.to_string());
+        Ok(())
+    }
+
+    fn set_image(&self, image: arboard::ImageData<'_>) -> Result<()> {
+        self.clear();
+        *self.image.lock().unwrap() = Some(image.to_owned_img());
+        Ok(())
+    }
+
+    fn set_file_list(&self, paths: &[PathBuf]) -> Result<()> {
+        self.clear();
+        *self.files.lock().unwrap() = Some(paths.to_vec());
+        Ok(())
+    }
+
+    fn set_rich_text(&self, rich_text: &RichText, plain_text: &str) -> Result<()> {
+        self.clear();
+        *self.text.lock().unwrap() = Some(plain_text.to_string());
+        *self.rich_text.lock().unwrap() = rich_text.clone();
+        Ok(())
+    }
+
+    fn get_text(&self) -> Result<Option<String>> {
+        Ok(self.text())
+    }
+
+    fn get_image(&self) -> Result<Option<arboard::ImageData<'static>>> {
+        Ok(self.image.lock().unwrap().clone())
+    }
+
+    fn get_rich_text(&self) -> Result<RichText> {
+        Ok(self.rich_text())
+    }
+
+    fn get_file_list(&self) -> Result<Vec<PathBuf>> {
+        Ok(self.files().unwrap_or_default())
+    }
+
+    fn change_count(&self) -> Option<u64> {
+        Some(self.writes() as u64)
+    }
+}
+
+/// 剪贴板被其他进程占用时的重试策略，每次重试的等待时间翻倍
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    pub initial_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        // 最多等待 20 + 40 + 80 + 160 = 300ms
+        Self {
+            attempts: 5,
+            initial_delay: Duration::from_millis(20),
+        }
+    }
+}
+
+/// 打开剪贴板并执行 op。剪贴板被占用（ClipboardOccupied）时按策略重试，
+/// 重试用尽后返回 clipboard_busy 错误；其他错误立即返回。会阻塞当前线程
+pub fn with_clipboard<F, T>(
+    factory: &F,
+    policy: &RetryPolicy,
+    mut op: impl FnMut(&mut F::Clipboard) -> std::result::Result<T, arboard::Error>,
+) -> Result<T>
+where
+    F: ClipboardFactory,
+{
+    let attempts = policy.attempts.max(1);
+    let mut delay = policy.initial_delay;
+    for attempt in 1..=attempts {
+        match factory.open().and_then(|mut clipboard| op(&mut clipboard)) {
+            Ok(value) => return Ok(value),
+            Err(arboard::Error::ClipboardOccupied) if attempt < attempts => {
+                log::debug!(
+                    "[Clipboard] 剪贴板被占用，{:?} 后重试 ({}/{})",
+                    delay,
+                    attempt,
+                    attempts
+                );
+                std::thread::sleep(delay);
+                delay *= 2;
+            }
+            Err(arboard::Error::ClipboardOccupied) => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    log::warn!("[Clipboard] 剪贴板持续被占用，放弃访问");
+    Err(CommandError::new(
+        ErrorCode::ClipboardBusy,
+        format!(
+            "Clipboard is busy (held by another application) after {} attempts",
+            attempts
+        ),
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Fails to open with the given error a fixed number of times, then succeeds
+    struct FlakyClipboard {
+        failures: u32,
+        error: fn() -> arboard::Error,
+        opened: Cell<u32>,
+    }
+
+    impl FlakyClipboard {
+        fn new(failures: u32, error: fn() -> arboard::Error) -> Self {
+            Self {
+                failures,
+                error,
+                opened: Cell::new(0),
+            }
+        }
+    }
+
+    impl ClipboardFactory for FlakyClipboard {
+        type Clipboard = Vec<String>;
+
+        fn open(&self) -> std::result::Result<Vec<String>, arboard::Error> {
+            self.opened.set(self.opened.get() + 1);
+            if self.opened.get() <= self.failures {
+                Err((self.error)())
+            } else {
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            attempts: 4,
+            initial_delay: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_retries_until_clipboard_is_free() {
+        let factory = FlakyClipboard::new(3, || arboard::Error::ClipboardOccupied);
+        let written = with_clipboard(&factory, &fast_policy(), |clipboard| {
+            clipboard.push("hello".to_string());
+            Ok(clipboard.clone())
+        })
+        .unwrap();
+        assert_eq!(written, vec!["hello"]);
+        assert_eq!(factory.opened.get(), 4);
+    }
+
+    #[test]
+    fn test_gives_up_with_clipboard_busy_error() {
+        let factory = FlakyClipboard::new(u32::MAX, || arboard::Error::ClipboardOccupied);
+        let error = with_clipboard(&factory, &fast_policy(), |_| Ok(())).unwrap_err();
+        assert_eq!(factory.opened.get(), 4);
+
+        let error = CommandError::from(error);
+        assert_eq!(error.code, ErrorCode::ClipboardBusy);
+        assert!(error.message.contains("after 4 attempts"), "{}", error);
+    }
+
+    #[test]
+    fn test_other_errors_are_not_retried() {
+        let factory = FlakyClipboard::new(u32::MAX, || arboard::Error::ClipboardNotSupported);
+        assert!(with_clipboard(&factory, &fast_policy(), |_| Ok(())).is_err());
+        assert_eq!(factory.opened.get(), 1);
+
+        // Errors raised while writing are retried the same way as open failures
+        let factory = FlakyClipboard::new(0, || arboard::Error::ClipboardOccupied);
+        let calls = Cell::new(0);
+        with_clipboard(&factory, &fast_policy(), |_| {
+            calls.set(calls.get() + 1);
+            if calls.get() < 2 {
+                Err(arboard::Error::ClipboardOccupied)
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap();
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn test_file_lists_are_not_read_where_they_cannot_be_pasted() {
+        // set_file_list only works on macOS, so recorded file entries could never be pasted back
+        assert!(ArboardBackend::default()
+            .get_file_list()
+            .unwrap()
+            .is_empty());
+        assert!(ArboardBackend::default()
+            .set_file_list(&[PathBuf::from("/tmp/a.txt")])
+            .is_err());
+    }
+}
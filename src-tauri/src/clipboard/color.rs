@@ -0,0 +1,304 @@
+use crate::error::CommandError;
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorFormat {
+    Hex,
+    Rgb,
+    Rgba,
+    Hsl,
+}
+
+impl ColorFormat {
+    /// 从前端传入的 snake_case 名称解析颜色格式
+    pub fn parse(value: &str) -> Option<Self> {
+        serde_json::from_value(Value::String(value.to_string())).ok()
+    }
+}
+
+/// sRGB 颜色，alpha 取值 0.0-1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: f32,
+}
+
+impl Color {
+    /// 解析 #rgb、#rgba、#rrggbb、#rrggbbaa、rgb()、rgba() 和 hsl()
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Some(hex) = text.strip_prefix('#') {
+            return Self::parse_hex(hex);
+        }
+
+        let rgb_regex = Regex::new(
+            r"^rgba?\(\s*(\d{1,3})\s*,\s*(\d{1,3})\s*,\s*(\d{1,3})\s*(?:,\s*([\d.]+))?\s*\)$",
+        )
+        .unwrap();
+        if let Some(captures) = rgb_regex.captures(text) {
+            let a = match captures.get(4) {
+                Some(alpha) => alpha.as_str().parse::<f32>().ok()?,
+                None => 1.0,
+            };
+            if !(0.0..=1.0).contains(&a) {
+                return None;
+            }
+            return Some(Self {
+                r: captures[1].parse().ok()?,
+                g: captures[2].parse().ok()?,
+                b: captures[3].parse().ok()?,
+                a,
+            });
+        }
+
+        let hsl_regex =
+            Regex::new(r"^hsl\(\s*(\d{1,3})\s*,\s*(\d{1,3})%\s*,\s*(\d{1,3})%\s*\)$").unwrap();
+        let captures = hsl_regex.captures(text)?;
+        let h: u32 = captures[1].parse().ok()?;
+        let s: u32 = captures[2].parse().ok()?;
+        let l: u32 = captures[3].parse().ok()?;
+        if h > 360 || s > 100 || l > 100 {
+            return None;
+        }
+        Some(Self::from_hsl(h as f64, s as f64 / 100.0, l as f64 / 100.0))
+    }
+
+    fn parse_hex(hex: &str) -> Option<Self> {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        // 简写形式每位重复一次：#f80 -> #ff8800
+        let expanded: String = match hex.len() {
+            3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 | 8 => hex.to_string(),
+            _ => return None,
+        };
+        let channel = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).ok();
+        let a = match expanded.len() {
+            8 => channel(6)? as f32 / 255.0,
+            _ => 1.0,
+        };
+        Some(Self {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+            a,
+        })
+    }
+
+    fn from_hsl(h: f64, s: f64, l: f64) -> Self {
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let h = (h % 360.0) / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = l - c / 2.0;
+        let channel = |value: f64| ((value + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+        Self {
+            r: channel(r),
+            g: channel(g),
+            b: channel(b),
+            a: 1.0,
+        }
+    }
+
+    /// 色相（度）、饱和度和亮度（百分比），均四舍五入为整数
+    fn to_hsl(self) -> (u32, u32, u32) {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| c as f64 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        if d == 0.0 {
+            return (0, 0, (l * 100.0).round() as u32);
+        }
+
+        let s = d / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == r {
+            60.0 * ((g - b) / d).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / d + 2.0)
+        } else {
+            60.0 * ((r - g) / d + 4.0)
+        };
+        (
+            h.round() as u32 % 360,
+            (s * 100.0).round() as u32,
+            (l * 100.0).round() as u32,
+        )
+    }
+
+    /// 按目标格式输出；rgb 和 hsl 不含透明度，hex 只在半透明时带 alpha
+    pub fn format(&self, target: ColorFormat) -> String {
+        match target {
+            ColorFormat::Hex => {
+                let hex = format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b);
+                if self.a < 1.0 {
+                    format!("{}{:02x}", hex, (self.a * 255.0).round() as u8)
+                } else {
+                    hex
+                }
+            }
+            ColorFormat::Rgb => format!("rgb({}, {}, {})", self.r, self.g, self.b),
+            ColorFormat::Rgba => format!(
+                "rgba({}, {}, {}, {})",
+                self.r,
+                self.g,
+                self.b,
+                format_alpha(self.a)
+            ),
+            ColorFormat::Hsl => {
+                let (h, s, l) = self.to_hsl();
+                format!("hsl({}, {}%, {}%)", h, s, l)
+            }
+        }
+    }
+}
+
+/// alpha 保留两位小数并去掉末尾的 0：0.50 -> 0.5，1.00 -> 1
+fn format_alpha(alpha: f32) -> String {
+    let formatted = format!("{:.2}", alpha);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// 把颜色字符串转换为目标格式
+pub fn convert(value: &str, target: ColorFormat) -> Result<String> {
+    let color = Color::parse(value)
+        .ok_or_else(|| CommandError::invalid_input(format!("Invalid color value: {}", value)))?;
+    Ok(color.format(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORMATS: [ColorFormat; 4] = [
+        ColorFormat::Hex,
+        ColorFormat::Rgb,
+        ColorFormat::Rgba,
+        ColorFormat::Hsl,
+    ];
+
+    #[test]
+    fn test_convert_between_all_formats() {
+        // Each row lists the same color as hex, rgb, rgba and hsl
+        let colors = [
+            [
+                "#ff0000",
+                "rgb(255, 0, 0)",
+                "rgba(255, 0, 0, 1)",
+                "hsl(0, 100%, 50%)",
+            ],
+            [
+                "#00ff00",
+                "rgb(0, 255, 0)",
+                "rgba(0, 255, 0, 1)",
+                "hsl(120, 100%, 50%)",
+            ],
+            [
+                "#0000ff",
+                "rgb(0, 0, 255)",
+                "rgba(0, 0, 255, 1)",
+                "hsl(240, 100%, 50%)",
+            ],
+            [
+                "#ffffff",
+                "rgb(255, 255, 255)",
+                "rgba(255, 255, 255, 1)",
+                "hsl(0, 0%, 100%)",
+            ],
+            [
+                "#000000",
+                "rgb(0, 0, 0)",
+                "rgba(0, 0, 0, 1)",
+                "hsl(0, 0%, 0%)",
+            ],
+            [
+                "#336699",
+                "rgb(51, 102, 153)",
+                "rgba(51, 102, 153, 1)",
+                "hsl(210, 50%, 40%)",
+            ],
+            [
+                "#ff00ff",
+                "rgb(255, 0, 255)",
+                "rgba(255, 0, 255, 1)",
+                "hsl(300, 100%, 50%)",
+            ],
+        ];
+
+        for representations in colors {
+            for (source, value) in FORMATS.iter().zip(representations) {
+                for (target, expected) in FORMATS.iter().zip(representations) {
+                    assert_eq!(
+                        convert(value, *target).unwrap(),
+                        expected,
+                        "{:?} -> {:?}",
+                        source,
+                        target
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_shorthand_and_alpha() {
+        assert_eq!(
+            convert("#F80", ColorFormat::Rgb).unwrap(),
+            "rgb(255, 136, 0)"
+        );
+        assert_eq!(
+            convert("rgba(255, 0, 0, 0.5)", ColorFormat::Hex).unwrap(),
+            "#ff000080"
+        );
+        assert_eq!(
+            convert("#ff000080", ColorFormat::Rgba).unwrap(),
+            "rgba(255, 0, 0, 0.5)"
+        );
+        assert_eq!(
+            convert("#f008", ColorFormat::Rgba).unwrap(),
+            "rgba(255, 0, 0, 0.53)"
+        );
+        // rgb and hsl drop the alpha channel
+        assert_eq!(
+            convert("rgba(0, 0, 255, 0.25)", ColorFormat::Rgb).unwrap(),
+            "rgb(0, 0, 255)"
+        );
+        assert_eq!(
+            convert("rgba(0, 0, 255, 0.25)", ColorFormat::Hsl).unwrap(),
+            "hsl(240, 100%, 50%)"
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_colors() {
+        for value in [
+            "#ff000",
+            "#gggggg",
+            "rgb(256, 0, 0)",
+            "rgba(0, 0, 0, 1.5)",
+            "hsl(400, 50%, 50%)",
+            "red",
+        ] {
+            assert!(convert(value, ColorFormat::Hex).is_err(), "{}", value);
+        }
+        assert_eq!(ColorFormat::parse("rgba"), Some(ColorFormat::Rgba));
+        assert_eq!(ColorFormat::parse("cmyk"), None);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::error::CommandError;
+use anyhow::Result;
+use base64::{engine::general_purpose, Engine as _};
+
+/// 解码 `data:image/...;base64,...` 形式的 data URL，返回其中的图片字节；
+/// 只接受 image/* 类型，返回的数据未经解码校验
+pub fn decode_image_data_url(data_url: &str) -> Result<Vec<u8>> {
+    let rest = data_url
+        .trim()
+        .strip_prefix("data:")
+        .ok_or_else(|| CommandError::invalid_input("Invalid data URL: missing data: prefix"))?;
+    let (header, payload) = rest
+        .split_once(',')
+        .ok_or_else(|| CommandError::invalid_input("Invalid data URL: missing data"))?;
+
+    let mut params = header.split(';');
+    let mime_type = params.next().unwrap_or_default().trim().to_lowercase();
+    if !mime_type.starts_with("image/") {
+        return Err(CommandError::invalid_input(format!(
+            "Invalid data URL: expected an image MIME type, got {:?}",
+            mime_type
+        ))
+        .into());
+    }
+    if !params.any(|param| param.trim().eq_ignore_ascii_case("base64")) {
+        return Err(
+            CommandError::invalid_input("Invalid data URL: image data must be base64").into(),
+        );
+    }
+
+    let bytes = general_purpose::STANDARD
+        .decode(payload.trim())
+        .map_err(|e| CommandError::invalid_input(format!("Invalid base64 image data: {}", e)))?;
+    if bytes.is_empty() {
+        return Err(CommandError::invalid_input("Invalid data URL: image data is empty").into());
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// 2x1 的 PNG 图片
+    pub(crate) fn png_data_url() -> String {
+        let image = image::RgbaImage::from_raw(2, 1, vec![255, 0, 0, 255, 0, 0, 255, 128]).unwrap();
+        let mut png = Vec::new();
+        image
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        format!(
+            "data:image/png;base64,{}",
+            general_purpose::STANDARD.encode(&png)
+        )
+    }
+
+    #[test]
+    fn test_decode_png_data_url() {
+        let bytes = decode_image_data_url(&png_data_url()).unwrap();
+        let image = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((image.width(), image.height()), (2, 1));
+    }
+
+    #[test]
+    fn test_rejects_non_image_data_urls() {
+        for data_url in [
+            "data:text/plain;base64,aGVsbG8=",
+            "data:application/octet-stream;base64,aGVsbG8=",
+            "data:image/png,raw-bytes",
+            "data:image/png;base64,not base64!",
+            "data:image/png;base64,",
+            "image/png;base64,aGVsbG8=",
+            "data:image/png;base64",
+        ] {
+            assert!(decode_image_data_url(data_url).is_err(), "{}", data_url);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::time::{Duration, Instant};
+
+/// 合并短时间内连续发生的剪贴板变化，只保留最后一次
+pub struct ChangeDebouncer<T> {
+    debounce: Duration,
+    pending: Option<(T, Instant)>,
+}
+
+impl<T> ChangeDebouncer<T> {
+    pub fn new(debounce: Duration) -> Self {
+        Self {
+            debounce,
+            pending: None,
+        }
+    }
+
+    /// 记录一次新的变化，返回被覆盖的尚未发出的旧变化
+    pub fn push(&mut self, item: T, now: Instant) -> Option<T> {
+        self.pending.replace((item, now)).map(|(item, _)| item)
+    }
+
+    /// 最后一次变化稳定超过防抖时间后将其取出
+    pub fn poll(&mut self, now: Instant) -> Option<T> {
+        match &self.pending {
+            Some((_, changed_at)) if now.duration_since(*changed_at) >= self.debounce => {
+                self.pending.take().map(|(item, _)| item)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_debounce_emits_immediately() {
+        let mut debouncer = ChangeDebouncer::new(Duration::ZERO);
+        let now = Instant::now();
+
+        assert_eq!(debouncer.push("a", now), None);
+        assert_eq!(debouncer.poll(now), Some("a"));
+        assert_eq!(debouncer.poll(now), None);
+    }
+
+    #[test]
+    fn test_rapid_changes_collapse_to_last() {
+        let mut debouncer = ChangeDebouncer::new(Duration::from_millis(300));
+        let start = Instant::now();
+
+        // A multi-step copy: three changes 100ms apart
+        assert_eq!(debouncer.push("step 1", start), None);
+        assert_eq!(debouncer.poll(start + Duration::from_millis(50)), None);
+        assert_eq!(
+            debouncer.push("step 2", start + Duration::from_millis(100)),
+            Some("step 1")
+        );
+        assert_eq!(debouncer.poll(start + Duration::from_millis(150)), None);
+        assert_eq!(
+            debouncer.push("step 3", start + Duration::from_millis(200)),
+            Some("step 2")
+        );
+
+        // Still within the window of the last change
+        assert_eq!(debouncer.poll(start + Duration::from_millis(450)), None);
+        // Stable for 300ms: only the final value comes out
+        assert_eq!(
+            debouncer.poll(start + Duration::from_millis(500)),
+            Some("step 3")
+        );
+        assert_eq!(debouncer.poll(start + Duration::from_millis(1000)), None);
+    }
+
+    #[test]
+    fn test_spaced_changes_are_all_emitted() {
+        let mut debouncer = ChangeDebouncer::new(Duration::from_millis(100));
+        let start = Instant::now();
+        let mut emitted = Vec::new();
+
+        for i in 0..3u64 {
+            let at = start + Duration::from_millis(i * 500);
+            assert_eq!(debouncer.push(i, at), None);
+            if let Some(item) = debouncer.poll(at + Duration::from_millis(100)) {
+                emitted.push(item);
+            }
+        }
+
+        assert_eq!(emitted, vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,248 @@
+use std::borrow::Cow;
+
+/// 在不重新编码的情况下移除图片中的 EXIF/XMP 元数据，像素数据和格式保持不变。
+/// 支持 JPEG、PNG 和 WebP，其他格式或文件结构损坏时返回 None
+pub fn strip_metadata(data: &[u8]) -> Option<Cow<'_, [u8]>> {
+    if data.starts_with(&[0xFF, 0xD8]) {
+        strip_jpeg(data)
+    } else if data.starts_with(PNG_SIGNATURE) {
+        strip_png(data)
+    } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+        strip_webp(data)
+    } else {
+        None
+    }
+}
+
+const PNG_SIGNATURE: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// JPEG：移除 APP1 段（EXIF 和 XMP 都存放在 APP1 中）
+fn strip_jpeg(data: &[u8]) -> Option<Cow<'_, [u8]>> {
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(&data[..2]);
+    let mut pos = 2;
+    let mut stripped = false;
+
+    loop {
+        if *data.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *data.get(pos + 1)?;
+
+        // 扫描数据开始后不再有元数据段，剩余部分原样保留
+        if marker == 0xDA || marker == 0xD9 {
+            output.extend_from_slice(&data[pos..]);
+            break;
+        }
+        // 不带长度的独立标记
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) || marker == 0xFF {
+            let step = if marker == 0xFF { 1 } else { 2 };
+            output.extend_from_slice(&data[pos..pos + step]);
+            pos += step;
+            continue;
+        }
+
+        let length = u16::from_be_bytes([*data.get(pos + 2)?, *data.get(pos + 3)?]) as usize;
+        let end = pos + 2 + length;
+        if length < 2 || end > data.len() {
+            return None;
+        }
+        if marker == 0xE1 {
+            stripped = true;
+        } else {
+            output.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+
+    Some(if stripped {
+        Cow::Owned(output)
+    } else {
+        Cow::Borrowed(data)
+    })
+}
+
+/// PNG：移除 eXIf 和文本类块（XMP 存放在 iTXt 中）
+fn strip_png(data: &[u8]) -> Option<Cow<'_, [u8]>> {
+    const METADATA_CHUNKS: [&[u8; 4]; 5] = [b"eXIf", b"tEXt", b"zTXt", b"iTXt", b"tIME"];
+
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(PNG_SIGNATURE);
+    let mut pos = PNG_SIGNATURE.len();
+    let mut stripped = false;
+
+    while pos < data.len() {
+        let header = data.get(pos..pos + 8)?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let end = pos.checked_add(12 + length)?;
+        if end > data.len() {
+            return None;
+        }
+        if METADATA_CHUNKS
+            .iter()
+            .any(|chunk| chunk[..] == header[4..8])
+        {
+            stripped = true;
+        } else {
+            output.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+
+    Some(if stripped {
+        Cow::Owned(output)
+    } else {
+        Cow::Borrowed(data)
+    })
+}
+
+/// WebP：移除 EXIF 和 XMP 块，并清除 VP8X 中对应的标志位
+fn strip_webp(data: &[u8]) -> Option<Cow<'_, [u8]>> {
+    const XMP_FLAG: u8 = 0x04;
+    const EXIF_FLAG: u8 = 0x08;
+
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(&data[..12]);
+    let mut pos = 12;
+    let mut stripped = false;
+
+    while pos < data.len() {
+        let header = data.get(pos..pos + 8)?;
+        let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        if pos + 8 + length > data.len() {
+            return None;
+        }
+        // 块数据按偶数字节对齐，最后一个块可能省略填充字节
+        let end = (pos + 8 + length + (length & 1)).min(data.len());
+        match &header[..4] {
+            b"EXIF" | b"XMP " => stripped = true,
+            b"VP8X" if length >= 1 => {
+                let flags_pos = output.len() + 8;
+                output.extend_from_slice(&data[pos..end]);
+                output[flags_pos] &= !(XMP_FLAG | EXIF_FLAG);
+            }
+            _ => output.extend_from_slice(&data[pos..end]),
+        }
+        pos = end;
+    }
+
+    if !stripped {
+        return Some(Cow::Borrowed(data));
+    }
+    let riff_size = u32::try_from(output.len() - 8).ok()?;
+    output[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Some(Cow::Owned(output))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// 最小的 EXIF 负载：TIFF 头加一个空 IFD
+    pub(crate) const EXIF_PAYLOAD: &[u8] = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\0\0\0\0\0";
+
+    pub(crate) fn contains(data: &[u8], needle: &[u8]) -> bool {
+        data.windows(needle.len()).any(|window| window == needle)
+    }
+
+    fn encode(format: image::ImageFormat) -> Vec<u8> {
+        let img =
+            image::RgbImage::from_fn(16, 16, |x, y| image::Rgb([x as u8 * 16, y as u8 * 16, 0]));
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut buffer, format).unwrap();
+        buffer.into_inner()
+    }
+
+    /// 在 SOI 之后插入一个 APP1 EXIF 段
+    pub(crate) fn jpeg_with_exif() -> Vec<u8> {
+        let jpeg = encode(image::ImageFormat::Jpeg);
+        let mut output = jpeg[..2].to_vec();
+        output.extend_from_slice(&[0xFF, 0xE1]);
+        output.extend_from_slice(&((EXIF_PAYLOAD.len() + 2) as u16).to_be_bytes());
+        output.extend_from_slice(EXIF_PAYLOAD);
+        output.extend_from_slice(&jpeg[2..]);
+        output
+    }
+
+    fn png_with_exif() -> Vec<u8> {
+        let png = encode(image::ImageFormat::Png);
+        let payload = &EXIF_PAYLOAD[6..]; // eXIf chunks hold the TIFF data without the prefix
+        let mut chunk = (payload.len() as u32).to_be_bytes().to_vec();
+        chunk.extend_from_slice(b"eXIf");
+        chunk.extend_from_slice(payload);
+        chunk.extend_from_slice(&[0, 0, 0, 0]); // CRC is not checked by the stripper
+
+        // Insert right after IHDR (8-byte signature + 25-byte chunk)
+        let mut output = png[..33].to_vec();
+        output.extend_from_slice(&chunk);
+        output.extend_from_slice(&png[33..]);
+        output
+    }
+
+    fn pixels(data: &[u8]) -> Vec<u8> {
+        image::load_from_memory(data).unwrap().to_rgb8().into_raw()
+    }
+
+    #[test]
+    fn test_strip_jpeg_exif() {
+        let original = jpeg_with_exif();
+        assert!(contains(&original, b"Exif\0\0"));
+
+        let stripped = strip_metadata(&original).unwrap();
+        assert!(!contains(&stripped, b"Exif\0\0"));
+        assert_eq!(stripped.len(), original.len() - EXIF_PAYLOAD.len() - 4);
+        assert_eq!(
+            image::guess_format(&stripped).unwrap(),
+            image::ImageFormat::Jpeg
+        );
+        assert_eq!(pixels(&stripped), pixels(&original));
+    }
+
+    #[test]
+    fn test_strip_png_exif() {
+        let original = png_with_exif();
+        assert!(contains(&original, b"eXIf"));
+
+        let stripped = strip_metadata(&original).unwrap();
+        assert!(!contains(&stripped, b"eXIf"));
+        assert_eq!(
+            stripped.as_ref(),
+            encode(image::ImageFormat::Png).as_slice()
+        );
+    }
+
+    #[test]
+    fn test_strip_webp_exif() {
+        // VP8X header with the EXIF flag set, a tiny VP8L bitstream and an EXIF chunk
+        let mut chunks = Vec::new();
+        chunks.extend_from_slice(b"VP8X");
+        chunks.extend_from_slice(&10u32.to_le_bytes());
+        chunks.extend_from_slice(&[0x08, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        chunks.extend_from_slice(b"VP8L");
+        chunks.extend_from_slice(&5u32.to_le_bytes());
+        chunks.extend_from_slice(&[0x2F, 0, 0, 0, 0, 0]); // odd length is padded
+        chunks.extend_from_slice(b"EXIF");
+        chunks.extend_from_slice(&(EXIF_PAYLOAD.len() as u32).to_le_bytes());
+        chunks.extend_from_slice(EXIF_PAYLOAD);
+        let mut original = b"RIFF".to_vec();
+        original.extend_from_slice(&(chunks.len() as u32 + 4).to_le_bytes());
+        original.extend_from_slice(b"WEBP");
+        original.extend_from_slice(&chunks);
+
+        let stripped = strip_metadata(&original).unwrap();
+        assert!(!contains(&stripped, b"EXIF"));
+        assert!(contains(&stripped, b"VP8L"));
+        assert_eq!(stripped[20] & 0x08, 0);
+        let riff_size = u32::from_le_bytes([stripped[4], stripped[5], stripped[6], stripped[7]]);
+        assert_eq!(riff_size as usize, stripped.len() - 8);
+    }
+
+    #[test]
+    fn test_strip_metadata_leaves_clean_and_unknown_data() {
+        let png = encode(image::ImageFormat::Png);
+        assert!(matches!(strip_metadata(&png), Some(Cow::Borrowed(_))));
+        assert!(strip_metadata(b"GIF89a....").is_none());
+        // Truncated segment
+        assert!(strip_metadata(&[0xFF, 0xD8, 0xFF, 0xE1, 0x00, 0x40, 0x00]).is_none());
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(not(target_os = "macos"))]
+use crate::error::CommandError;
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// 以文件引用的形式写入剪贴板（macOS 为 NSFilenamesPboardType），
+/// 粘贴到只接受文件拖放的应用时会得到文件本身而不是像素数据
+#[cfg(target_os = "macos")]
+pub fn set_file_references(paths: &[PathBuf]) -> Result<()> {
+    use cocoa::appkit::{NSFilenamesPboardType, NSPasteboard};
+    use cocoa::base::{id, nil, NO};
+    use cocoa::foundation::{NSArray, NSAutoreleasePool, NSString};
+    use objc::{msg_send, sel, sel_impl};
+
+    let paths = paths
+        .iter()
+        .map(|path| {
+            path.to_str()
+                .ok_or_else(|| anyhow::anyhow!("Path is not valid UTF-8: {:?}", path))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    unsafe {
+        let pool = NSAutoreleasePool::new(nil);
+
+        let filenames: Vec<id> = paths
+            .iter()
+            .map(|path| NSString::alloc(nil).init_str(path))
+            .collect();
+        let pasteboard = NSPasteboard::generalPasteboard(nil);
+        // declareTypes 会清空剪贴板原有内容
+        pasteboard.declareTypes_owner(NSArray::arrayWithObject(nil, NSFilenamesPboardType), nil);
+        let success = pasteboard.setPropertyList_forType(
+            NSArray::arrayWithObjects(nil, &filenames),
+            NSFilenamesPboardType,
+        );
+
+        for filename in filenames {
+            let _: () = msg_send![filename, release];
+        }
+        pool.drain();
+
+        if success == NO {
+            return Err(anyhow::anyhow!(
+                "Failed to write file references to pasteboard"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn set_file_references(_paths: &[PathBuf]) -> Result<()> {
+    Err(CommandError::unsupported("Pasting files is only supported on macOS").into())
+}
+
+#[cfg(all(test, target_os = "macos"))]
+mod tests {
+    use super::*;
+    use cocoa::appkit::{NSFilenamesPboardType, NSPasteboard};
+    use cocoa::base::{id, nil, BOOL, YES};
+    use cocoa::foundation::NSArray;
+    use objc::{msg_send, sel, sel_impl};
+    use std::ffi::CStr;
+
+    #[test]
+    fn test_set_file_references_declares_filenames_type() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file = temp_dir.path().join("pasted.png");
+        std::fs::write(&file, b"png").unwrap();
+
+        set_file_references(&[file.clone()]).unwrap();
+
+        unsafe {
+            let pasteboard = NSPasteboard::generalPasteboard(nil);
+            let types: id = msg_send![pasteboard, types];
+            let declared: BOOL = msg_send![types, containsObject: NSFilenamesPboardType];
+            assert_eq!(declared, YES);
+
+            let filenames: id = msg_send![pasteboard, propertyListForType: NSFilenamesPboardType];
+            assert_eq!(NSArray::count(filenames), 1);
+            let first: id = NSArray::objectAtIndex(filenames, 0);
+            let utf8: *const std::os::raw::c_char = msg_send![first, UTF8String];
+            assert_eq!(
+                CStr::from_ptr(utf8).to_str().unwrap(),
+                file.to_str().unwrap()
+            );
+        }
+    }
+}
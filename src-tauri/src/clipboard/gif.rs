@@ -0,0 +1,137 @@
+#[cfg(not(target_os = "macos"))]
+use crate::error::CommandError;
+use anyhow::Result;
+
+/// 根据文件头判断是否为 GIF
+pub fn is_gif(data: &[u8]) -> bool {
+    data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a")
+}
+
+/// 只遍历块结构统计 GIF 的帧数（不解码像素），数据不是完整的 GIF 时返回 None
+pub fn frame_count(data: &[u8]) -> Option<usize> {
+    if !is_gif(data) {
+        return None;
+    }
+
+    // 逻辑屏幕描述符之后可能跟着全局颜色表
+    let mut pos = 13;
+    let packed = *data.get(10)?;
+    if packed & 0x80 != 0 {
+        pos += 3 << ((packed & 0x07) + 1);
+    }
+
+    let mut frames = 0;
+    loop {
+        match *data.get(pos)? {
+            // 扩展块：标签后跟数据子块
+            0x21 => pos = skip_sub_blocks(data, pos + 2)?,
+            // 图像描述符：可能带局部颜色表，然后是 LZW 最小码长和图像数据子块
+            0x2C => {
+                let packed = *data.get(pos + 9)?;
+                pos += 10;
+                if packed & 0x80 != 0 {
+                    pos += 3 << ((packed & 0x07) + 1);
+                }
+                pos = skip_sub_blocks(data, pos + 1)?;
+                frames += 1;
+            }
+            0x3B => return Some(frames),
+            _ => return None,
+        }
+    }
+}
+
+/// 跳过以长度为 0 的块结尾的数据子块序列，返回其后的位置
+fn skip_sub_blocks(data: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let size = *data.get(pos)? as usize;
+        pos += 1 + size;
+        if size == 0 {
+            return Some(pos);
+        }
+    }
+}
+
+/// 多帧 GIF 需要保留原始数据，解码成单帧 RGBA 会丢失动画
+pub fn is_animated(data: &[u8]) -> bool {
+    frame_count(data).is_some_and(|frames| frames > 1)
+}
+
+/// 将原始 GIF 数据写入剪贴板（com.compuserve.gif），保留动画
+#[cfg(target_os = "macos")]
+pub fn set_clipboard_gif(data: &[u8]) -> Result<()> {
+    use cocoa::appkit::NSPasteboard;
+    use cocoa::base::{nil, NO};
+    use cocoa::foundation::{NSArray, NSAutoreleasePool, NSData, NSString};
+    use objc::{msg_send, sel, sel_impl};
+
+    unsafe {
+        let pool = NSAutoreleasePool::new(nil);
+
+        let gif_type = NSString::alloc(nil).init_str("com.compuserve.gif");
+        let pasteboard = NSPasteboard::generalPasteboard(nil);
+        pasteboard.declareTypes_owner(NSArray::arrayWithObject(nil, gif_type), nil);
+        let bytes = NSData::dataWithBytes_length_(
+            nil,
+            data.as_ptr() as *const std::os::raw::c_void,
+            data.len() as u64,
+        );
+        let success = pasteboard.setData_forType(bytes, gif_type);
+
+        let _: () = msg_send![gif_type, release];
+        pool.drain();
+
+        if success == NO {
+            return Err(anyhow::anyhow!("Failed to write GIF data to pasteboard"));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn set_clipboard_gif(_data: &[u8]) -> Result<()> {
+    Err(CommandError::unsupported("Copying animated GIFs is only supported on macOS").into())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use image::codecs::gif::GifEncoder;
+    use image::{Delay, Frame, Rgba, RgbaImage};
+
+    /// 生成指定帧数的 GIF，每帧颜色不同
+    pub(crate) fn encode_gif(frames: u8) -> Vec<u8> {
+        let mut data = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut data);
+            encoder
+                .encode_frames((0..frames).map(|i| {
+                    let image = RgbaImage::from_pixel(8, 6, Rgba([i * 60, 0, 255 - i * 60, 255]));
+                    Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(100, 1))
+                }))
+                .unwrap();
+        }
+        data
+    }
+
+    #[test]
+    fn test_detect_animated_gif() {
+        let animated = encode_gif(3);
+        assert!(is_gif(&animated));
+        assert!(is_animated(&animated));
+        assert_eq!(frame_count(&animated), Some(3));
+
+        let still = encode_gif(1);
+        assert!(is_gif(&still));
+        assert!(!is_animated(&still));
+        assert_eq!(frame_count(&still), Some(1));
+
+        // Truncated data is not treated as an animation
+        assert_eq!(frame_count(&animated[..animated.len() - 10]), None);
+
+        assert!(!is_gif(b"\x89PNG\r\n\x1a\n"));
+        assert!(!is_animated(b"GIF89a truncated"));
+        assert_eq!(frame_count(b"not a gif"), None);
+    }
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+/// 是否编译了自然语言识别（`language-detection` feature，依赖 whatlang）
+pub const AVAILABLE: bool = cfg!(feature = "language-detection");
+
+/// 短文本的识别结果不可靠，少于这个字符数的文本不做识别
+#[cfg(feature = "language-detection")]
+const MIN_TEXT_CHARS: usize = 20;
+
+/// 纯文本的自然语言，保存在 metadata 的 natural_language 字段中
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NaturalLanguage {
+    pub code: String,    // ISO 639-3, e.g. eng, cmn
+    pub confidence: f64, // 0.0-1.0
+}
+
+impl NaturalLanguage {
+    /// 合并进已有的 metadata JSON（保留内容检测、富文本等其他字段）
+    pub fn merge_into_metadata(&self, metadata: Option<&str>) -> String {
+        let mut value = metadata
+            .and_then(|metadata| serde_json::from_str::<serde_json::Value>(metadata).ok())
+            .filter(|value| value.is_object())
+            .unwrap_or_else(|| serde_json::json!({}));
+        value["natural_language"] = serde_json::to_value(self).unwrap_or_default();
+        value.to_string()
+    }
+}
+
+/// 识别文本的自然语言，文本太短或结果不可靠时返回 None
+#[cfg(feature = "language-detection")]
+pub fn detect(text: &str) -> Option<NaturalLanguage> {
+    if text.chars().count() < MIN_TEXT_CHARS {
+        return None;
+    }
+    let info = whatlang::detect(text).filter(|info| info.is_reliable())?;
+    Some(NaturalLanguage {
+        code: info.lang().code().to_string(),
+        confidence: info.confidence(),
+    })
+}
+
+#[cfg(not(feature = "language-detection"))]
+pub fn detect(_text: &str) -> Option<NaturalLanguage> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_into_metadata_keeps_other_fields() {
+        let language = NaturalLanguage {
+            code: "eng".to_string(),
+            confidence: 0.9,
+        };
+        let metadata: serde_json::Value = serde_json::from_str(
+            &language.merge_into_metadata(Some(r#"{"rich_text":{"html":"<b>hi</b>"}}"#)),
+        )
+        .unwrap();
+        assert_eq!(metadata["rich_text"]["html"], "<b>hi</b>");
+        assert_eq!(metadata["natural_language"]["code"], "eng");
+
+        let metadata: serde_json::Value =
+            serde_json::from_str(&language.merge_into_metadata(None)).unwrap();
+        assert_eq!(metadata["natural_language"]["confidence"], 0.9);
+    }
+
+    #[test]
+    fn test_short_text_is_not_detected() {
+        assert_eq!(detect("Hello there"), None);
+        assert_eq!(detect("你好"), None);
+    }
+
+    #[cfg(feature = "language-detection")]
+    #[test]
+    fn test_detects_english_and_chinese() {
+        let english = detect(
+            "The quick brown fox jumps over the lazy dog while the children watch from the garden.",
+        )
+        .unwrap();
+        assert_eq!(english.code, "eng");
+        assert!(english.confidence > 0.5);
+
+        let chinese =
+            detect("今天天气很好，我们一起去公园散步，然后在湖边的咖啡馆喝茶聊天。").unwrap();
+        assert_eq!(chinese.code, "cmn");
+        assert!(chinese.confidence > 0.5);
+    }
+}
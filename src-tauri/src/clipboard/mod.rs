@@ -1,6 +1,19 @@
+pub mod backend;
+pub mod color;
 pub mod content_detector;
+pub mod data_url;
+pub mod debounce;
+pub mod exif;
+pub mod file_reference;
+pub mod gif;
+pub mod language;
 pub mod monitor;
+pub mod ocr;
 pub mod processor;
+pub mod recent_writes;
+pub mod rich_text;
+pub mod smart_paste;
+pub mod transform;
 
 pub use monitor::ClipboardMonitor;
 pub use processor::ContentProcessor;
@@ -2,21 +2,48 @@ use anyhow::Result;
 use serde_json;
 use sha2::{Digest, Sha256};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
 use tokio::time::sleep;
 
+use crate::clipboard::backend::ClipboardBackend;
 use crate::clipboard::content_detector::ContentDetector;
+use crate::clipboard::debounce::ChangeDebouncer;
 use crate::clipboard::processor::ContentProcessor;
-use crate::config::ConfigManager;
-use crate::models::{ClipboardEntry, ContentType};
-use crate::utils::app_detector::get_active_app_info;
+use crate::clipboard::recent_writes::RecentWrites;
+use crate::config::{ConfigManager, DedupMode, MonitorConfig};
+use crate::models::{ClipboardEntry, ContentType, FileListMetadata, ImageMetadata, RichText};
+use crate::utils::active_window::{get_frontmost_app, AppInfo};
+
+/// 最近一次检测到的剪贴板内容
+#[derive(Default)]
+struct LastSeen {
+    hash: Option<String>,
+    change_count: Option<u64>,
+}
+
+impl LastSeen {
+    /// 记录本次检测到的内容，返回是否为新内容。Hash 相同但剪贴板变化计数改变
+    /// （再次复制了相同内容）时，只有 record_repeats 为 true 才视为新内容
+    fn observe(&mut self, hash: &str, change_count: Option<u64>, record_repeats: bool) -> bool {
+        let is_new = self.hash.as_deref() != Some(hash)
+            || (record_repeats && change_count != self.change_count);
+        self.hash = Some(hash.to_string());
+        self.change_count = change_count;
+        is_new
+    }
+}
 
 pub struct ClipboardMonitor {
-    last_hash: Arc<Mutex<Option<String>>>,
+    last_seen: Arc<Mutex<LastSeen>>,
     tx: broadcast::Sender<ClipboardEntry>,
     processor: Arc<ContentProcessor>,
     config_manager: Arc<Mutex<ConfigManager>>,
+    recent_writes: Arc<Mutex<RecentWrites>>,
+    clipboard: Arc<dyn ClipboardBackend>,
+    monitor_config: MonitorConfig,
+    tasks: std::sync::Mutex<Vec<JoinHandle<()>>>, // Aborted when the monitor is dropped
 }
 
 impl ClipboardMonitor {
@@ -24,42 +51,82 @@ impl ClipboardMonitor {
         tx: broadcast::Sender<ClipboardEntry>,
         processor: Arc<ContentProcessor>,
         config_manager: Arc<Mutex<ConfigManager>>,
+        recent_writes: Arc<Mutex<RecentWrites>>,
+        clipboard: Arc<dyn ClipboardBackend>,
+        monitor_config: MonitorConfig,
     ) -> Result<Self> {
-        let last_hash = Arc::new(Mutex::new(None));
-
         Ok(Self {
-            last_hash,
+            last_seen: Arc::new(Mutex::new(LastSeen::default())),
             tx,
             processor,
             config_manager,
+            recent_writes,
+            clipboard,
+            monitor_config,
+            tasks: std::sync::Mutex::new(Vec::new()),
         })
     }
 
-    fn get_saved_file_size(file_path: &str) -> Option<u64> {
-        // 将相对路径转换为绝对路径
-        let absolute_path = if file_path.starts_with("imgs/") {
-            let config_dir = dirs::config_dir()?;
-            let app_dir = config_dir.join("clipboard-app");
-            app_dir.join(file_path)
-        } else {
-            std::path::PathBuf::from(file_path)
-        };
-
-        std::fs::metadata(absolute_path).ok().map(|meta| meta.len())
+    /// 关联一个随监控器一起停止的后台任务
+    pub fn attach_task(&self, task: JoinHandle<()>) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.push(task);
+        }
+    }
+
+    /// 最近一次检测到的剪贴板内容Hash
+    pub async fn last_hash(&self) -> Option<String> {
+        self.last_seen.lock().await.hash.clone()
+    }
+
+    /// 再次复制相同内容时是否重新记录：需要 dedup_mode 不合并全部重复（consecutive_only / none），
+    /// 并且平台提供剪贴板变化计数，否则无法区分“再次复制”和“剪贴板没有变化”
+    pub fn records_repeated_copies(dedup_mode: DedupMode, change_count: Option<u64>) -> bool {
+        dedup_mode != DedupMode::ByHash && change_count.is_some()
+    }
+
+    /// 立即检查一次剪贴板（不经过防抖），供测试模拟一轮轮询
+    #[cfg(test)]
+    pub(crate) async fn check_once(&self) -> Result<Option<ClipboardEntry>> {
+        Self::check_clipboard(
+            None,
+            &self.last_seen,
+            &self.processor,
+            &self.config_manager,
+            &self.recent_writes,
+            &self.clipboard,
+        )
+        .await
     }
 
     pub async fn start_monitoring(&self) {
-        log::info!("[ClipboardMonitor] 启动剪贴板监控");
+        log::info!(
+            "[ClipboardMonitor] 启动剪贴板监控 (轮询间隔: {}ms, 防抖: {}ms)",
+            self.monitor_config.poll_interval_ms,
+            self.monitor_config.debounce_ms
+        );
 
-        let last_hash = Arc::clone(&self.last_hash);
+        let last_seen = Arc::clone(&self.last_seen);
         let tx = self.tx.clone();
         let processor = Arc::clone(&self.processor);
         let config_manager = Arc::clone(&self.config_manager);
-
-        tokio::spawn(async move {
+        let recent_writes = Arc::clone(&self.recent_writes);
+        let clipboard = Arc::clone(&self.clipboard);
+        let poll_interval = Duration::from_millis(
+            self.monitor_config
+                .poll_interval_ms
+                .max(MonitorConfig::MIN_POLL_INTERVAL_MS),
+        );
+        let mut debouncer =
+            ChangeDebouncer::new(Duration::from_millis(self.monitor_config.debounce_ms));
+
+        let task = tokio::spawn(async move {
             loop {
-                // 获取当前应用信息，用于记录剪贴板内容来源
-                let app_info = get_active_app_info();
+                // 获取当前应用信息，用于记录剪贴板内容来源。每轮只获取一次，
+                // Linux 上需要启动 xprop 进程，放到阻塞线程中执行
+                let app_info = tokio::task::spawn_blocking(get_frontmost_app)
+                    .await
+                    .unwrap_or(None);
                 if let Some(ref info) = app_info {
                     log::trace!(
                         "[ClipboardMonitor] 当前活跃应用: {} ({})",
@@ -70,34 +137,182 @@ impl ClipboardMonitor {
                     log::trace!("[ClipboardMonitor] 无法获取当前活跃应用信息");
                 }
 
-                if let Err(e) =
-                    Self::check_clipboard(&last_hash, &tx, &processor, &config_manager).await
+                match Self::check_clipboard(
+                    app_info,
+                    &last_seen,
+                    &processor,
+                    &config_manager,
+                    &recent_writes,
+                    &clipboard,
+                )
+                .await
                 {
-                    log::error!("剪切板检查错误: {}", e);
+                    Ok(Some(entry)) => {
+                        // 防抖窗口内被新变化覆盖的条目不再记录
+                        if let Some(dropped) = debouncer.push(entry, Instant::now()) {
+                            log::debug!("[ClipboardMonitor] 快速连续变化，丢弃中间内容");
+                            Self::discard_entry_files(&processor, &dropped);
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::error!("剪切板检查错误: {}", e),
+                }
+
+                if let Some(entry) = debouncer.poll(Instant::now()) {
+                    let _ = tx.send(entry);
                 }
-                sleep(Duration::from_millis(500)).await;
+
+                sleep(poll_interval).await;
             }
         });
+        self.attach_task(task);
     }
 
+    /// 根据剪贴板文本构建条目：检测子类型和元数据，记录来源，附带富文本时合并进 metadata
+    pub(crate) fn build_text_entry(
+        trimmed_text: &str,
+        hash: String,
+        app_info: Option<&AppInfo>,
+        rich_text: &RichText,
+    ) -> ClipboardEntry {
+        // 检测内容子类型
+        let (subtype, metadata) = ContentDetector::detect(trimmed_text);
+        log::debug!("[ClipboardMonitor] 内容检测结果: {:?}", subtype);
+
+        // 将metadata转换为JSON字符串
+        let mut metadata_json = metadata.and_then(|m| serde_json::to_string(&m).ok());
+        if !rich_text.is_empty() {
+            metadata_json = Some(rich_text.merge_into_metadata(metadata_json.as_deref()));
+        }
+
+        let mut entry = ClipboardEntry::new(
+            ContentType::Text,
+            Some(trimmed_text.to_string()),
+            hash,
+            app_info.map(|info| info.name.clone()),
+            None,
+        );
+
+        // 设置子类型、元数据和bundle ID
+        // 使用serde_json::to_value获取正确的snake_case字符串
+        let subtype_str = serde_json::to_value(&subtype)
+            .ok()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_else(|| "plain_text".to_string());
+        entry.content_subtype = Some(subtype_str);
+        entry.metadata = metadata_json;
+        entry.app_bundle_id = app_info.and_then(|info| info.bundle_id.clone());
+        entry.source_window_title = app_info.and_then(|info| info.window_title.clone());
+        entry
+    }
+
+    /// 根据复制的文件列表构建文件条目：content_data 为换行分隔的路径（用于搜索和预览），
+    /// 完整列表保存在 metadata 的 file_list 中。不设置 file_path，删除条目时不会删除这些文件
+    pub(crate) fn build_file_list_entry(
+        paths: &[String],
+        app_info: Option<&AppInfo>,
+    ) -> ClipboardEntry {
+        let mut entry = ClipboardEntry::new(
+            ContentType::File,
+            Some(paths.join("\n")),
+            Self::file_list_hash(paths),
+            app_info.map(|info| info.name.clone()),
+            None,
+        );
+        entry.metadata = Some(
+            FileListMetadata {
+                paths: paths.to_vec(),
+            }
+            .to_entry_metadata(),
+        );
+        entry.app_bundle_id = app_info.and_then(|info| info.bundle_id.clone());
+        entry.source_window_title = app_info.and_then(|info| info.window_title.clone());
+        entry
+    }
+
+    /// 文件列表的 Hash，加前缀以免与内容相同的文本条目被当作重复
+    pub fn file_list_hash(paths: &[String]) -> String {
+        Self::calculate_hash(format!("file_list\n{}", paths.join("\n")).as_bytes())
+    }
+
+    /// 删除被丢弃条目已保存的图片文件
+    fn discard_entry_files(processor: &ContentProcessor, entry: &ClipboardEntry) {
+        if let Some(file_path) = &entry.file_path {
+            let full_path = processor.resolve_path(file_path);
+            if let Err(e) = std::fs::remove_file(&full_path) {
+                log::warn!(
+                    "[ClipboardMonitor] 删除丢弃的图片失败 {:?}: {}",
+                    full_path,
+                    e
+                );
+            }
+        }
+    }
+
+    /// 检查剪贴板是否有新内容，app_info 为本轮获取的前台应用（内容来源）
     async fn check_clipboard(
-        last_hash: &Arc<Mutex<Option<String>>>,
-        tx: &broadcast::Sender<ClipboardEntry>,
+        app_info: Option<AppInfo>,
+        last_seen: &Arc<Mutex<LastSeen>>,
         processor: &Arc<ContentProcessor>,
         config_manager: &Arc<Mutex<ConfigManager>>,
-    ) -> Result<()> {
-        // 获取当前活跃应用信息
-        let app_info = get_active_app_info();
-
-        // 检查文本内容 - 使用独立的剪切板实例，避免长时间锁定
-        let text_result = tokio::task::spawn_blocking(|| match arboard::Clipboard::new() {
-            Ok(mut temp_clipboard) => temp_clipboard.get_text(),
-            Err(e) => Err(e),
-        })
-        .await
-        .unwrap_or(Err(arboard::Error::ClipboardNotSupported));
+        recent_writes: &Arc<Mutex<RecentWrites>>,
+        clipboard: &Arc<dyn ClipboardBackend>,
+    ) -> Result<Option<ClipboardEntry>> {
+        let change_count = clipboard.change_count();
+        let record_repeats = Self::records_repeated_copies(
+            config_manager.lock().await.config.dedup_mode,
+            change_count,
+        );
+
+        // 先检查复制的文件：Finder 等同时提供文件名文本，按文本处理会丢失文件路径
+        let file_list =
+            Self::read_clipboard(clipboard, |clipboard| clipboard.get_file_list().map(Some))
+                .await
+                .unwrap_or_default();
+        if !file_list.is_empty() {
+            let paths: Vec<String> = file_list
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            let hash = Self::file_list_hash(&paths);
+
+            let should_send = last_seen
+                .lock()
+                .await
+                .observe(&hash, change_count, record_repeats);
+            if !should_send {
+                return Ok(None);
+            }
+            if Self::is_recent_write(recent_writes, &hash).await {
+                log::debug!("[ClipboardMonitor] 文件列表由应用自身写入，跳过记录");
+                return Ok(None);
+            }
 
-        if let Ok(text) = text_result {
+            if let Some(bundle_id) = app_info.as_ref().and_then(|info| info.bundle_id.as_ref()) {
+                if config_manager.lock().await.is_app_excluded(bundle_id) {
+                    log::debug!("[ClipboardMonitor] 文件来源应用在排除列表中，跳过");
+                    return Ok(None);
+                }
+            }
+            if Self::is_window_title_excluded(config_manager, app_info.as_ref()).await {
+                log::debug!("[ClipboardMonitor] 文件来源窗口标题命中排除模式，跳过");
+                return Ok(None);
+            }
+
+            log::info!(
+                "[ClipboardMonitor] 发现复制的文件: {} 个 | 来源: {}",
+                paths.len(),
+                app_info
+                    .as_ref()
+                    .map(|info| info.name.as_str())
+                    .unwrap_or("未知应用")
+            );
+            return Ok(Some(Self::build_file_list_entry(&paths, app_info.as_ref())));
+        }
+
+        // 检查文本内容
+        if let Some(text) = Self::read_clipboard(clipboard, |clipboard| clipboard.get_text()).await
+        {
             // 先trim处理文本
             let trimmed_text = text.trim();
             if !trimmed_text.is_empty() {
@@ -111,23 +326,27 @@ impl ClipboardMonitor {
                     trimmed_text.starts_with("data:image/") && trimmed_text.contains(";base64,");
                 if is_base64_image {
                     log::debug!("[ClipboardMonitor] 跳过base64图片URL，避免循环记录");
-                    return Ok(());
+                    return Ok(None);
                 }
 
                 let hash = Self::calculate_hash(trimmed_text.as_bytes());
                 log::debug!("[ClipboardMonitor] 计算内容Hash: {}", &hash[..8]);
 
-                let should_send = {
-                    let mut last = last_hash.lock().await;
-                    if last.as_ref() != Some(&hash) {
-                        *last = Some(hash.clone());
-                        log::debug!("[ClipboardMonitor] 新内容Hash，准备处理");
-                        true
-                    } else {
-                        log::debug!("[ClipboardMonitor] 重复内容Hash，跳过处理");
-                        false
-                    }
-                };
+                let should_send =
+                    last_seen
+                        .lock()
+                        .await
+                        .observe(&hash, change_count, record_repeats);
+                if should_send {
+                    log::debug!("[ClipboardMonitor] 新内容Hash，准备处理");
+                } else {
+                    log::debug!("[ClipboardMonitor] 重复内容Hash，跳过处理");
+                }
+
+                if should_send && Self::is_recent_write(recent_writes, &hash).await {
+                    log::debug!("[ClipboardMonitor] 内容由应用自身写入，跳过记录");
+                    return Ok(None);
+                }
 
                 if should_send {
                     // 检查是否是被排除的应用
@@ -139,47 +358,42 @@ impl ClipboardMonitor {
                                     "[ClipboardMonitor] 应用 {} 在排除列表中，跳过",
                                     app_info.name
                                 );
-                                return Ok(());
-                            }
-
-                            // 检查文本大小限制
-                            if !config_guard.is_text_size_valid(trimmed_text) {
-                                log::warn!(
-                                    "[ClipboardMonitor] 文本大小超限 ({}字符)，跳过",
-                                    trimmed_text.len()
-                                );
-                                return Ok(());
+                                return Ok(None);
                             }
                         }
                     }
 
-                    // 检测内容子类型
-                    let (subtype, metadata) = ContentDetector::detect(trimmed_text);
-                    log::debug!("[ClipboardMonitor] 内容检测结果: {:?}", subtype);
+                    if Self::is_window_title_excluded(config_manager, app_info.as_ref()).await {
+                        log::debug!("[ClipboardMonitor] 来源窗口标题命中排除模式，跳过");
+                        return Ok(None);
+                    }
 
-                    // 将metadata转换为JSON字符串
-                    let metadata_json = metadata.and_then(|m| serde_json::to_string(&m).ok());
+                    // 检查内容是否命中排除模式
+                    if config_manager
+                        .lock()
+                        .await
+                        .is_content_excluded(trimmed_text)
+                    {
+                        log::debug!("[ClipboardMonitor] 内容命中排除模式，跳过");
+                        return Ok(None);
+                    }
 
-                    let mut entry = ClipboardEntry::new(
-                        ContentType::Text,
-                        Some(trimmed_text.to_string()),
-                        hash,
-                        app_info.as_ref().map(|info| info.name.clone()),
-                        None,
-                    );
+                    // 同时读取 HTML/RTF，纯文本仍作为条目内容
+                    let rich_text = if config_manager.lock().await.config.capture_rich_text {
+                        Self::read_clipboard(clipboard, |clipboard| {
+                            clipboard.get_rich_text().map(Some)
+                        })
+                        .await
+                        .unwrap_or_default()
+                    } else {
+                        RichText::default()
+                    };
 
-                    // 设置子类型、元数据和bundle ID
-                    // 使用serde_json::to_value获取正确的snake_case字符串
-                    let subtype_str = serde_json::to_value(&subtype)
-                        .ok()
-                        .and_then(|v| v.as_str().map(|s| s.to_string()))
-                        .unwrap_or_else(|| "plain_text".to_string());
-                    entry.content_subtype = Some(subtype_str);
-                    entry.metadata = metadata_json;
-                    entry.app_bundle_id = app_info.as_ref().and_then(|info| info.bundle_id.clone());
+                    let entry =
+                        Self::build_text_entry(trimmed_text, hash, app_info.as_ref(), &rich_text);
 
                     log::info!(
-                        "[ClipboardMonitor] 发现新文本内容: {} | 来源: {} | 类型: {:?}",
+                        "[ClipboardMonitor] 发现新文本内容: {} | 来源: {} | 类型: {}",
                         if trimmed_text.chars().count() > 50 {
                             format!("{}...", trimmed_text.chars().take(50).collect::<String>())
                         } else {
@@ -189,24 +403,18 @@ impl ClipboardMonitor {
                             .as_ref()
                             .map(|info| info.name.as_str())
                             .unwrap_or("未知应用"),
-                        subtype
+                        entry.content_subtype.as_deref().unwrap_or("plain_text")
                     );
 
-                    let _ = tx.send(entry);
-                    return Ok(());
+                    return Ok(Some(entry));
                 }
             }
         }
 
-        // 检查图片内容 - 使用独立的剪切板实例
-        let image_result = tokio::task::spawn_blocking(|| match arboard::Clipboard::new() {
-            Ok(mut temp_clipboard) => temp_clipboard.get_image(),
-            Err(e) => Err(e),
-        })
-        .await
-        .unwrap_or(Err(arboard::Error::ClipboardNotSupported));
-
-        if let Ok(image_data) = image_result {
+        // 检查图片内容
+        if let Some(image_data) =
+            Self::read_clipboard(clipboard, |clipboard| clipboard.get_image()).await
+        {
             // arboard 返回的图片数据包含宽高信息
             let width = image_data.width;
             let height = image_data.height;
@@ -222,17 +430,20 @@ impl ClipboardMonitor {
             let hash = Self::calculate_hash(bytes);
             log::debug!("[ClipboardMonitor] 计算图片Hash: {}", &hash[..8]);
 
-            let should_send = {
-                let mut last = last_hash.lock().await;
-                if last.as_ref() != Some(&hash) {
-                    *last = Some(hash.clone());
-                    log::debug!("[ClipboardMonitor] 新图片Hash，准备处理");
-                    true
-                } else {
-                    log::debug!("[ClipboardMonitor] 重复图片Hash，跳过处理");
-                    false
-                }
-            };
+            let should_send = last_seen
+                .lock()
+                .await
+                .observe(&hash, change_count, record_repeats);
+            if should_send {
+                log::debug!("[ClipboardMonitor] 新图片Hash，准备处理");
+            } else {
+                log::debug!("[ClipboardMonitor] 重复图片Hash，跳过处理");
+            }
+
+            if should_send && Self::is_recent_write(recent_writes, &hash).await {
+                log::debug!("[ClipboardMonitor] 图片由应用自身写入，跳过记录");
+                return Ok(None);
+            }
 
             if should_send {
                 // 检查是否是被排除的应用
@@ -244,39 +455,35 @@ impl ClipboardMonitor {
                                 "[ClipboardMonitor] 图片来源应用 {} 在排除列表中，跳过",
                                 app_info.name
                             );
-                            return Ok(());
+                            return Ok(None);
                         }
                     }
                 }
 
+                if Self::is_window_title_excluded(config_manager, app_info.as_ref()).await {
+                    log::debug!("[ClipboardMonitor] 图片来源窗口标题命中排除模式，跳过");
+                    return Ok(None);
+                }
+
                 // 使用宽高信息处理图片
-                match processor
-                    .process_image_with_dimensions(bytes, width as u32, height as u32)
+                let storage = config_manager.lock().await.config.image_storage();
+                let entry = match processor
+                    .process_image_with_dimensions(bytes, width as u32, height as u32, storage)
                     .await
                 {
                     Ok(image_info) => {
                         log::info!(
                             "[ClipboardMonitor] 图片处理成功: {}x{} -> {} ({}字节) | 来源: {}",
-                            image_info.width,
-                            image_info.height,
+                            image_info.metadata.width,
+                            image_info.metadata.height,
                             image_info.file_path,
-                            image_info.actual_size,
+                            image_info.metadata.byte_size,
                             app_info
                                 .as_ref()
                                 .map(|info| info.name.as_str())
                                 .unwrap_or("未知应用")
                         );
 
-                        // 创建图片元数据，使用实际压缩后的文件大小
-                        let image_metadata = serde_json::json!({
-                            "image_metadata": {
-                                "width": image_info.width,
-                                "height": image_info.height,
-                                "file_size": image_info.actual_size,
-                                "format": "png"
-                            }
-                        });
-
                         let mut entry = ClipboardEntry::new(
                             ContentType::Image,
                             Some(image_info.file_path.clone()),
@@ -286,9 +493,11 @@ impl ClipboardMonitor {
                         );
                         entry.app_bundle_id =
                             app_info.as_ref().and_then(|info| info.bundle_id.clone());
-                        entry.metadata = Some(image_metadata.to_string());
+                        entry.source_window_title =
+                            app_info.as_ref().and_then(|info| info.window_title.clone());
+                        entry.metadata = Some(image_info.metadata.to_entry_metadata());
 
-                        let _ = tx.send(entry);
+                        Some(entry)
                     }
                     Err(e) => {
                         log::warn!(
@@ -296,29 +505,33 @@ impl ClipboardMonitor {
                             e
                         );
                         // 降级到自动检测
-                        match processor.process_image(bytes).await {
+                        match processor.process_image(bytes, storage).await {
                             Ok(file_path) => {
-                                // 获取实际保存的文件大小
-                                let actual_size = Self::get_saved_file_size(&file_path)
-                                    .unwrap_or(bytes.len() as u64);
+                                // 无法识别格式的原始数据读不出尺寸，使用剪贴板提供的尺寸
+                                let image_metadata = processor
+                                    .image_metadata(&file_path)
+                                    .unwrap_or_else(|_| ImageMetadata {
+                                        width: width as u32,
+                                        height: height as u32,
+                                        byte_size: std::fs::metadata(
+                                            processor.resolve_path(&file_path),
+                                        )
+                                        .map(|meta| meta.len())
+                                        .unwrap_or(bytes.len() as u64),
+                                        format: file_path
+                                            .rsplit('.')
+                                            .next()
+                                            .unwrap_or("bin")
+                                            .to_string(),
+                                    });
 
                                 log::info!("[ClipboardMonitor] 图片降级处理成功: {}x{} -> {} ({}字节) | 来源: {}", 
-                                    width, height,
+                                    image_metadata.width, image_metadata.height,
                                     file_path,
-                                    actual_size,
+                                    image_metadata.byte_size,
                                     app_info.as_ref().map(|info| info.name.as_str()).unwrap_or("未知应用")
                                 );
 
-                                // 创建图片元数据（使用压缩后的文件大小）
-                                let image_metadata = serde_json::json!({
-                                    "image_metadata": {
-                                        "width": width as u32,
-                                        "height": height as u32,
-                                        "file_size": actual_size,
-                                        "format": "png"
-                                    }
-                                });
-
                                 let mut entry = ClipboardEntry::new(
                                     ContentType::Image,
                                     Some(file_path.clone()),
@@ -328,9 +541,11 @@ impl ClipboardMonitor {
                                 );
                                 entry.app_bundle_id =
                                     app_info.as_ref().and_then(|info| info.bundle_id.clone());
-                                entry.metadata = Some(image_metadata.to_string());
+                                entry.source_window_title =
+                                    app_info.as_ref().and_then(|info| info.window_title.clone());
+                                entry.metadata = Some(image_metadata.to_entry_metadata());
 
-                                let _ = tx.send(entry);
+                                Some(entry)
                             }
                             Err(fallback_error) => {
                                 log::error!(
@@ -338,20 +553,68 @@ impl ClipboardMonitor {
                                     e,
                                     fallback_error
                                 );
+                                None
                             }
                         }
                     }
-                }
-                return Ok(());
+                };
+                return Ok(entry);
             }
         }
 
-        Ok(())
+        Ok(None)
+    }
+
+    /// 在阻塞线程中读取剪贴板，读取失败或没有对应格式时返回 None
+    async fn read_clipboard<T: Send + 'static>(
+        clipboard: &Arc<dyn ClipboardBackend>,
+        read: fn(&dyn ClipboardBackend) -> Result<Option<T>>,
+    ) -> Option<T> {
+        let clipboard = Arc::clone(clipboard);
+        tokio::task::spawn_blocking(move || read(clipboard.as_ref()))
+            .await
+            .ok()?
+            .ok()
+            .flatten()
     }
 
-    fn calculate_hash(data: &[u8]) -> String {
+    /// 来源窗口标题是否命中排除模式（没有标题时不排除）
+    async fn is_window_title_excluded(
+        config_manager: &Arc<Mutex<ConfigManager>>,
+        app_info: Option<&AppInfo>,
+    ) -> bool {
+        match app_info.and_then(|info| info.window_title.as_deref()) {
+            Some(title) => config_manager.lock().await.is_window_title_excluded(title),
+            None => false,
+        }
+    }
+
+    /// 内容是否由应用自身刚刚写入（命中后取出该记录）
+    async fn is_recent_write(recent_writes: &Arc<Mutex<RecentWrites>>, hash: &str) -> bool {
+        recent_writes.lock().await.take(hash, Instant::now())
+    }
+
+    pub fn calculate_hash(data: &[u8]) -> String {
         let mut hasher = Sha256::new();
         hasher.update(data);
         format!("{:x}", hasher.finalize())
     }
+
+    /// 按监听器读取图片的方式（RGBA 像素）计算编码图片的 Hash，无法解码时返回 None
+    pub fn calculate_image_hash(encoded: &[u8]) -> Option<String> {
+        let image = image::load_from_memory(encoded).ok()?;
+        Some(Self::calculate_hash(image.to_rgba8().as_raw()))
+    }
+}
+
+impl Drop for ClipboardMonitor {
+    fn drop(&mut self) {
+        // 停止轮询，否则 stop_monitoring 之后仍会继续记录
+        if let Ok(tasks) = self.tasks.get_mut() {
+            for task in tasks.drain(..) {
+                task.abort();
+            }
+        }
+        log::info!("[ClipboardMonitor] 已停止剪贴板监控");
+    }
 }
@@ -0,0 +1,50 @@
+#[cfg(not(feature = "ocr"))]
+use crate::error::CommandError;
+use anyhow::Result;
+use std::path::Path;
+
+/// 是否编译了 OCR 支持（`ocr` feature，需要系统安装 Tesseract）
+pub const AVAILABLE: bool = cfg!(feature = "ocr");
+
+/// 识别图片中的文字，没有识别到文字时返回 None
+#[cfg(feature = "ocr")]
+pub fn recognize_text(image_path: &Path) -> Result<Option<String>> {
+    let mut tesseract = leptess::LepTess::new(None, "eng")
+        .map_err(|e| anyhow::anyhow!("Failed to initialize Tesseract: {}", e))?;
+    tesseract
+        .set_image(image_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load image for OCR: {}", e))?;
+    let text = tesseract
+        .get_utf8_text()
+        .map_err(|e| anyhow::anyhow!("OCR output is not valid UTF-8: {}", e))?;
+    Ok(normalize_text(&text))
+}
+
+#[cfg(not(feature = "ocr"))]
+pub fn recognize_text(_image_path: &Path) -> Result<Option<String>> {
+    Err(
+        CommandError::unsupported("OCR support is not compiled in (build with the `ocr` feature)")
+            .into(),
+    )
+}
+
+/// 合并换行和多余空白，使跨行的文字也能被搜索到
+#[cfg(any(feature = "ocr", test))]
+fn normalize_text(text: &str) -> Option<String> {
+    let normalized = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!normalized.is_empty()).then_some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_text() {
+        assert_eq!(
+            normalize_text("  Hello\n\nWorld \t!\n"),
+            Some("Hello World !".to_string())
+        );
+        assert_eq!(normalize_text(" \n\x0c"), None);
+    }
+}
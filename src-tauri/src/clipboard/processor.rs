@@ -1,15 +1,61 @@
+use crate::error::CommandError;
 use anyhow::Result;
 use image::ImageFormat;
+use image_hasher::{HashAlg, HasherConfig, ImageHash};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+use crate::clipboard::{exif, gif, ocr};
+use crate::models::ImageMetadata;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SavedImageInfo {
     pub file_path: String,
-    pub actual_size: u64,
-    pub width: u32,
-    pub height: u32,
+    pub metadata: ImageMetadata,
+}
+
+/// 缩略图最长边允许的范围
+pub const MIN_THUMBNAIL_DIM: u32 = 16;
+pub const MAX_THUMBNAIL_DIM: u32 = 1024;
+
+/// 缩略图缓存目录名，位于 imgs/ 下，按尺寸分子目录
+pub const THUMBS_DIR_NAME: &str = "thumbs";
+
+/// 保存的图片最长边，超过时按比例缩小（4K）
+const MAX_IMAGE_DIMENSION: u32 = 3840;
+
+pub const DEFAULT_WEBP_QUALITY: u8 = 80;
+
+/// 图片的保存格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageStorageFormat {
+    /// 重新编码为无损 PNG
+    #[default]
+    Png,
+    /// 重新编码为有损 WebP，截图通常比 PNG 小得多
+    Webp,
+    /// 剪贴板里已是编码好的图片时保留原格式，原始像素数据仍保存为 PNG
+    Original,
+}
+
+/// 保存图片的选项，由配置生成
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageStorage {
+    pub format: ImageStorageFormat,
+    pub webp_quality: u8,     // 1-100
+    pub strip_metadata: bool, // Remove EXIF/XMP from images kept in their original encoding
+}
+
+impl Default for ImageStorage {
+    fn default() -> Self {
+        Self {
+            format: ImageStorageFormat::default(),
+            webp_quality: DEFAULT_WEBP_QUALITY,
+            strip_metadata: true,
+        }
+    }
 }
 
 pub struct ContentProcessor {
@@ -26,11 +72,132 @@ impl ContentProcessor {
         Ok(Self { imgs_dir })
     }
 
+    #[cfg(test)]
+    pub fn with_dir(imgs_dir: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&imgs_dir)?;
+        Ok(Self { imgs_dir })
+    }
+
+    pub fn imgs_dir(&self) -> &PathBuf {
+        &self.imgs_dir
+    }
+
+    /// 将数据库中的相对路径（imgs/xxx.png）解析为绝对路径
+    pub fn resolve_path(&self, file_path: &str) -> PathBuf {
+        self.imgs_dir.join(file_path.trim_start_matches("imgs/"))
+    }
+
+    /// 缩略图缓存目录 imgs/thumbs/
+    pub fn thumbs_dir(&self) -> PathBuf {
+        self.imgs_dir.join(THUMBS_DIR_NAME)
+    }
+
+    /// 删除原图在各个尺寸下缓存的缩略图，返回释放的字节数；删除失败时只记录日志
+    pub fn remove_thumbnails(&self, file_path: &str) -> u64 {
+        let Some(stem) = Path::new(file_path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+        else {
+            return 0;
+        };
+        let Ok(size_dirs) = std::fs::read_dir(self.thumbs_dir()) else {
+            return 0;
+        };
+
+        let mut freed_bytes = 0;
+        for size_dir in size_dirs.flatten() {
+            let thumb_path = size_dir.path().join(format!("{}.png", stem));
+            let Ok(metadata) = std::fs::metadata(&thumb_path) else {
+                continue;
+            };
+            match std::fs::remove_file(&thumb_path) {
+                Ok(()) => freed_bytes += metadata.len(),
+                Err(e) => log::warn!("[ContentProcessor] 删除缩略图失败 {:?}: {}", thumb_path, e),
+            }
+        }
+        freed_bytes
+    }
+
+    /// 读取已保存图片的尺寸、文件大小和格式（按文件内容识别格式，只解析文件头）
+    pub fn image_metadata(&self, file_path: &str) -> Result<ImageMetadata> {
+        let full_path = self.resolve_path(file_path);
+        let reader = image::ImageReader::open(&full_path)?.with_guessed_format()?;
+        let format = reader
+            .format()
+            .ok_or_else(|| anyhow::anyhow!("无法识别图片格式: {:?}", full_path))?;
+        let (width, height) = reader.into_dimensions()?;
+
+        Ok(ImageMetadata {
+            width,
+            height,
+            byte_size: std::fs::metadata(&full_path)?.len(),
+            format: format.extensions_str()[0].to_string(),
+        })
+    }
+
+    /// 获取图片的缩略图路径，首次请求时生成并缓存到 imgs/thumbs/<max_dim>/ 下
+    pub async fn get_thumbnail(&self, file_path: &str, max_dim: u32) -> Result<PathBuf> {
+        if !(MIN_THUMBNAIL_DIM..=MAX_THUMBNAIL_DIM).contains(&max_dim) {
+            return Err(CommandError::invalid_input(format!(
+                "Thumbnail size must be between {} and {}",
+                MIN_THUMBNAIL_DIM, MAX_THUMBNAIL_DIM
+            ))
+            .into());
+        }
+
+        let source_path = self.resolve_path(file_path);
+        let stem = source_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| {
+                CommandError::invalid_input(format!("Invalid image path: {}", file_path))
+            })?;
+        let thumb_path = self
+            .thumbs_dir()
+            .join(max_dim.to_string())
+            .join(format!("{}.png", stem));
+
+        if thumb_path.exists() {
+            return Ok(thumb_path);
+        }
+        if !source_path.exists() {
+            return Err(
+                CommandError::not_found(format!("File not found: {:?}", source_path)).into(),
+            );
+        }
+
+        let output_path = thumb_path.clone();
+        tokio::task::spawn_blocking(move || {
+            generate_thumbnail(&source_path, &output_path, max_dim)
+        })
+        .await??;
+
+        Ok(thumb_path)
+    }
+
+    /// 识别已保存图片中的文字，在阻塞线程池中执行
+    pub async fn recognize_image_text(&self, file_path: &str) -> Result<Option<String>> {
+        let full_path = self.resolve_path(file_path);
+        tokio::task::spawn_blocking(move || ocr::recognize_text(&full_path)).await?
+    }
+
+    /// 计算已保存图片的感知哈希（dHash），缩放或重新编码后的相同图片哈希值相近
+    pub async fn perceptual_hash(&self, file_path: &str) -> Result<String> {
+        let full_path = self.resolve_path(file_path);
+        tokio::task::spawn_blocking(move || -> Result<String> {
+            let img = image::load_from_memory(&std::fs::read(&full_path)?)?;
+            let hasher = HasherConfig::new().hash_alg(HashAlg::Gradient).to_hasher();
+            Ok(hasher.hash_image(&img).to_base64())
+        })
+        .await?
+    }
+
     pub async fn process_image_with_dimensions(
         &self,
         image_data: &[u8],
         width: u32,
         height: u32,
+        storage: ImageStorage,
     ) -> Result<SavedImageInfo> {
         log::info!(
             "[ContentProcessor] 开始处理指定尺寸图片: {}x{}, 数据大小: {} 字节",
@@ -39,6 +206,10 @@ impl ContentProcessor {
             image_data.len()
         );
 
+        if gif::is_animated(image_data) {
+            return self.save_animated_gif(image_data).await;
+        }
+
         // 验证数据长度是否匹配RGBA格式
         let expected_size = (width * height * 4) as usize;
         if image_data.len() == expected_size {
@@ -47,7 +218,7 @@ impl ContentProcessor {
                 expected_size
             );
             return self
-                .process_raw_rgba_data_with_info(image_data, width, height)
+                .process_raw_rgba_data_with_info(image_data, width, height, storage)
                 .await;
         }
 
@@ -57,11 +228,13 @@ impl ContentProcessor {
             image_data.len(),
             expected_size
         );
-        self.process_image_with_info(image_data, width, height)
+        self.process_image_with_info(image_data, width, height, storage)
             .await
     }
 
-    pub async fn process_image(&self, image_data: &[u8]) -> Result<String> {
+    /// 处理格式未知的图片数据。重新编码后的图片本身不含元数据，
+    /// storage.strip_metadata 控制按原始字节保存的图片是否移除 EXIF
+    pub async fn process_image(&self, image_data: &[u8], storage: ImageStorage) -> Result<String> {
         log::info!(
             "[ContentProcessor] 开始处理未知尺寸图片，数据大小: {} 字节",
             image_data.len()
@@ -71,6 +244,10 @@ impl ContentProcessor {
             &image_data[..image_data.len().min(32)]
         );
 
+        if gif::is_animated(image_data) {
+            return Ok(self.save_animated_gif(image_data).await?.file_path);
+        }
+
         // 首先检查是否是原始像素数据
         if let Some((width, height)) = self.detect_raw_rgba_data(image_data) {
             log::info!(
@@ -78,7 +255,9 @@ impl ContentProcessor {
                 width,
                 height
             );
-            return self.process_raw_rgba_data(image_data, width, height).await;
+            return self
+                .process_raw_rgba_data(image_data, width, height, storage)
+                .await;
         }
 
         // 如果不是标准分辨率，但数据长度是4的倍数，可能仍然是RGBA数据
@@ -108,7 +287,7 @@ impl ContentProcessor {
             for (w, h) in possible_dimensions {
                 if w > 0 && h > 0 && (w * h) as usize == pixel_count {
                     log::debug!("[process_image] 尝试使用推断尺寸: {}x{}", w, h);
-                    match self.process_raw_rgba_data(image_data, w, h).await {
+                    match self.process_raw_rgba_data(image_data, w, h, storage).await {
                         Ok(result) => {
                             log::debug!("[process_image] 成功使用尺寸 {}x{}", w, h);
                             return Ok(result);
@@ -183,7 +362,7 @@ impl ContentProcessor {
                                 img.width(),
                                 img.height()
                             );
-                            return self.save_image(img, &file_path).await;
+                            return self.save_image(img, &file_path, storage).await;
                         }
                         Err(e) => {
                             log::trace!("[ContentProcessor] 格式 {:?} 加载失败: {}", format, e);
@@ -194,11 +373,44 @@ impl ContentProcessor {
 
                 // 如果所有格式都失败，但确实是图片数据，保存原始数据
                 log::warn!("[ContentProcessor] 所有标准格式解码失败，尝试保存原始数据");
-                return self.save_raw_image_data(image_data, &file_path).await;
+                return self
+                    .save_raw_image_data(image_data, &file_path, storage.strip_metadata)
+                    .await;
             }
         };
 
-        self.save_image(img, &file_path).await
+        if Self::keeps_original_encoding(&img, storage) {
+            return self
+                .save_raw_image_data(image_data, &file_path, storage.strip_metadata)
+                .await;
+        }
+        self.save_image(img, &file_path, storage).await
+    }
+
+    /// 选择保留原格式且不需要缩小时，直接保存原始字节
+    fn keeps_original_encoding(img: &image::DynamicImage, storage: ImageStorage) -> bool {
+        storage.format == ImageStorageFormat::Original
+            && img.width().max(img.height()) <= MAX_IMAGE_DIMENSION
+    }
+
+    /// 动图按原始字节保存，重新编码只会保留第一帧
+    async fn save_animated_gif(&self, image_data: &[u8]) -> Result<SavedImageInfo> {
+        let filename = format!("{}.gif", Uuid::new_v4());
+        tokio::fs::write(self.imgs_dir.join(&filename), image_data).await?;
+
+        let file_path = format!("imgs/{}", filename);
+        let metadata = self.image_metadata(&file_path)?;
+        log::info!(
+            "[ContentProcessor] 保存动图: {}x{}, {} 字节",
+            metadata.width,
+            metadata.height,
+            metadata.byte_size
+        );
+
+        Ok(SavedImageInfo {
+            file_path,
+            metadata,
+        })
     }
 
     async fn process_image_with_info(
@@ -206,6 +418,7 @@ impl ContentProcessor {
         image_data: &[u8],
         _expected_width: u32,
         _expected_height: u32,
+        storage: ImageStorage,
     ) -> Result<SavedImageInfo> {
         log::debug!(
             "[process_image_with_info] 开始处理图片数据，大小: {} 字节",
@@ -228,39 +441,56 @@ impl ContentProcessor {
 
         // 尝试解析并保存图片
         let img = image::load_from_memory(image_data)?;
-        let (actual_width, actual_height) = (img.width(), img.height());
-
-        self.save_image(img, &file_path).await?;
+        let saved_path = if Self::keeps_original_encoding(&img, storage) {
+            self.save_raw_image_data(image_data, &file_path, storage.strip_metadata)
+                .await?
+        } else {
+            self.save_image(img, &file_path, storage).await?
+        };
 
-        // 获取实际保存的文件大小
-        let actual_size = std::fs::metadata(&file_path)?.len();
+        // 记录实际保存的尺寸（超大图片会被缩小）和压缩后的文件大小
+        let metadata = self.image_metadata(&saved_path)?;
 
         log::info!(
             "[process_image_with_info] 成功处理图片: {}x{}, 压缩后大小: {} 字节",
-            actual_width,
-            actual_height,
-            actual_size
+            metadata.width,
+            metadata.height,
+            metadata.byte_size
         );
 
         Ok(SavedImageInfo {
-            file_path: format!("imgs/{}", filename),
-            actual_size,
-            width: actual_width,
-            height: actual_height,
+            file_path: saved_path,
+            metadata,
         })
     }
 
+    /// 按配置的格式编码并保存，扩展名随格式调整，返回相对路径
     async fn save_image(
         &self,
         img: image::DynamicImage,
         file_path: &std::path::Path,
+        storage: ImageStorage,
     ) -> Result<String> {
         // 压缩图片：保持分辨率但优化质量和文件大小
         let compressed_img = self.compress_image(img)?;
-        compressed_img.save(file_path)?;
+        let file_path = match storage.format {
+            ImageStorageFormat::Webp => {
+                let file_path = file_path.with_extension("webp");
+                std::fs::write(
+                    &file_path,
+                    encode_webp(&compressed_img, storage.webp_quality),
+                )?;
+                file_path
+            }
+            ImageStorageFormat::Png | ImageStorageFormat::Original => {
+                let file_path = file_path.with_extension("png");
+                compressed_img.save_with_format(&file_path, ImageFormat::Png)?;
+                file_path
+            }
+        };
 
         // 记录压缩后的实际文件大小
-        if let Ok(metadata) = std::fs::metadata(file_path) {
+        if let Ok(metadata) = std::fs::metadata(&file_path) {
             let compressed_size = metadata.len();
             log::debug!("[save_image] 压缩后文件大小: {} 字节", compressed_size);
         }
@@ -280,7 +510,7 @@ impl ContentProcessor {
         log::debug!("[compress_image] 原始图片尺寸: {}x{}", width, height);
 
         // 如果图片太大（超过4K），缩小到合理尺寸但保持宽高比
-        let max_dimension = 3840; // 4K 最大边长
+        let max_dimension = MAX_IMAGE_DIMENSION;
         let img = if width > max_dimension || height > max_dimension {
             let ratio = (max_dimension as f32) / (width.max(height) as f32);
             let new_width = (width as f32 * ratio) as u32;
@@ -315,6 +545,7 @@ impl ContentProcessor {
         rgba_data: &[u8],
         width: u32,
         height: u32,
+        storage: ImageStorage,
     ) -> Result<String> {
         log::debug!(
             "[process_raw_rgba_data] 开始处理RGBA数据: {}x{}, 数据大小: {} 字节",
@@ -389,19 +620,13 @@ impl ContentProcessor {
         let dynamic_img = image::DynamicImage::ImageRgba8(img_buffer);
 
         // 压缩后保存
-        let compressed_img = self.compress_image(dynamic_img)?;
-        compressed_img.save(&file_path)?;
-
-        let filename = file_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .ok_or_else(|| anyhow::anyhow!("无法获取文件名"))?;
+        let saved_path = self.save_image(dynamic_img, &file_path, storage).await?;
 
         log::info!(
             "[process_raw_rgba_data] 成功处理原始数据并保存为: {}",
-            filename
+            saved_path
         );
-        Ok(format!("imgs/{}", filename))
+        Ok(saved_path)
     }
 
     async fn process_raw_rgba_data_with_info(
@@ -409,6 +634,7 @@ impl ContentProcessor {
         rgba_data: &[u8],
         width: u32,
         height: u32,
+        storage: ImageStorage,
     ) -> Result<SavedImageInfo> {
         log::debug!(
             "[process_raw_rgba_data_with_info] 开始处理RGBA数据: {}x{}, 数据大小: {} 字节",
@@ -455,24 +681,19 @@ impl ContentProcessor {
             .ok_or_else(|| anyhow::anyhow!("无法从原始数据创建图像缓冲区"))?;
 
         let dynamic_img = image::DynamicImage::ImageRgba8(img_buffer);
-        let compressed_img = self.compress_image(dynamic_img)?;
-        compressed_img.save(&file_path)?;
-
-        // 获取实际保存的文件大小
-        let actual_size = std::fs::metadata(&file_path)?.len();
+        let saved_path = self.save_image(dynamic_img, &file_path, storage).await?;
+        let metadata = self.image_metadata(&saved_path)?;
 
         log::info!(
             "[process_raw_rgba_data_with_info] 成功处理原始数据: {}x{}, 压缩后大小: {} 字节",
-            width,
-            height,
-            actual_size
+            metadata.width,
+            metadata.height,
+            metadata.byte_size
         );
 
         Ok(SavedImageInfo {
-            file_path: format!("imgs/{}", filename),
-            actual_size,
-            width,
-            height,
+            file_path: saved_path,
+            metadata,
         })
     }
 
@@ -480,7 +701,22 @@ impl ContentProcessor {
         &self,
         image_data: &[u8],
         file_path: &std::path::Path,
+        strip_metadata: bool,
     ) -> Result<String> {
+        // 移除 EXIF 等元数据（如 GPS 位置、设备信息），不改变像素和格式
+        let image_data = if strip_metadata {
+            match exif::strip_metadata(image_data) {
+                Some(stripped) => stripped,
+                None => {
+                    log::debug!("[save_raw_image_data] 不支持的格式，保留原始数据");
+                    image_data.into()
+                }
+            }
+        } else {
+            image_data.into()
+        };
+        let image_data = image_data.as_ref();
+
         // 尝试根据检测到的格式使用正确的扩展名
         let (extension, actual_path) = if let Some(mime_type) = infer::get(image_data) {
             let ext = match mime_type.mime_type() {
@@ -761,3 +997,365 @@ impl ContentProcessor {
         false
     }
 }
+
+/// 两个感知哈希之间的汉明距离，哈希无效或长度不同时返回 None
+pub fn perceptual_hash_distance(a: &str, b: &str) -> Option<u32> {
+    let a = ImageHash::<Box<[u8]>>::from_base64(a).ok()?;
+    let b = ImageHash::<Box<[u8]>>::from_base64(b).ok()?;
+    (a.as_bytes().len() == b.as_bytes().len()).then(|| a.dist(&b))
+}
+
+/// 有损编码为 WebP，quality 取值 1-100
+fn encode_webp(img: &image::DynamicImage, quality: u8) -> Vec<u8> {
+    let quality = quality.clamp(1, 100) as f32;
+    if img.color().has_alpha() {
+        let rgba = img.to_rgba8();
+        webp::Encoder::from_rgba(&rgba, rgba.width(), rgba.height())
+            .encode(quality)
+            .to_vec()
+    } else {
+        let rgb = img.to_rgb8();
+        webp::Encoder::from_rgb(&rgb, rgb.width(), rgb.height())
+            .encode(quality)
+            .to_vec()
+    }
+}
+
+/// 按最长边缩放图片并保存为 PNG，小于目标尺寸的图片不放大
+fn generate_thumbnail(source_path: &Path, thumb_path: &Path, max_dim: u32) -> Result<()> {
+    // 原图可能是 .bin 等扩展名，按内容识别格式
+    let img = image::load_from_memory(&std::fs::read(source_path)?)?;
+    let thumbnail = if img.width() > max_dim || img.height() > max_dim {
+        img.thumbnail(max_dim, max_dim)
+    } else {
+        img
+    };
+
+    if let Some(parent) = thumb_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    // 先写临时文件再重命名，避免并发请求读到写了一半的缓存
+    let tmp_path = thumb_path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+    thumbnail.save_with_format(&tmp_path, ImageFormat::Png)?;
+    std::fs::rename(&tmp_path, thumb_path)?;
+
+    log::debug!(
+        "[ContentProcessor] 生成缩略图 {}x{}: {:?}",
+        thumbnail.width(),
+        thumbnail.height(),
+        thumb_path
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_sample_image(processor: &ContentProcessor, name: &str, width: u32, height: u32) {
+        let img = image::RgbImage::from_fn(width, height, |x, y| {
+            image::Rgb([(x % 256) as u8, (y % 256) as u8, 128])
+        });
+        img.save(processor.imgs_dir().join(name)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_thumbnail_scales_to_max_dim() {
+        let temp_dir = TempDir::new().unwrap();
+        let processor = ContentProcessor::with_dir(temp_dir.path().join("imgs")).unwrap();
+        write_sample_image(&processor, "wide.png", 400, 200);
+
+        let thumb_path = processor.get_thumbnail("imgs/wide.png", 100).await.unwrap();
+        assert_eq!(
+            thumb_path,
+            processor
+                .imgs_dir()
+                .join("thumbs")
+                .join("100")
+                .join("wide.png")
+        );
+        assert_eq!(image::image_dimensions(&thumb_path).unwrap(), (100, 50));
+
+        // Each size has its own cache entry
+        let larger = processor.get_thumbnail("imgs/wide.png", 200).await.unwrap();
+        assert_eq!(image::image_dimensions(&larger).unwrap(), (200, 100));
+
+        // Small images are not upscaled
+        write_sample_image(&processor, "small.png", 40, 20);
+        let small = processor
+            .get_thumbnail("imgs/small.png", 100)
+            .await
+            .unwrap();
+        assert_eq!(image::image_dimensions(&small).unwrap(), (40, 20));
+    }
+
+    #[tokio::test]
+    async fn test_get_thumbnail_reuses_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let processor = ContentProcessor::with_dir(temp_dir.path().join("imgs")).unwrap();
+        write_sample_image(&processor, "cached.png", 300, 300);
+
+        let first = processor
+            .get_thumbnail("imgs/cached.png", 64)
+            .await
+            .unwrap();
+        std::fs::write(&first, b"cached thumbnail").unwrap();
+
+        let second = processor
+            .get_thumbnail("imgs/cached.png", 64)
+            .await
+            .unwrap();
+        assert_eq!(first, second);
+        assert_eq!(std::fs::read(&second).unwrap(), b"cached thumbnail");
+    }
+
+    #[tokio::test]
+    async fn test_remove_thumbnails_removes_every_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let processor = ContentProcessor::with_dir(temp_dir.path().join("imgs")).unwrap();
+        write_sample_image(&processor, "gone.png", 300, 300);
+        write_sample_image(&processor, "kept.png", 300, 300);
+
+        let small = processor.get_thumbnail("imgs/gone.png", 64).await.unwrap();
+        let large = processor.get_thumbnail("imgs/gone.png", 128).await.unwrap();
+        let other = processor.get_thumbnail("imgs/kept.png", 64).await.unwrap();
+        let expected =
+            std::fs::metadata(&small).unwrap().len() + std::fs::metadata(&large).unwrap().len();
+
+        assert_eq!(processor.remove_thumbnails("imgs/gone.png"), expected);
+        assert!(!small.exists() && !large.exists());
+        assert!(other.exists());
+        assert_eq!(processor.remove_thumbnails("imgs/gone.png"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_decoded_images_are_saved_without_exif() {
+        use crate::clipboard::exif::tests::{contains, jpeg_with_exif};
+
+        let temp_dir = TempDir::new().unwrap();
+        let processor = ContentProcessor::with_dir(temp_dir.path().join("imgs")).unwrap();
+
+        let info = processor
+            .process_image_with_dimensions(&jpeg_with_exif(), 16, 16, ImageStorage::default())
+            .await
+            .unwrap();
+        let saved = std::fs::read(processor.resolve_path(&info.file_path)).unwrap();
+        assert!(!contains(&saved, b"Exif"));
+        assert_eq!((info.metadata.width, info.metadata.height), (16, 16));
+    }
+
+    #[tokio::test]
+    async fn test_save_raw_image_data_strips_exif() {
+        use crate::clipboard::exif::tests::{contains, jpeg_with_exif};
+
+        let temp_dir = TempDir::new().unwrap();
+        let processor = ContentProcessor::with_dir(temp_dir.path().join("imgs")).unwrap();
+        let original = jpeg_with_exif();
+
+        let stripped = processor
+            .save_raw_image_data(&original, &processor.imgs_dir().join("stripped.bin"), true)
+            .await
+            .unwrap();
+        assert_eq!(stripped, "imgs/stripped.jpg");
+        let saved = std::fs::read(processor.resolve_path(&stripped)).unwrap();
+        assert!(!contains(&saved, b"Exif"));
+        assert_eq!(
+            image::load_from_memory(&saved).unwrap().to_rgb8(),
+            image::load_from_memory(&original).unwrap().to_rgb8()
+        );
+
+        let kept = processor
+            .save_raw_image_data(&original, &processor.imgs_dir().join("kept.bin"), false)
+            .await
+            .unwrap();
+        let saved = std::fs::read(processor.resolve_path(&kept)).unwrap();
+        assert_eq!(saved, original);
+    }
+
+    fn encode_sample_image(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+        let img = image::RgbImage::from_fn(width, height, |x, y| {
+            image::Rgb([(x * 7 % 256) as u8, (y * 11 % 256) as u8, 64])
+        });
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut buffer, format).unwrap();
+        buffer.into_inner()
+    }
+
+    #[tokio::test]
+    async fn test_saved_image_metadata_matches_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let processor = ContentProcessor::with_dir(temp_dir.path().join("imgs")).unwrap();
+
+        for format in [ImageFormat::Png, ImageFormat::Jpeg] {
+            let data = encode_sample_image(37, 23, format);
+            let info = processor
+                .process_image_with_dimensions(&data, 37, 23, ImageStorage::default())
+                .await
+                .unwrap();
+
+            let saved_size = std::fs::metadata(processor.resolve_path(&info.file_path))
+                .unwrap()
+                .len();
+            assert_eq!(
+                info.metadata,
+                ImageMetadata {
+                    width: 37,
+                    height: 23,
+                    byte_size: saved_size,
+                    format: "png".to_string(),
+                },
+                "source format {:?}",
+                format
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_image_metadata_detects_stored_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let processor = ContentProcessor::with_dir(temp_dir.path().join("imgs")).unwrap();
+
+        // Undecodable-at-save-time images keep their original encoding
+        let jpeg = encode_sample_image(50, 40, ImageFormat::Jpeg);
+        let saved = processor
+            .save_raw_image_data(&jpeg, &processor.imgs_dir().join("raw.bin"), false)
+            .await
+            .unwrap();
+
+        let metadata = processor.image_metadata(&saved).unwrap();
+        assert_eq!((metadata.width, metadata.height), (50, 40));
+        assert_eq!(metadata.byte_size, jpeg.len() as u64);
+        assert_eq!(metadata.format, "jpg");
+
+        assert!(processor.image_metadata("imgs/missing.png").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_webp_storage_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let processor = ContentProcessor::with_dir(temp_dir.path().join("imgs")).unwrap();
+        let storage = ImageStorage {
+            format: ImageStorageFormat::Webp,
+            ..Default::default()
+        };
+
+        let png = encode_sample_image(64, 48, ImageFormat::Png);
+        let info = processor
+            .process_image_with_dimensions(&png, 64, 48, storage)
+            .await
+            .unwrap();
+        assert!(info.file_path.ends_with(".webp"));
+        assert_eq!(info.metadata.format, "webp");
+        let saved = std::fs::read(processor.resolve_path(&info.file_path)).unwrap();
+        let decoded = image::load_from_memory_with_format(&saved, ImageFormat::WebP).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (64, 48));
+
+        // Raw RGBA pixels from the clipboard are converted as well
+        let rgba = vec![200u8; 20 * 10 * 4];
+        let info = processor
+            .process_image_with_dimensions(&rgba, 20, 10, storage)
+            .await
+            .unwrap();
+        assert_eq!(info.metadata.format, "webp");
+        assert_eq!((info.metadata.width, info.metadata.height), (20, 10));
+        let saved = std::fs::read(processor.resolve_path(&info.file_path)).unwrap();
+        assert!(image::load_from_memory(&saved).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_original_storage_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let processor = ContentProcessor::with_dir(temp_dir.path().join("imgs")).unwrap();
+        let storage = ImageStorage {
+            format: ImageStorageFormat::Original,
+            ..Default::default()
+        };
+
+        let jpeg = encode_sample_image(37, 23, ImageFormat::Jpeg);
+        let info = processor
+            .process_image_with_dimensions(&jpeg, 37, 23, storage)
+            .await
+            .unwrap();
+        assert_eq!(info.metadata.format, "jpg");
+        let saved = std::fs::read(processor.resolve_path(&info.file_path)).unwrap();
+        assert_eq!(saved, jpeg);
+
+        let file_path = processor.process_image(&jpeg, storage).await.unwrap();
+        assert!(file_path.ends_with(".jpg"));
+
+        // Raw pixels have no original encoding and fall back to PNG
+        let rgba = vec![200u8; 20 * 10 * 4];
+        let info = processor
+            .process_image_with_dimensions(&rgba, 20, 10, storage)
+            .await
+            .unwrap();
+        assert_eq!(info.metadata.format, "png");
+        let saved = std::fs::read(processor.resolve_path(&info.file_path)).unwrap();
+        assert!(image::load_from_memory_with_format(&saved, ImageFormat::Png).is_ok());
+    }
+
+    #[test]
+    fn test_image_storage_format_serde() {
+        assert_eq!(
+            serde_json::to_string(&ImageStorageFormat::Webp).unwrap(),
+            "\"webp\""
+        );
+        assert_eq!(
+            serde_json::from_str::<ImageStorageFormat>("\"original\"").unwrap(),
+            ImageStorageFormat::Original
+        );
+        assert_eq!(ImageStorageFormat::default(), ImageStorageFormat::Png);
+    }
+
+    #[tokio::test]
+    async fn test_animated_gif_keeps_all_frames() {
+        use crate::clipboard::gif::tests::encode_gif;
+
+        let temp_dir = TempDir::new().unwrap();
+        let processor = ContentProcessor::with_dir(temp_dir.path().join("imgs")).unwrap();
+        let animated = encode_gif(3);
+
+        let info = processor
+            .process_image_with_dimensions(&animated, 8, 6, ImageStorage::default())
+            .await
+            .unwrap();
+        assert!(info.file_path.ends_with(".gif"));
+        assert_eq!(info.metadata.format, "gif");
+        let saved = std::fs::read(processor.resolve_path(&info.file_path)).unwrap();
+        assert_eq!(gif::frame_count(&saved), Some(3));
+        assert_eq!(saved, animated);
+
+        let file_path = processor
+            .process_image(&animated, ImageStorage::default())
+            .await
+            .unwrap();
+        let saved = std::fs::read(processor.resolve_path(&file_path)).unwrap();
+        assert_eq!(gif::frame_count(&saved), Some(3));
+
+        // Single-frame GIFs are still converted like any other image
+        let still = processor
+            .process_image_with_dimensions(&encode_gif(1), 8, 6, ImageStorage::default())
+            .await
+            .unwrap();
+        assert_eq!(still.metadata.format, "png");
+    }
+
+    #[tokio::test]
+    async fn test_get_thumbnail_rejects_bad_input() {
+        let temp_dir = TempDir::new().unwrap();
+        let processor = ContentProcessor::with_dir(temp_dir.path().join("imgs")).unwrap();
+
+        assert!(processor
+            .get_thumbnail("imgs/missing.png", 64)
+            .await
+            .is_err());
+        write_sample_image(&processor, "sized.png", 32, 32);
+        assert!(processor.get_thumbnail("imgs/sized.png", 0).await.is_err());
+        assert!(processor
+            .get_thumbnail("imgs/sized.png", MAX_THUMBNAIL_DIM + 1)
+            .await
+            .is_err());
+    }
+}
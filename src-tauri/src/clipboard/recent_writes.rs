@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// 应用写入剪贴板后等待监听器读到该内容的最长时间，超时的记录不再用于跳过
+pub const RECENT_WRITE_TTL: Duration = Duration::from_secs(5);
+
+/// 应用自身最近写入剪贴板的内容 Hash 及写入时间。
+/// 监听器读到其中的内容时不再记录，连续多次写入时每一次都能被识别
+#[derive(Debug)]
+pub struct RecentWrites {
+    ttl: Duration,
+    hashes: HashMap<String, Instant>,
+}
+
+impl Default for RecentWrites {
+    fn default() -> Self {
+        Self::new(RECENT_WRITE_TTL)
+    }
+}
+
+impl RecentWrites {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            hashes: HashMap::new(),
+        }
+    }
+
+    /// 记录一次写入，同一内容再次写入时刷新时间
+    pub fn insert(&mut self, hash: String, now: Instant) {
+        self.prune(now);
+        self.hashes.insert(hash, now);
+    }
+
+    /// 写入剪贴板失败时撤销记录
+    pub fn remove(&mut self, hash: &str) {
+        self.hashes.remove(hash);
+    }
+
+    /// 内容是否由应用刚刚写入；命中的记录被取出，之后外部再复制相同内容时仍会记录
+    pub fn take(&mut self, hash: &str, now: Instant) -> bool {
+        self.prune(now);
+        self.hashes.remove(hash).is_some()
+    }
+
+    #[cfg(test)]
+    pub fn contains(&self, hash: &str, now: Instant) -> bool {
+        self.hashes
+            .get(hash)
+            .is_some_and(|written_at| now.duration_since(*written_at) < self.ttl)
+    }
+
+    fn prune(&mut self, now: Instant) {
+        let ttl = self.ttl;
+        self.hashes
+            .retain(|_, written_at| now.duration_since(*written_at) < ttl);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rapid_app_writes_are_all_skipped() {
+        let mut writes = RecentWrites::default();
+        let start = Instant::now();
+
+        // Three programmatic copies before the monitor polls even once
+        for (i, hash) in ["a", "b", "c"].into_iter().enumerate() {
+            writes.insert(
+                hash.to_string(),
+                start + Duration::from_millis(i as u64 * 10),
+            );
+        }
+
+        // The monitor may observe any of them, in any order
+        let poll = start + Duration::from_millis(500);
+        assert!(writes.take("c", poll));
+        assert!(writes.take("a", poll));
+        assert!(writes.take("b", poll));
+    }
+
+    #[test]
+    fn test_external_changes_are_not_skipped() {
+        let mut writes = RecentWrites::default();
+        let start = Instant::now();
+        writes.insert("app".to_string(), start);
+
+        assert!(!writes.take("external", start + Duration::from_millis(100)));
+        assert!(writes.take("app", start + Duration::from_millis(200)));
+
+        // Once consumed, copying the same content from another app is recorded again
+        assert!(!writes.take("app", start + Duration::from_millis(300)));
+    }
+
+    #[test]
+    fn test_writes_expire_after_ttl() {
+        let mut writes = RecentWrites::new(Duration::from_secs(2));
+        let start = Instant::now();
+        writes.insert("old".to_string(), start);
+        writes.insert("new".to_string(), start + Duration::from_secs(1));
+
+        let later = start + Duration::from_millis(2500);
+        assert!(!writes.contains("old", later));
+        assert!(writes.contains("new", later));
+        assert!(!writes.take("old", later));
+        assert!(writes.take("new", later));
+
+        // Failed writes are forgotten immediately
+        writes.insert("failed".to_string(), later);
+        writes.remove("failed");
+        assert!(!writes.take("failed", later));
+    }
+}
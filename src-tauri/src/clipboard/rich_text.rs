@@ -0,0 +1,91 @@
+use crate::models::RichText;
+#[cfg(target_os = "macos")]
+use anyhow::Result;
+
+/// 组合剪贴板后端读取到的 HTML 和剪贴板上的 RTF，空白内容视为没有该格式
+pub fn with_rtf(html: Option<String>) -> RichText {
+    RichText {
+        html: html.filter(|html| !html.trim().is_empty()),
+        rtf: read_rtf(),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn read_rtf() -> Option<String> {
+    use cocoa::appkit::NSPasteboard;
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::{NSAutoreleasePool, NSString};
+    use objc::{msg_send, sel, sel_impl};
+
+    unsafe {
+        let pool = NSAutoreleasePool::new(nil);
+
+        let rtf_type = NSString::alloc(nil).init_str("public.rtf");
+        let pasteboard = NSPasteboard::generalPasteboard(nil);
+        let value: id = pasteboard.stringForType(rtf_type);
+        let rtf = if value == nil {
+            None
+        } else {
+            let c_str: *const i8 = msg_send![value, UTF8String];
+            (!c_str.is_null()).then(|| {
+                std::ffi::CStr::from_ptr(c_str)
+                    .to_string_lossy()
+                    .to_string()
+            })
+        };
+
+        let _: () = msg_send![rtf_type, release];
+        pool.drain();
+
+        rtf.filter(|rtf| !rtf.trim().is_empty())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_rtf() -> Option<String> {
+    None
+}
+
+/// 通过 NSPasteboard 写入 HTML 和 RTF，同时写入纯文本供不支持富文本的应用粘贴
+#[cfg(target_os = "macos")]
+pub fn write(rich_text: &RichText, plain_text: &str) -> Result<()> {
+    use cocoa::appkit::NSPasteboard;
+    use cocoa::base::{id, nil, NO};
+    use cocoa::foundation::{NSArray, NSAutoreleasePool, NSString};
+    use objc::{msg_send, sel, sel_impl};
+
+    unsafe {
+        let pool = NSAutoreleasePool::new(nil);
+
+        let mut values: Vec<(id, &str)> = Vec::new();
+        if let Some(html) = &rich_text.html {
+            values.push((NSString::alloc(nil).init_str("public.html"), html));
+        }
+        if let Some(rtf) = &rich_text.rtf {
+            values.push((NSString::alloc(nil).init_str("public.rtf"), rtf));
+        }
+        values.push((
+            NSString::alloc(nil).init_str("public.utf8-plain-text"),
+            plain_text,
+        ));
+
+        let types: Vec<id> = values.iter().map(|(data_type, _)| *data_type).collect();
+        let pasteboard = NSPasteboard::generalPasteboard(nil);
+        pasteboard.declareTypes_owner(NSArray::arrayWithObjects(nil, &types), nil);
+
+        let mut success = true;
+        for (data_type, value) in &values {
+            let string = NSString::alloc(nil).init_str(value);
+            success &= pasteboard.setString_forType(string, *data_type) != NO;
+            let _: () = msg_send![string, release];
+            let _: () = msg_send![*data_type, release];
+        }
+        pool.drain();
+
+        if !success {
+            return Err(anyhow::anyhow!("Failed to write rich text to pasteboard"));
+        }
+    }
+
+    Ok(())
+}
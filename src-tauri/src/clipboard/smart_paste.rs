@@ -0,0 +1,129 @@
+use crate::clipboard::color::{self, ColorFormat};
+use crate::clipboard::content_detector::{ContentDetector, ContentSubType};
+use crate::clipboard::transform::Transform;
+use crate::error::CommandError;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 粘贴时间戳时使用的格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampFormat {
+    Iso8601,
+    UnixSeconds,
+    UnixMillis,
+}
+
+/// smart_paste 对某个子类型应用的转换，配置中直接写格式或转换的名称，如 "hex"、"iso8601"、"uppercase"
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum SmartPasteTransform {
+    Color(ColorFormat),
+    Timestamp(TimestampFormat),
+    Text(Transform),
+}
+
+impl SmartPasteTransform {
+    /// 颜色和时间戳格式只能用于对应子类型的条目，文本转换可用于任意子类型
+    pub fn supports(&self, subtype: ContentSubType) -> bool {
+        match self {
+            SmartPasteTransform::Color(_) => subtype == ContentSubType::Color,
+            SmartPasteTransform::Timestamp(_) => subtype == ContentSubType::Timestamp,
+            SmartPasteTransform::Text(_) => true,
+        }
+    }
+
+    pub fn apply(&self, content: &str) -> Result<String> {
+        match self {
+            SmartPasteTransform::Color(format) => color::convert(content, *format),
+            SmartPasteTransform::Timestamp(format) => format_timestamp(content, *format),
+            SmartPasteTransform::Text(transform) => transform.apply(content),
+        }
+    }
+}
+
+/// 把 Unix 时间戳（秒或毫秒）或 ISO 8601 时间转换为指定格式，ISO 8601 输出为 UTC
+pub fn format_timestamp(text: &str, format: TimestampFormat) -> Result<String> {
+    let time = parse_timestamp(text.trim()).ok_or_else(|| {
+        CommandError::invalid_input(format!("Invalid timestamp: {}", text.trim()))
+    })?;
+    Ok(match format {
+        TimestampFormat::Iso8601 => time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        TimestampFormat::UnixSeconds => time.timestamp().to_string(),
+        TimestampFormat::UnixMillis => time.timestamp_millis().to_string(),
+    })
+}
+
+/// 与子类型检测使用同样的规则识别时间戳，没有时区的 ISO 8601 时间按 UTC 处理
+fn parse_timestamp(text: &str) -> Option<DateTime<Utc>> {
+    let (_, metadata) = ContentDetector::detect(text);
+    let formats = metadata?.timestamp_formats?;
+    if let Some(unix_ms) = formats.unix_ms {
+        return DateTime::from_timestamp_millis(unix_ms);
+    }
+    let iso = formats.iso8601?;
+    DateTime::parse_from_rfc3339(&iso)
+        .map(|time| time.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(&iso, "%Y-%m-%dT%H:%M:%S%.f").map(|time| time.and_utc())
+        })
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transforms_parse_from_config_names() {
+        let parse = |name: &str| serde_json::from_value::<SmartPasteTransform>(name.into());
+        assert_eq!(
+            parse("hex").unwrap(),
+            SmartPasteTransform::Color(ColorFormat::Hex)
+        );
+        assert_eq!(
+            parse("iso8601").unwrap(),
+            SmartPasteTransform::Timestamp(TimestampFormat::Iso8601)
+        );
+        assert_eq!(
+            parse("uppercase").unwrap(),
+            SmartPasteTransform::Text(Transform::Uppercase)
+        );
+        assert!(parse("rot13").is_err());
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        let iso = |text| format_timestamp(text, TimestampFormat::Iso8601).unwrap();
+        assert_eq!(iso("1700000000"), "2023-11-14T22:13:20Z");
+        assert_eq!(iso("1700000000123"), "2023-11-14T22:13:20.123Z");
+        assert_eq!(iso("2023-11-15T06:13:20+08:00"), "2023-11-14T22:13:20Z");
+        assert_eq!(iso("2023-11-14T22:13:20"), "2023-11-14T22:13:20Z");
+
+        assert_eq!(
+            format_timestamp("2023-11-14T22:13:20Z", TimestampFormat::UnixSeconds).unwrap(),
+            "1700000000"
+        );
+        assert_eq!(
+            format_timestamp("1700000000", TimestampFormat::UnixMillis).unwrap(),
+            "1700000000000"
+        );
+        assert!(format_timestamp("not a time", TimestampFormat::Iso8601).is_err());
+    }
+
+    #[test]
+    fn test_color_and_timestamp_transforms_only_support_their_subtype() {
+        let hex = SmartPasteTransform::Color(ColorFormat::Hex);
+        assert!(hex.supports(ContentSubType::Color));
+        assert!(!hex.supports(ContentSubType::Timestamp));
+        assert_eq!(hex.apply("rgb(255, 128, 0)").unwrap(), "#ff8000");
+
+        let iso = SmartPasteTransform::Timestamp(TimestampFormat::Iso8601);
+        assert!(iso.supports(ContentSubType::Timestamp));
+        assert!(!iso.supports(ContentSubType::PlainText));
+
+        let trim = SmartPasteTransform::Text(Transform::Trim);
+        assert!(trim.supports(ContentSubType::Url));
+    }
+}
@@ -0,0 +1,503 @@
+use crate::error::CommandError;
+use anyhow::Result;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Transform {
+    Uppercase,
+    Lowercase,
+    Trim,
+    JsonPretty,
+    JsonMinify,
+    Base64Encode,
+    Base64Decode,
+    UrlDecode,
+    HtmlDecode,
+}
+
+impl Transform {
+    /// 从前端传入的 snake_case 名称解析转换类型
+    pub fn parse(value: &str) -> Option<Self> {
+        serde_json::from_value(Value::String(value.to_string())).ok()
+    }
+
+    pub fn apply(&self, content: &str) -> Result<String> {
+        match self {
+            Transform::Uppercase => Ok(content.to_uppercase()),
+            Transform::Lowercase => Ok(content.to_lowercase()),
+            Transform::Trim => Ok(content.trim().to_string()),
+            Transform::JsonPretty => {
+                let value = Self::parse_json(content)?;
+                Ok(serde_json::to_string_pretty(&value)?)
+            }
+            Transform::JsonMinify => {
+                let value = Self::parse_json(content)?;
+                Ok(serde_json::to_string(&value)?)
+            }
+            Transform::Base64Encode => Ok(general_purpose::STANDARD.encode(content.as_bytes())),
+            Transform::Base64Decode => {
+                let cleaned: String = content.chars().filter(|c| !c.is_whitespace()).collect();
+                let decoded = general_purpose::STANDARD
+                    .decode(cleaned)
+                    .map_err(|e| CommandError::invalid_input(format!("Invalid base64: {}", e)))?;
+                String::from_utf8(decoded).map_err(|_| {
+                    CommandError::invalid_input("Decoded base64 is not valid UTF-8 text").into()
+                })
+            }
+            Transform::UrlDecode => percent_decode(content.trim())
+                .ok_or_else(|| CommandError::invalid_input("Invalid percent-encoded text").into()),
+            Transform::HtmlDecode => Ok(decode_html_entities(content).0),
+        }
+    }
+
+    fn parse_json(content: &str) -> Result<Value> {
+        serde_json::from_str(content.trim())
+            .map_err(|e| CommandError::invalid_input(format!("Invalid JSON: {}", e)).into())
+    }
+}
+
+/// 解码 %XX 转义（+ 保持原样）。转义不完整或解码结果不是 UTF-8 时返回 None
+pub fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = text
+                .get(i + 1..i + 3)
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// 支持解码的 HTML 命名实体
+const HTML_ENTITIES: [(&str, char); 30] = [
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{a0}'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("hellip", '…'),
+    ("mdash", '—'),
+    ("ndash", '–'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+    ("laquo", '«'),
+    ("raquo", '»'),
+    ("middot", '·'),
+    ("bull", '•'),
+    ("deg", '°'),
+    ("times", '×'),
+    ("divide", '÷'),
+    ("plusmn", '±'),
+    ("euro", '€'),
+    ("pound", '£'),
+    ("yen", '¥'),
+    ("cent", '¢'),
+    ("sect", '§'),
+    ("para", '¶'),
+];
+
+/// 实体名（不含 & 和 ;）的最大长度
+const MAX_HTML_ENTITY_LEN: usize = 10;
+
+/// 解码命名实体和 &#NN; / &#xHH; 数字实体，无法识别的 & 原样保留。
+/// 只解码一层：&amp;lt; 得到 &lt;。返回解码结果和解码的实体个数
+pub fn decode_html_entities(text: &str) -> (String, usize) {
+    let mut decoded = String::with_capacity(text.len());
+    let mut count = 0;
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match parse_html_entity(rest) {
+            Some((c, len)) => {
+                decoded.push(c);
+                rest = &rest[len..];
+                count += 1;
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    (decoded, count)
+}
+
+/// 解析以 & 开头的实体，返回对应字符和实体占用的字节数
+fn parse_html_entity(text: &str) -> Option<(char, usize)> {
+    let end = text[1..]
+        .find(';')
+        .filter(|&end| end <= MAX_HTML_ENTITY_LEN)?;
+    let name = &text[1..end + 1];
+    let c = if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) if hex.bytes().all(|b| b.is_ascii_hexdigit()) => {
+                u32::from_str_radix(hex, 16).ok()?
+            }
+            None if number.bytes().all(|b| b.is_ascii_digit()) => number.parse().ok()?,
+            _ => return None,
+        };
+        char::from_u32(code)?
+    } else {
+        HTML_ENTITIES.iter().find(|(entity, _)| *entity == name)?.1
+    };
+    Some((c, end + 2))
+}
+
+/// 移除 URL 中的跟踪参数。参数名不区分大小写，以 * 结尾的表示前缀匹配（如 utm_*）。
+/// 没有需要移除的参数时原样返回
+pub fn clean_url(url: &str, tracking_params: &[String]) -> Result<String> {
+    let url = url.trim();
+    let mut parsed = url::Url::parse(url)
+        .map_err(|e| CommandError::invalid_input(format!("Invalid URL: {}", e)))?;
+
+    let is_tracking = |key: &str| {
+        let key = key.to_ascii_lowercase();
+        tracking_params.iter().any(|param| {
+            let param = param.to_ascii_lowercase();
+            match param.strip_suffix('*') {
+                Some(prefix) => key.starts_with(prefix),
+                None => key == param,
+            }
+        })
+    };
+
+    let pairs: Vec<(String, String)> = parsed.query_pairs().into_owned().collect();
+    let kept: Vec<_> = pairs.iter().filter(|(key, _)| !is_tracking(key)).collect();
+    if kept.len() == pairs.len() {
+        return Ok(url.to_string());
+    }
+
+    if kept.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    Ok(parsed.into())
+}
+
+/// 生成 Markdown 链接 [text](url)。链接文本合并空白并转义方括号，URL 中的空格和括号按百分号编码
+pub fn markdown_link(text: &str, url: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let text = text
+        .replace('\\', "\\\\")
+        .replace('[', "\\[")
+        .replace(']', "\\]");
+    let url = url
+        .trim()
+        .replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29");
+    format!("[{}]({})", text, url)
+}
+
+/// 按正则表达式替换文本，replacement 中可用 $1、${name} 引用捕获组。
+/// flags 为标志字符组合：i 不区分大小写，m 多行模式（^、$ 匹配每行首尾），g 替换全部匹配（否则只替换第一个）
+pub fn regex_replace(text: &str, pattern: &str, replacement: &str, flags: &str) -> Result<String> {
+    let mut builder = regex::RegexBuilder::new(pattern);
+    let mut global = false;
+    for flag in flags.chars() {
+        match flag {
+            'i' => {
+                builder.case_insensitive(true);
+            }
+            'm' => {
+                builder.multi_line(true);
+            }
+            'g' => global = true,
+            _ => {
+                return Err(
+                    CommandError::invalid_input(format!("Unknown regex flag: {}", flag)).into(),
+                )
+            }
+        }
+    }
+    let regex = builder
+        .build()
+        .map_err(|e| CommandError::invalid_input(format!("Invalid regex pattern: {}", e)))?;
+
+    let replaced = if global {
+        regex.replace_all(text, replacement)
+    } else {
+        regex.replace(text, replacement)
+    };
+    Ok(replaced.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    #[test]
+    fn test_parse_transform_names() {
+        assert_eq!(Transform::parse("uppercase"), Some(Transform::Uppercase));
+        assert_eq!(Transform::parse("json_pretty"), Some(Transform::JsonPretty));
+        assert_eq!(
+            Transform::parse("base64_decode"),
+            Some(Transform::Base64Decode)
+        );
+        assert_eq!(Transform::parse("Uppercase"), None);
+        assert_eq!(Transform::parse("rot13"), None);
+    }
+
+    #[test]
+    fn test_case_transforms() {
+        assert_eq!(
+            Transform::Uppercase.apply("Hello, World").unwrap(),
+            "HELLO, WORLD"
+        );
+        assert_eq!(
+            Transform::Lowercase.apply("Hello, World").unwrap(),
+            "hello, world"
+        );
+        assert_eq!(Transform::Uppercase.apply("straße").unwrap(), "STRASSE");
+        assert_eq!(Transform::Lowercase.apply("").unwrap(), "");
+    }
+
+    #[test]
+    fn test_trim_transform() {
+        assert_eq!(Transform::Trim.apply("  padded\n\t").unwrap(), "padded");
+        assert_eq!(Transform::Trim.apply("   ").unwrap(), "");
+    }
+
+    #[test]
+    fn test_json_pretty_transform() {
+        let pretty = Transform::JsonPretty.apply(r#"{"a":1,"b":[1,2]}"#).unwrap();
+        assert_eq!(pretty, "{\n  \"a\": 1,\n  \"b\": [\n    1,\n    2\n  ]\n}");
+    }
+
+    #[test]
+    fn test_json_minify_transform() {
+        let minified = Transform::JsonMinify
+            .apply("{\n  \"a\": 1,\n  \"b\": [1, 2]\n}\n")
+            .unwrap();
+        assert_eq!(minified, r#"{"a":1,"b":[1,2]}"#);
+    }
+
+    #[test]
+    fn test_json_transforms_reject_invalid_json() {
+        for invalid in ["{\"a\": }", "not json", "", "{'a': 1}"] {
+            let result = Transform::JsonPretty.apply(invalid);
+            assert!(result.is_err(), "expected error for {:?}", invalid);
+            assert!(result.unwrap_err().to_string().contains("Invalid JSON"));
+            assert!(Transform::JsonMinify.apply(invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn test_base64_round_trip() {
+        let encoded = Transform::Base64Encode.apply("Hello, 世界").unwrap();
+        assert_eq!(encoded, "SGVsbG8sIOS4lueVjA==");
+        assert_eq!(
+            Transform::Base64Decode.apply(&encoded).unwrap(),
+            "Hello, 世界"
+        );
+    }
+
+    #[test]
+    fn test_base64_decode_ignores_whitespace() {
+        assert_eq!(
+            Transform::Base64Decode.apply("SGVs\nbG8=\n").unwrap(),
+            "Hello"
+        );
+    }
+
+    #[test]
+    fn test_base64_decode_failures() {
+        // Not base64 at all
+        assert!(Transform::Base64Decode.apply("not base64!").is_err());
+        // Valid base64 but binary (not UTF-8) payload
+        assert!(Transform::Base64Decode.apply("/w==").is_err());
+    }
+
+    #[test]
+    fn test_url_decode_transform() {
+        assert_eq!(
+            Transform::UrlDecode.apply("%2Fpath%3Fq%3D1").unwrap(),
+            "/path?q=1"
+        );
+        assert_eq!(
+            Transform::UrlDecode
+                .apply(" %E4%B8%96%E7%95%8C+a ")
+                .unwrap(),
+            "世界+a"
+        );
+        assert_eq!(Transform::parse("url_decode"), Some(Transform::UrlDecode));
+
+        // Incomplete escapes and non-UTF-8 bytes are rejected
+        for invalid in ["100%", "%2", "%zz", "%+1", "%FF%FE"] {
+            assert!(Transform::UrlDecode.apply(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_decode_html_entities() {
+        assert_eq!(
+            decode_html_entities("&lt;div&gt;&amp;nbsp;&lt;/div&gt;"),
+            ("<div>&nbsp;</div>".to_string(), 5)
+        );
+        assert_eq!(
+            decode_html_entities("&#39;quoted&#x27; &copy; 2024"),
+            ("'quoted' © 2024".to_string(), 3)
+        );
+        // Lone ampersands and unknown or malformed entities are kept as-is
+        assert_eq!(
+            decode_html_entities("Tom & Jerry &unknown; &#xZZ; &#; &"),
+            ("Tom & Jerry &unknown; &#xZZ; &#; &".to_string(), 0)
+        );
+        assert_eq!(
+            Transform::HtmlDecode.apply("a &amp;&amp; b").unwrap(),
+            "a && b"
+        );
+        assert_eq!(Transform::parse("html_decode"), Some(Transform::HtmlDecode));
+    }
+
+    fn tracking_params() -> Vec<String> {
+        ["utm_*", "fbclid", "gclid"]
+            .iter()
+            .map(|param| param.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_url_removes_tracking_params() {
+        let params = tracking_params();
+        assert_eq!(
+            clean_url(
+                "https://example.com/post?utm_source=news&utm_medium=email&fbclid=abc",
+                &params
+            )
+            .unwrap(),
+            "https://example.com/post"
+        );
+        // Matching is case-insensitive and keeps the fragment
+        assert_eq!(
+            clean_url(
+                "https://example.com/?UTM_Campaign=x&GCLID=y#section",
+                &params
+            )
+            .unwrap(),
+            "https://example.com/#section"
+        );
+    }
+
+    #[test]
+    fn test_markdown_link_escapes_text_and_url() {
+        assert_eq!(
+            markdown_link("Dance Clipboard", "https://example.com/docs"),
+            "[Dance Clipboard](https://example.com/docs)"
+        );
+        assert_eq!(
+            markdown_link("  [Draft]\n  Release notes ", "https://example.com/a (1)"),
+            "[\\[Draft\\] Release notes](https://example.com/a%20%281%29)"
+        );
+    }
+
+    #[test]
+    fn test_regex_replace_strips_line_numbers() {
+        let listing = "1: fn main() {\n2:     run();\n3: }";
+        assert_eq!(
+            regex_replace(listing, r"^\d+:\s?", "", "gm").unwrap(),
+            "fn main() {\n    run();\n}"
+        );
+        assert_eq!(
+            regex_replace("2024-01-31", r"(\d+)-(\d+)-(\d+)", "$3/$2/$1", "").unwrap(),
+            "31/01/2024"
+        );
+        // No match leaves the text unchanged
+        assert_eq!(regex_replace("hello", "xyz", "_", "g").unwrap(), "hello");
+    }
+
+    #[test]
+    fn test_regex_replace_flags() {
+        // Without g only the first match is replaced
+        assert_eq!(regex_replace("a-b-c", "-", "+", "").unwrap(), "a+b-c");
+        assert_eq!(regex_replace("a-b-c", "-", "+", "g").unwrap(), "a+b+c");
+
+        assert_eq!(
+            regex_replace("Foo foo", "foo", "bar", "g").unwrap(),
+            "Foo bar"
+        );
+        assert_eq!(
+            regex_replace("Foo foo", "foo", "bar", "gi").unwrap(),
+            "bar bar"
+        );
+        assert_eq!(
+            regex_replace("Foo foo", "foo", "bar", "i").unwrap(),
+            "bar foo"
+        );
+
+        // Without m, ^ only anchors at the start of the text
+        assert_eq!(regex_replace("x\nx", "^x", "y", "g").unwrap(), "y\nx");
+        assert_eq!(regex_replace("x\nx", "^x", "y", "gm").unwrap(), "y\ny");
+    }
+
+    #[test]
+    fn test_regex_replace_reports_invalid_input() {
+        let error = CommandError::from(regex_replace("text", "(unclosed", "", "g").unwrap_err());
+        assert_eq!(error.code, ErrorCode::InvalidInput);
+        assert!(
+            error.message.starts_with("Invalid regex pattern"),
+            "{}",
+            error
+        );
+
+        let error = CommandError::from(regex_replace("text", "t", "", "gx").unwrap_err());
+        assert_eq!(error.code, ErrorCode::InvalidInput);
+        assert_eq!(error.message, "Unknown regex flag: x");
+    }
+
+    #[test]
+    fn test_clean_url_preserves_other_params() {
+        let params = tracking_params();
+        assert_eq!(
+            clean_url(
+                "https://shop.example.com/item?id=42&utm_source=ad&color=red",
+                &params
+            )
+            .unwrap(),
+            "https://shop.example.com/item?id=42&color=red"
+        );
+
+        // Untouched URLs are returned verbatim, including their original encoding
+        let url = "https://example.com/search?q=a%20b&page=2";
+        assert_eq!(clean_url(url, &params).unwrap(), url);
+        assert_eq!(
+            clean_url("  https://example.com/  ", &params).unwrap(),
+            "https://example.com/"
+        );
+
+        // `utm` alone is not matched by the `utm_*` prefix
+        assert_eq!(
+            clean_url("https://example.com/?utm=1", &params).unwrap(),
+            "https://example.com/?utm=1"
+        );
+    }
+
+    #[test]
+    fn test_clean_url_rejects_malformed_urls() {
+        let params = tracking_params();
+        assert!(clean_url("not a url", &params).is_err());
+        assert!(clean_url("example.com/?utm_source=x", &params).is_err());
+        assert!(clean_url("", &params).is_err());
+    }
+}
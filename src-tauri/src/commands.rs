@@ -1,12 +1,26 @@
+use crate::clipboard::content_detector::{ContentDetector, ContentEntropy, JsonValidation};
+use crate::clipboard::ClipboardMonitor;
 use crate::config::AppConfig;
-use crate::models::{ClipboardEntry, Statistics};
+use crate::database::backup::{BackupInfo, RestoreResult};
+use crate::error::{CommandError, ErrorCode};
+use crate::logging::{self, LogLevel};
+use crate::models::{
+    ActivityBucket, ActivitySummary, ClipboardEntry, HistoryFilter, HistoryPage, Statistics,
+};
+use crate::shortcuts::{ShortcutAction, ShortcutValidation};
 use crate::state::AppState;
-use crate::updater::{UpdateInfo, UpdateManager};
+use crate::sync_bundle::{SyncExportResult, SyncImportResult};
+use crate::updater::{Changelog, UpdateError, UpdateInfo, UpdateManager};
+use crate::url_preview::UrlPreview;
 use crate::utils::app_icon_extractor::AppIconExtractor;
 use crate::utils::app_list::{AppListManager, InstalledApp};
+use crate::utils::media_metadata::MediaCacheStats;
+use crate::utils::system_open;
+use crate::utils::window_state::WindowState;
 use anyhow::Result;
 use base64::{engine::general_purpose, Engine as _};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use tauri::{State, Window};
 use tauri_plugin_aptabase::EventTracker;
 
@@ -17,6 +31,8 @@ pub struct CacheStatistics {
     pub total_entries: i64,
     pub text_entries: i64,
     pub image_entries: i64,
+    pub subtype_counts: Vec<(String, i64)>, // Sorted by count descending; NULL subtypes count as "unknown"
+    pub media_metadata: MediaCacheStats,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,12 +42,18 @@ pub struct CleanupResult {
     pub size_freed_bytes: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactResult {
+    pub db_size_before_bytes: u64,
+    pub db_size_after_bytes: u64,
+}
+
 #[tauri::command]
 pub async fn start_monitoring(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let result = state.start_monitoring().await.map_err(|e| e.to_string());
+) -> Result<(), CommandError> {
+    let result = state.start_monitoring().await.map_err(CommandError::from);
     if result.is_ok() {
         let app_handle = app.clone();
         tokio::spawn(async move {
@@ -41,12 +63,36 @@ pub async fn start_monitoring(
     result
 }
 
+#[tauri::command]
+pub async fn pause_monitoring(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    minutes: u32,
+) -> Result<(), CommandError> {
+    let result = state
+        .pause_monitoring(minutes)
+        .await
+        .map_err(CommandError::from);
+    if result.is_ok() {
+        let app_handle = app.clone();
+        tokio::spawn(async move {
+            let _ = app_handle.track_event("monitoring_paused", None);
+        });
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn is_monitoring(state: State<'_, AppState>) -> Result<bool, CommandError> {
+    Ok(state.is_monitoring().await)
+}
+
 #[tauri::command]
 pub async fn stop_monitoring(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let result = state.stop_monitoring().await.map_err(|e| e.to_string());
+) -> Result<(), CommandError> {
+    let result = state.stop_monitoring().await.map_err(CommandError::from);
     if result.is_ok() {
         let app_handle = app.clone();
         tokio::spawn(async move {
@@ -57,16 +103,121 @@ pub async fn stop_monitoring(
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn get_clipboard_history(
     state: State<'_, AppState>,
     limit: Option<i32>,
     offset: Option<i32>,
     search: Option<String>,
-) -> Result<Vec<ClipboardEntry>, String> {
+    subtype: Option<String>,
+    start_ms: Option<i64>,
+    end_ms: Option<i64>,
+    source_app: Option<String>,
+    search_metadata: Option<bool>,
+    favorites_only: Option<bool>,
+    preview_only: Option<bool>,
+) -> Result<Vec<ClipboardEntry>, CommandError> {
+    let filter = HistoryFilter {
+        subtype,
+        start_ms,
+        end_ms,
+        source_app,
+        search_metadata: search_metadata.unwrap_or(false),
+        favorites_only: favorites_only.unwrap_or(false),
+        preview_only: preview_only.unwrap_or(false),
+    };
     state
-        .get_clipboard_history(limit, offset, search)
+        .get_clipboard_history(limit, offset, search, filter)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_history_page(
+    state: State<'_, AppState>,
+    limit: Option<i32>,
+    offset: Option<i32>,
+    search: Option<String>,
+    subtype: Option<String>,
+    start_ms: Option<i64>,
+    end_ms: Option<i64>,
+    source_app: Option<String>,
+    search_metadata: Option<bool>,
+    favorites_only: Option<bool>,
+    preview_only: Option<bool>,
+) -> Result<HistoryPage, CommandError> {
+    let filter = HistoryFilter {
+        subtype,
+        start_ms,
+        end_ms,
+        source_app,
+        search_metadata: search_metadata.unwrap_or(false),
+        favorites_only: favorites_only.unwrap_or(false),
+        preview_only: preview_only.unwrap_or(false),
+    };
+    state
+        .get_history_page(limit, offset, search, filter)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn get_favorites(
+    state: State<'_, AppState>,
+    limit: Option<i32>,
+    offset: Option<i32>,
+    search: Option<String>,
+) -> Result<Vec<ClipboardEntry>, CommandError> {
+    let filter = HistoryFilter {
+        favorites_only: true,
+        ..Default::default()
+    };
+    state
+        .get_clipboard_history(limit, offset, search, filter)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn get_entries_by_app(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<Vec<ClipboardEntry>, CommandError> {
+    state
+        .get_entries_by_app(app)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn get_entries_since(
+    state: State<'_, AppState>,
+    timestamp_ms: i64,
+) -> Result<Vec<ClipboardEntry>, CommandError> {
+    state
+        .get_entries_since(timestamp_ms)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn get_entry(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Option<ClipboardEntry>, CommandError> {
+    state.get_entry(id).await.map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn get_entry_content(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Option<String>, CommandError> {
+    state
+        .get_entry_content(id)
+        .await
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -74,8 +225,8 @@ pub async fn toggle_favorite(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     id: String,
-) -> Result<(), String> {
-    let result = state.toggle_favorite(id).await.map_err(|e| e.to_string());
+) -> Result<(), CommandError> {
+    let result = state.toggle_favorite(id).await.map_err(CommandError::from);
     if result.is_ok() {
         let app_handle = app.clone();
         tokio::spawn(async move {
@@ -90,8 +241,8 @@ pub async fn delete_entry(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     id: String,
-) -> Result<(), String> {
-    let result = state.delete_entry(id).await.map_err(|e| e.to_string());
+) -> Result<(), CommandError> {
+    let result = state.delete_entry(id).await.map_err(CommandError::from);
     if result.is_ok() {
         let app_handle = app.clone();
         tokio::spawn(async move {
@@ -101,12 +252,49 @@ pub async fn delete_entry(
     result
 }
 
+#[tauri::command]
+pub async fn delete_entries(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+) -> Result<u32, CommandError> {
+    let result = state.delete_entries(ids).await.map_err(CommandError::from);
+    if result.is_ok() {
+        let app_handle = app.clone();
+        tokio::spawn(async move {
+            let _ = app_handle.track_event("entries_deleted", None);
+        });
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn merge_entries(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    primary_id: String,
+    other_ids: Vec<String>,
+    separator: Option<String>,
+) -> Result<ClipboardEntry, CommandError> {
+    let result = state
+        .merge_entries(primary_id, other_ids, separator)
+        .await
+        .map_err(CommandError::from);
+    if result.is_ok() {
+        let app_handle = app.clone();
+        tokio::spawn(async move {
+            let _ = app_handle.track_event("entries_merged", None);
+        });
+    }
+    result
+}
+
 #[tauri::command]
 pub async fn clear_history(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let result = state.clear_history().await.map_err(|e| e.to_string());
+) -> Result<(), CommandError> {
+    let result = state.clear_history().await.map_err(CommandError::from);
     if result.is_ok() {
         let app_handle = app.clone();
         tokio::spawn(async move {
@@ -117,8 +305,54 @@ pub async fn clear_history(
 }
 
 #[tauri::command]
-pub async fn get_statistics(state: State<'_, AppState>) -> Result<Statistics, String> {
-    state.get_statistics().await.map_err(|e| e.to_string())
+pub async fn clear_history_before(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    timestamp_ms: i64,
+) -> Result<u32, CommandError> {
+    let result = state
+        .clear_history_before(timestamp_ms)
+        .await
+        .map_err(CommandError::from);
+    if result.is_ok() {
+        let app_handle = app.clone();
+        tokio::spawn(async move {
+            let _ = app_handle.track_event("history_cleared_before", None);
+        });
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn get_statistics(
+    state: State<'_, AppState>,
+    since_ms: Option<i64>,
+) -> Result<Statistics, CommandError> {
+    state
+        .get_statistics(since_ms)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn get_activity_summary(
+    state: State<'_, AppState>,
+) -> Result<ActivitySummary, CommandError> {
+    state
+        .get_activity_summary()
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn get_activity_histogram(
+    state: State<'_, AppState>,
+    bucket: String,
+) -> Result<Vec<ActivityBucket>, CommandError> {
+    state
+        .get_activity_histogram(bucket)
+        .await
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -126,11 +360,30 @@ pub async fn copy_to_clipboard(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     content: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let result = state
         .copy_to_clipboard(content)
         .await
-        .map_err(|e| e.to_string());
+        .map_err(CommandError::from);
+    if result.is_ok() {
+        let app_handle = app.clone();
+        tokio::spawn(async move {
+            let _ = app_handle.track_event("item_copied", None);
+        });
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn copy_with_formatting(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), CommandError> {
+    let result = state
+        .copy_with_formatting(id)
+        .await
+        .map_err(CommandError::from);
     if result.is_ok() {
         let app_handle = app.clone();
         tokio::spawn(async move {
@@ -145,11 +398,129 @@ pub async fn paste_text(
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
     content: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     state
         .paste_text(content, Some(app_handle))
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn paste_recent(state: State<'_, AppState>, index: u32) -> Result<(), CommandError> {
+    state.paste_recent(index).await.map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn paste_plain_text(
+    state: State<'_, AppState>,
+    content: String,
+) -> Result<(), CommandError> {
+    state
+        .paste_plain_text(content)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn smart_paste(state: State<'_, AppState>, id: String) -> Result<String, CommandError> {
+    state.smart_paste(id).await.map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn transform_and_copy(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id_or_content: String,
+    transform: String,
+    record: Option<bool>,
+) -> Result<String, CommandError> {
+    let result = state
+        .transform_and_copy(id_or_content, transform, record.unwrap_or(false))
+        .await
+        .map_err(CommandError::from);
+    if result.is_ok() {
+        let app_handle = app.clone();
+        tokio::spawn(async move {
+            let _ = app_handle.track_event("content_transformed", None);
+        });
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn clean_url(
+    state: State<'_, AppState>,
+    url: String,
+    copy: Option<bool>,
+) -> Result<String, CommandError> {
+    state
+        .clean_url(url, copy.unwrap_or(false))
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn copy_as_markdown_link(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<String, CommandError> {
+    let result = state
+        .copy_as_markdown_link(id)
+        .await
+        .map_err(CommandError::from);
+    if result.is_ok() {
+        let app_handle = app.clone();
+        tokio::spawn(async move {
+            let _ = app_handle.track_event("markdown_link_copied", None);
+        });
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn regex_replace_and_copy(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    content: String,
+    pattern: String,
+    replacement: String,
+    flags: Option<String>,
+) -> Result<String, CommandError> {
+    let result = state
+        .regex_replace_and_copy(content, pattern, replacement, flags.unwrap_or_default())
+        .await
+        .map_err(CommandError::from);
+    if result.is_ok() {
+        let app_handle = app.clone();
+        tokio::spawn(async move {
+            let _ = app_handle.track_event("regex_replaced", None);
+        });
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn convert_color(
+    state: State<'_, AppState>,
+    value: String,
+    target: String,
+    copy: Option<bool>,
+) -> Result<String, CommandError> {
+    state
+        .convert_color(value, target, copy.unwrap_or(false))
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn content_entropy(content: String) -> Result<ContentEntropy, CommandError> {
+    Ok(ContentDetector::content_entropy(&content))
+}
+
+#[tauri::command]
+pub async fn validate_json(content: String) -> Result<JsonValidation, CommandError> {
+    Ok(ContentDetector::validate_json(&content))
 }
 
 #[tauri::command]
@@ -157,69 +528,92 @@ pub async fn paste_image(
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
     file_path: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     state
         .paste_image(file_path, Some(app_handle))
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn open_file_with_system(file_path: String) -> Result<(), String> {
-    use std::path::PathBuf;
-    use std::process::Command;
+pub async fn paste_image_as_file(
+    state: State<'_, AppState>,
+    file_path: String,
+) -> Result<(), CommandError> {
+    state
+        .paste_image_as_file(file_path)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn paste_file_list(state: State<'_, AppState>, id: String) -> Result<(), CommandError> {
+    state.paste_file_list(id).await.map_err(CommandError::from)
+}
 
+#[tauri::command]
+pub async fn open_file_with_system(file_path: String) -> Result<(), CommandError> {
     log::info!("[open_file_with_system] 打开文件: {}", file_path);
 
     // 如果是相对路径（如 imgs/xxx.png），转换为绝对路径
-    let absolute_path = if file_path.starts_with("imgs/") {
-        let config_dir =
-            dirs::config_dir().ok_or_else(|| "Unable to get config directory".to_string())?;
-        let app_dir = config_dir.join("clipboard-app");
-        app_dir.join(&file_path)
-    } else {
-        PathBuf::from(&file_path)
-    };
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| CommandError::not_found("Unable to get config directory"))?;
+    let absolute_path =
+        system_open::resolve_stored_path(&file_path, &config_dir.join("clipboard-app"));
 
     if !absolute_path.exists() {
-        return Err(format!("File not found: {:?}", absolute_path));
+        return Err(CommandError::not_found(format!(
+            "File not found: {:?}",
+            absolute_path
+        )));
     }
 
-    // 在 macOS 上使用 open 命令
-    #[cfg(target_os = "macos")]
-    {
-        let result = Command::new("open").arg(&absolute_path).spawn();
+    match system_open::open_with_system(&absolute_path) {
+        Ok(()) => {
+            log::info!("[open_file_with_system] 成功打开文件");
+            Ok(())
+        }
+        Err(e) => {
+            log::error!("[open_file_with_system] 打开文件失败: {}", e);
+            Err(CommandError::from(e))
+        }
+    }
+}
 
-        match result {
-            Ok(_) => {
-                log::info!("[open_file_with_system] 成功打开文件");
-                Ok(())
-            }
-            Err(e) => {
-                log::error!("[open_file_with_system] 打开文件失败: {}", e);
-                Err(format!("Failed to open file: {}", e))
-            }
+/// 按文件内容识别图片的 MIME 类型（图片可能按配置转为 WebP 或保留原格式），识别不出时按扩展名推断
+fn image_mime_type(path: &std::path::Path, data: &[u8]) -> &'static str {
+    if let Some(kind) = infer::get(data) {
+        if kind.matcher_type() == infer::MatcherType::Image {
+            return kind.mime_type();
         }
     }
 
-    #[cfg(not(target_os = "macos"))]
-    {
-        Err("This feature is only supported on macOS".to_string())
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("png");
+    match extension.to_lowercase().as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "tif" | "tiff" => "image/tiff",
+        _ => "image/png",
     }
 }
 
 #[tauri::command]
-pub async fn get_image_url(file_path: String) -> Result<String, String> {
+pub async fn get_image_url(file_path: String) -> Result<String, CommandError> {
     use base64::Engine;
     use std::fs;
     use std::path::PathBuf;
 
-    // println!("[get_image_url] 请求加载图片: {}", file_path);
+    log::debug!("[get_image_url] 请求加载图片: {}", file_path);
 
     // 如果是相对路径（如 imgs/xxx.png），转换为绝对路径
     let absolute_path = if file_path.starts_with("imgs/") {
-        let config_dir =
-            dirs::config_dir().ok_or_else(|| "Unable to get config directory".to_string())?;
+        let config_dir = dirs::config_dir()
+            .ok_or_else(|| CommandError::not_found("Unable to get config directory"))?;
         let app_dir = config_dir.join("clipboard-app");
 
         // 确保 imgs 目录存在
@@ -227,7 +621,10 @@ pub async fn get_image_url(file_path: String) -> Result<String, String> {
         if !imgs_dir.exists() {
             log::info!("[get_image_url] 创建 imgs 目录: {:?}", imgs_dir);
             if let Err(e) = fs::create_dir_all(&imgs_dir) {
-                return Err(format!("Failed to create imgs directory: {}", e));
+                return Err(CommandError::io(format!(
+                    "Failed to create imgs directory: {}",
+                    e
+                )));
             }
         }
 
@@ -236,7 +633,7 @@ pub async fn get_image_url(file_path: String) -> Result<String, String> {
         PathBuf::from(&file_path)
     };
 
-    // println!("[get_image_url] 绝对路径: {:?}", absolute_path);
+    log::debug!("[get_image_url] 绝对路径: {:?}", absolute_path);
 
     if !absolute_path.exists() {
         log::warn!("[get_image_url] 文件不存在: {:?}", absolute_path);
@@ -251,71 +648,62 @@ pub async fn get_image_url(file_path: String) -> Result<String, String> {
                 }
             }
         }
-        return Err(format!("File not found: {:?}", absolute_path));
+        return Err(CommandError::not_found(format!(
+            "File not found: {:?}",
+            absolute_path
+        )));
     }
 
     match fs::read(&absolute_path) {
         Ok(data) => {
-            // println!("[get_image_url] 成功读取文件，大小: {} 字节", data.len());
-
-            let extension = absolute_path
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .unwrap_or("png");
-
-            let mime_type = match extension.to_lowercase().as_str() {
-                "png" => "image/png",
-                "jpg" | "jpeg" => "image/jpeg",
-                "gif" => "image/gif",
-                "webp" => "image/webp",
-                "bin" => {
-                    // 对于 .bin 文件，尝试检测实际格式
-                    if data.len() >= 4 {
-                        if data.starts_with(&[0x89, 0x50, 0x4E, 0x47]) {
-                            "image/png"
-                        } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
-                            "image/jpeg"
-                        } else if data.starts_with(&[0x47, 0x49, 0x46, 0x38]) {
-                            "image/gif"
-                        } else if data.starts_with(&[0x52, 0x49, 0x46, 0x46])
-                            && data.len() >= 12
-                            && &data[8..12] == b"WEBP"
-                        {
-                            "image/webp"
-                        } else {
-                            "image/png" // 默认使用 PNG
-                        }
-                    } else {
-                        "image/png"
-                    }
-                }
-                _ => "image/png",
-            };
+            log::debug!("[get_image_url] 成功读取文件，大小: {} 字节", data.len());
 
-            // println!("[get_image_url] MIME 类型: {}", mime_type);
+            let mime_type = image_mime_type(&absolute_path, &data);
+
+            log::debug!("[get_image_url] MIME 类型: {}", mime_type);
 
             let base64_data = base64::engine::general_purpose::STANDARD.encode(&data);
             Ok(format!("data:{};base64,{}", mime_type, base64_data))
         }
         Err(e) => {
             log::error!("[get_image_url] 读取文件失败: {}", e);
-            Err(format!("Failed to read file: {}", e))
+            Err(CommandError::io(format!("Failed to read file: {}", e)))
         }
     }
 }
 
 #[tauri::command]
-pub async fn get_app_icon(bundle_id: String) -> Result<Option<String>, String> {
+pub async fn get_image_thumbnail(
+    state: State<'_, AppState>,
+    file_path: String,
+    max_dim: u32,
+) -> Result<String, CommandError> {
+    let thumb_path = state
+        .processor
+        .get_thumbnail(&file_path, max_dim)
+        .await
+        .map_err(CommandError::from)?;
+    let data = tokio::fs::read(&thumb_path)
+        .await
+        .map_err(|e| CommandError::io(format!("Failed to read thumbnail: {}", e)))?;
+    Ok(format!(
+        "data:image/png;base64,{}",
+        general_purpose::STANDARD.encode(&data)
+    ))
+}
+
+#[tauri::command]
+pub async fn get_app_icon(bundle_id: String) -> Result<Option<String>, CommandError> {
     use base64::Engine;
     use std::fs;
 
-    // println!("[get_app_icon] 请求应用图标: {}", bundle_id);
+    log::debug!("[get_app_icon] 请求应用图标: {}", bundle_id);
 
-    let extractor = AppIconExtractor::new().map_err(|e| e.to_string())?;
+    let extractor = AppIconExtractor::new().map_err(CommandError::from)?;
 
     // 首先检查缓存
     if let Some(cached_path) = extractor.get_cached_icon_path(&bundle_id) {
-        // println!("[get_app_icon] 找到缓存图标: {:?}", cached_path);
+        log::debug!("[get_app_icon] 找到缓存图标: {:?}", cached_path);
 
         match fs::read(&cached_path) {
             Ok(data) => {
@@ -362,7 +750,7 @@ pub async fn convert_and_scale_image(
     format: String,
     scale: f32,
     _skip_recording: bool,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     use image::DynamicImage;
     use std::fs;
     use std::path::PathBuf;
@@ -376,22 +764,26 @@ pub async fn convert_and_scale_image(
 
     // 转换为绝对路径
     let absolute_path = if file_path.starts_with("imgs/") {
-        let config_dir =
-            dirs::config_dir().ok_or_else(|| "Unable to get config directory".to_string())?;
+        let config_dir = dirs::config_dir()
+            .ok_or_else(|| CommandError::not_found("Unable to get config directory"))?;
         config_dir.join("clipboard-app").join(&file_path)
     } else {
         PathBuf::from(&file_path)
     };
 
     if !absolute_path.exists() {
-        return Err(format!("File not found: {:?}", absolute_path));
+        return Err(CommandError::not_found(format!(
+            "File not found: {:?}",
+            absolute_path
+        )));
     }
 
     // 读取原始图片
-    let img_data = fs::read(&absolute_path).map_err(|e| format!("Failed to read image: {}", e))?;
+    let img_data = fs::read(&absolute_path)
+        .map_err(|e| CommandError::io(format!("Failed to read image: {}", e)))?;
 
-    let img =
-        image::load_from_memory(&img_data).map_err(|e| format!("Failed to decode image: {}", e))?;
+    let img = image::load_from_memory(&img_data)
+        .map_err(|e| CommandError::invalid_input(format!("Failed to decode image: {}", e)))?;
 
     // 缩放图片
     let (width, height) = (img.width(), img.height());
@@ -422,7 +814,7 @@ pub async fn convert_and_scale_image(
                     &mut std::io::Cursor::new(&mut buffer),
                     image::ImageFormat::Jpeg,
                 )
-                .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+                .map_err(|e| CommandError::internal(format!("Failed to encode JPEG: {}", e)))?;
             "jpeg"
         }
         "webp" => {
@@ -432,7 +824,7 @@ pub async fn convert_and_scale_image(
                     &mut std::io::Cursor::new(&mut buffer),
                     image::ImageFormat::WebP,
                 )
-                .map_err(|e| format!("Failed to encode WebP: {}", e))?;
+                .map_err(|e| CommandError::internal(format!("Failed to encode WebP: {}", e)))?;
             "webp"
         }
         _ => {
@@ -442,7 +834,7 @@ pub async fn convert_and_scale_image(
                     &mut std::io::Cursor::new(&mut buffer),
                     image::ImageFormat::Png,
                 )
-                .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+                .map_err(|e| CommandError::internal(format!("Failed to encode PNG: {}", e)))?;
             "png"
         }
     };
@@ -465,53 +857,78 @@ pub async fn copy_converted_image(
     state: State<'_, AppState>,
     base64_data: String,
     _skip_recording: bool,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     log::info!("[copy_converted_image] 复制转换后的图片到剪贴板");
 
     // 解析base64数据
     let data_parts: Vec<&str> = base64_data.split(',').collect();
     if data_parts.len() != 2 {
-        return Err("Invalid base64 data format".to_string());
+        return Err(CommandError::invalid_input("Invalid base64 data format"));
     }
 
     let base64_content = data_parts[1];
     let image_data = general_purpose::STANDARD
         .decode(base64_content)
-        .map_err(|e| format!("Failed to decode base64: {}", e))?;
+        .map_err(|e| CommandError::invalid_input(format!("Failed to decode base64: {}", e)))?;
 
     // 将图片数据写入临时文件
     let temp_dir = std::env::temp_dir();
     let temp_file = temp_dir.join(format!("clipboard_temp_{}.png", uuid::Uuid::new_v4()));
 
     std::fs::write(&temp_file, &image_data)
-        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+        .map_err(|e| CommandError::io(format!("Failed to write temp file: {}", e)))?;
 
-    // 暂时禁用监控以避免记录
-    state.set_skip_next_clipboard_change(true).await;
+    // 记为应用自身的写入，避免监听器把转换后的图片记录为新条目
+    let hash = ClipboardMonitor::calculate_image_hash(&image_data);
+    if let Some(hash) = &hash {
+        state.remember_recent_write(hash.clone()).await;
+    }
 
     // 复制到剪贴板
     let result = state
         .copy_image_to_clipboard(temp_file.to_str().unwrap().to_string())
         .await;
+    if let (Err(_), Some(hash)) = (&result, &hash) {
+        state.forget_recent_write(hash).await;
+    }
 
     // 清理临时文件
     let _ = std::fs::remove_file(&temp_file);
 
-    result.map_err(|e| e.to_string())
+    result.map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn fetch_url_content(url: String) -> Result<String, String> {
+pub async fn copy_image_from_base64(
+    state: State<'_, AppState>,
+    data_url: String,
+) -> Result<(), CommandError> {
+    state
+        .copy_image_from_base64(data_url)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn fetch_url_content(
+    url: String,
+    state: State<'_, AppState>,
+) -> Result<String, CommandError> {
     use std::time::Duration;
 
     log::info!("[fetch_url_content] 请求获取URL内容: {}", url);
 
+    let config = state.get_config().await.map_err(CommandError::from)?;
+    if !config.allow_network_fetch {
+        return Err(CommandError::unsupported("Network fetches are disabled"));
+    }
+
     // 创建HTTP客户端，配置超时
     let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(30))
         .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
         .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        .map_err(|e| CommandError::internal(format!("Failed to create HTTP client: {}", e)))?;
 
     // 发起HTTP请求
     match client.get(&url).send().await {
@@ -527,23 +944,43 @@ pub async fn fetch_url_content(url: String) -> Result<String, String> {
                     }
                     Err(e) => {
                         log::error!("[fetch_url_content] 读取响应内容失败: {}", e);
-                        Err(format!("Failed to read response content: {}", e))
+                        Err(CommandError::new(
+                            ErrorCode::NetworkError,
+                            format!("Failed to read response content: {}", e),
+                        ))
                     }
                 }
             } else {
                 log::error!("[fetch_url_content] HTTP错误状态: {}", response.status());
-                Err(format!("HTTP error: {}", response.status()))
+                Err(CommandError::new(
+                    ErrorCode::NetworkError,
+                    format!("HTTP error: {}", response.status()),
+                ))
             }
         }
         Err(e) => {
             log::error!("[fetch_url_content] 网络请求失败: {}", e);
-            Err(format!("Network request failed: {}", e))
+            Err(CommandError::new(
+                ErrorCode::NetworkError,
+                format!("Network request failed: {}", e),
+            ))
         }
     }
 }
 
 #[tauri::command]
-pub async fn check_ffprobe_available() -> Result<bool, String> {
+pub async fn fetch_url_preview(
+    url: String,
+    state: State<'_, AppState>,
+) -> Result<UrlPreview, CommandError> {
+    state
+        .fetch_url_preview(url)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn check_ffprobe_available() -> Result<bool, CommandError> {
     use std::process::Command;
 
     log::debug!("[check_ffprobe_available] 检查 ffprobe 是否可用");
@@ -563,140 +1000,118 @@ pub async fn check_ffprobe_available() -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub async fn extract_media_metadata(url: String) -> Result<serde_json::Value, String> {
-    use std::process::Command;
-
+pub async fn extract_media_metadata(
+    url: String,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, CommandError> {
     log::info!("[extract_media_metadata] 提取媒体元数据: {}", url);
 
-    // 首先检查 ffprobe 是否可用
-    if !check_ffprobe_available().await? {
-        return Err("FFprobe not available".to_string());
-    }
-
-    // 使用 ffprobe 提取媒体信息
-    let output = Command::new("ffprobe")
-        .args([
-            "-v",
-            "quiet",
-            "-print_format",
-            "json",
-            "-show_format",
-            "-show_streams",
-            &url,
-        ])
-        .output()
-        .map_err(|e| format!("Failed to execute ffprobe: {}", e))?;
-
-    if !output.status.success() {
-        let error_msg = String::from_utf8_lossy(&output.stderr);
-        log::error!("[extract_media_metadata] ffprobe 执行失败: {}", error_msg);
-        return Err(format!("FFprobe execution failed: {}", error_msg));
-    }
-
-    let json_output = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Failed to parse ffprobe output as UTF-8: {}", e))?;
-
-    // 解析 JSON 输出
-    let metadata: serde_json::Value = serde_json::from_str(&json_output)
-        .map_err(|e| format!("Failed to parse ffprobe JSON output: {}", e))?;
-
-    // 提取关键信息
-    let mut result = serde_json::Map::new();
-
-    if let Some(format) = metadata.get("format") {
-        if let Some(duration) = format.get("duration") {
-            if let Some(duration_str) = duration.as_str() {
-                if let Ok(duration_f64) = duration_str.parse::<f64>() {
-                    let minutes = (duration_f64 / 60.0) as u32;
-                    let seconds = (duration_f64 % 60.0) as u32;
-                    result.insert(
-                        "duration".to_string(),
-                        serde_json::Value::String(format!("{}:{:02}", minutes, seconds)),
-                    );
-                }
-            }
-        }
-        if let Some(bit_rate) = format.get("bit_rate") {
-            if let Some(bit_rate_str) = bit_rate.as_str() {
-                if let Ok(bit_rate_i64) = bit_rate_str.parse::<i64>() {
-                    let kbps = bit_rate_i64 / 1000;
-                    result.insert(
-                        "bitrate".to_string(),
-                        serde_json::Value::String(format!("{} kbps", kbps)),
-                    );
-                }
-            }
-        }
-    }
-
-    if let Some(streams) = metadata.get("streams") {
-        if let Some(streams_array) = streams.as_array() {
-            for stream in streams_array {
-                if let Some(codec_type) = stream.get("codec_type") {
-                    if codec_type == "video" {
-                        if let Some(width) = stream.get("width") {
-                            result.insert("width".to_string(), width.clone());
-                        }
-                        if let Some(height) = stream.get("height") {
-                            result.insert("height".to_string(), height.clone());
-                        }
-                        if let Some(codec_name) = stream.get("codec_name") {
-                            result.insert("codec".to_string(), codec_name.clone());
-                        }
-                        if let Some(r_frame_rate) = stream.get("r_frame_rate") {
-                            if let Some(fps_str) = r_frame_rate.as_str() {
-                                // 处理分数形式的帧率，如 "30/1"
-                                if let Some(slash_pos) = fps_str.find('/') {
-                                    let numerator: f64 =
-                                        fps_str[..slash_pos].parse().unwrap_or(0.0);
-                                    let denominator: f64 =
-                                        fps_str[slash_pos + 1..].parse().unwrap_or(1.0);
-                                    if denominator != 0.0 {
-                                        let fps = numerator / denominator;
-                                        result.insert(
-                                            "fps".to_string(),
-                                            serde_json::Value::String(format!("{:.2}", fps)),
-                                        );
-                                    }
-                                }
-                            }
-                        }
-                    } else if codec_type == "audio" {
-                        if let Some(sample_rate) = stream.get("sample_rate") {
-                            result.insert("sample_rate".to_string(), sample_rate.clone());
-                        }
-                        if result.get("codec").is_none() {
-                            if let Some(codec_name) = stream.get("codec_name") {
-                                result.insert("codec".to_string(), codec_name.clone());
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    log::info!("[extract_media_metadata] 成功提取元数据: {:?}", result);
-    Ok(serde_json::Value::Object(result))
+    state
+        .extract_media_metadata(&url)
+        .await
+        .map_err(CommandError::from)
 }
 
 // Configuration commands
 #[tauri::command]
-pub async fn get_config(state: State<'_, AppState>) -> Result<AppConfig, String> {
-    state.get_config().await.map_err(|e| e.to_string())
+pub async fn get_config(state: State<'_, AppState>) -> Result<AppConfig, CommandError> {
+    state.get_config().await.map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn update_config(state: State<'_, AppState>, config: AppConfig) -> Result<(), String> {
-    state.update_config(config).await.map_err(|e| e.to_string())
+pub async fn update_config(
+    state: State<'_, AppState>,
+    config: AppConfig,
+) -> Result<(), CommandError> {
+    state
+        .update_config(config)
+        .await
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_cache_statistics(state: State<'_, AppState>) -> Result<CacheStatistics, String> {
+pub async fn get_cache_statistics(
+    state: State<'_, AppState>,
+) -> Result<CacheStatistics, CommandError> {
     state
         .get_cache_statistics()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn compact_database(state: State<'_, AppState>) -> Result<CompactResult, CommandError> {
+    state.compact_database().await.map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn clean_orphaned_images(state: State<'_, AppState>) -> Result<u64, CommandError> {
+    state
+        .clean_orphaned_images()
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn backup_now(state: State<'_, AppState>) -> Result<BackupInfo, CommandError> {
+    state.backup_now().await.map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn restore_backup(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<RestoreResult, CommandError> {
+    let result = state
+        .restore_backup(path.into())
+        .await
+        .map_err(CommandError::from);
+    if result.is_ok() {
+        let app_handle = app.clone();
+        tokio::spawn(async move {
+            let _ = app_handle.track_event("backup_restored", None);
+        });
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn export_sync_bundle(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<SyncExportResult, CommandError> {
+    let result = state
+        .export_sync_bundle(path.into())
+        .await
+        .map_err(CommandError::from);
+    if result.is_ok() {
+        let app_handle = app.clone();
+        tokio::spawn(async move {
+            let _ = app_handle.track_event("sync_bundle_exported", None);
+        });
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn import_sync_bundle(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<SyncImportResult, CommandError> {
+    let result = state
+        .import_sync_bundle(path.into())
+        .await
+        .map_err(CommandError::from);
+    if result.is_ok() {
+        let app_handle = app.clone();
+        tokio::spawn(async move {
+            let _ = app_handle.track_event("sync_bundle_imported", None);
+        });
+    }
+    result
 }
 
 // Global shortcut commands
@@ -705,50 +1120,120 @@ pub async fn register_global_shortcut(
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
     shortcut: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     state
         .register_global_shortcut(app_handle, shortcut)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn unregister_global_shortcut(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn unregister_global_shortcut(state: State<'_, AppState>) -> Result<(), CommandError> {
     state
         .unregister_global_shortcut()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn register_action_shortcut(
+    state: State<'_, AppState>,
+    action: String,
+    accelerator: String,
+) -> Result<(), CommandError> {
+    let action = ShortcutAction::parse(&action).ok_or_else(|| {
+        CommandError::invalid_input(format!("Unknown shortcut action: {}", action))
+    })?;
+    state
+        .register_action_shortcut(action, accelerator)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn unregister_action_shortcut(
+    state: State<'_, AppState>,
+    action: String,
+) -> Result<(), CommandError> {
+    let action = ShortcutAction::parse(&action).ok_or_else(|| {
+        CommandError::invalid_input(format!("Unknown shortcut action: {}", action))
+    })?;
+    state
+        .unregister_action_shortcut(action)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn get_action_shortcuts(
+    state: State<'_, AppState>,
+) -> Result<BTreeMap<ShortcutAction, String>, CommandError> {
+    Ok(state.get_action_shortcuts().await)
 }
 
 // Auto startup commands
 #[tauri::command]
-pub async fn set_auto_startup(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
+pub async fn set_auto_startup(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), CommandError> {
     state
         .set_auto_startup(enabled)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_auto_startup_status(state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn get_auto_startup_status(state: State<'_, AppState>) -> Result<bool, CommandError> {
     state
         .get_auto_startup_status()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 // Cache cleanup command
 #[tauri::command]
-pub async fn cleanup_expired_entries(state: State<'_, AppState>) -> Result<CleanupResult, String> {
+pub async fn cleanup_expired_entries(
+    state: State<'_, AppState>,
+) -> Result<CleanupResult, CommandError> {
     state
         .cleanup_expired_entries()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn deduplicate_history(
+    state: State<'_, AppState>,
+) -> Result<CleanupResult, CommandError> {
+    state
+        .deduplicate_history()
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn reprocess_entries(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    limit: Option<i64>,
+) -> Result<u64, CommandError> {
+    let result = state
+        .reprocess_entries(limit)
+        .await
+        .map_err(CommandError::from);
+    if result.is_ok() {
+        let app_handle = app.clone();
+        tokio::spawn(async move {
+            let _ = app_handle.track_event("entries_reprocessed", None);
+        });
+    }
+    result
 }
 
 // App list commands
 #[tauri::command]
-pub async fn get_installed_applications() -> Result<Vec<InstalledApp>, String> {
+pub async fn get_installed_applications() -> Result<Vec<InstalledApp>, CommandError> {
     log::info!("[get_installed_applications] Starting to load applications...");
 
     match AppListManager::get_installed_applications() {
@@ -764,48 +1249,31 @@ pub async fn get_installed_applications() -> Result<Vec<InstalledApp>, String> {
                 "[get_installed_applications] Error loading applications: {}",
                 e
             );
-            Err(e.to_string())
+            Err(CommandError::from(e))
         }
     }
 }
 
 #[tauri::command]
-pub async fn get_common_excluded_apps() -> Result<Vec<InstalledApp>, String> {
+pub async fn get_common_excluded_apps() -> Result<Vec<InstalledApp>, CommandError> {
     Ok(AppListManager::get_common_excluded_apps())
 }
 
 // Shortcut validation command
 #[tauri::command]
-pub async fn validate_shortcut(shortcut: String) -> Result<bool, String> {
-    // Basic validation for shortcut format
-    if shortcut.is_empty() {
-        return Ok(false);
-    }
-
-    // Check for required modifier keys (at least one)
-    let has_modifier = shortcut.contains("Cmd")
-        || shortcut.contains("Ctrl")
-        || shortcut.contains("Alt")
-        || shortcut.contains("Shift");
-
-    if !has_modifier {
-        return Ok(false);
-    }
-
-    // Check for system shortcut conflicts (basic check)
-    let system_shortcuts = [
-        "CmdOrCtrl+Q",   // Quit
-        "CmdOrCtrl+W",   // Close window
-        "CmdOrCtrl+H",   // Hide window
-        "CmdOrCtrl+M",   // Minimize
-        "CmdOrCtrl+Tab", // Switch apps
-    ];
-
-    if system_shortcuts.contains(&shortcut.as_str()) {
-        return Ok(false);
-    }
-
-    Ok(true)
+pub async fn validate_shortcut(
+    state: State<'_, AppState>,
+    shortcut: String,
+    action: Option<String>,
+) -> Result<ShortcutValidation, CommandError> {
+    let action = action
+        .map(|action| {
+            ShortcutAction::parse(&action).ok_or_else(|| {
+                CommandError::invalid_input(format!("Unknown shortcut action: {}", action))
+            })
+        })
+        .transpose()?;
+    Ok(state.validate_shortcut(&shortcut, action).await)
 }
 
 // Update commands
@@ -813,15 +1281,16 @@ pub async fn validate_shortcut(shortcut: String) -> Result<bool, String> {
 pub async fn check_for_update(
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<UpdateInfo, String> {
+) -> Result<UpdateInfo, UpdateError> {
     log::info!("[check_for_update] Manual update check requested");
 
     // Update last check time in config
     let mut config = state.get_config().await.map_err(|e| e.to_string())?;
+    let channel = config.update_channel;
     config.last_update_check = Some(UpdateManager::get_current_timestamp());
     let _ = state.update_config(config).await;
 
-    match UpdateManager::check_for_updates(&app_handle).await {
+    match UpdateManager::check_for_updates(&app_handle, channel).await {
         Ok(Some(update_info)) => {
             log::info!("[check_for_update] Check completed successfully - update available");
             Ok(update_info)
@@ -836,22 +1305,59 @@ pub async fn check_for_update(
             })
         }
         Err(e) => {
-            log::error!("[check_for_update] Update check failed: {}", e);
-            Err(format!("更新检查失败: {}", e))
+            let error = UpdateError::new("更新检查失败", e);
+            log::error!(
+                "[check_for_update] Update check failed ({:?}): {}",
+                error.kind,
+                error.message
+            );
+            Err(error)
         }
     }
 }
 
 #[tauri::command]
-pub async fn install_update(app_handle: tauri::AppHandle) -> Result<(), String> {
-    UpdateManager::download_and_install(&app_handle)
+pub async fn get_changelog(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<Changelog>, UpdateError> {
+    let channel = state
+        .get_config()
+        .await
+        .map_err(|e| e.to_string())?
+        .update_channel;
+    UpdateManager::check_for_updates(&app_handle, channel)
+        .await
+        .map(|update| update.as_ref().map(Changelog::from_update_info))
+        .map_err(|e| UpdateError::new("更新检查失败", e))
+}
+
+#[tauri::command]
+pub async fn install_update(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), UpdateError> {
+    let channel = state
+        .get_config()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?
+        .update_channel;
+    UpdateManager::download_and_install(&app_handle, channel)
+        .await
+        .map_err(|e| {
+            let error = UpdateError::new("更新安装失败", e);
+            log::error!(
+                "[install_update] Update install failed ({:?}): {}",
+                error.kind,
+                error.message
+            );
+            error
+        })
 }
 
 #[tauri::command]
-pub async fn should_check_for_updates(state: State<'_, AppState>) -> Result<bool, String> {
-    let config = state.get_config().await.map_err(|e| e.to_string())?;
+pub async fn should_check_for_updates(state: State<'_, AppState>) -> Result<bool, CommandError> {
+    let config = state.get_config().await.map_err(CommandError::from)?;
 
     // Check if auto-update is enabled
     if !config.auto_update {
@@ -861,50 +1367,77 @@ pub async fn should_check_for_updates(state: State<'_, AppState>) -> Result<bool
     // Check if enough time has passed since last check
     Ok(UpdateManager::should_check_for_updates(
         config.last_update_check.as_deref(),
+        config.update_snoozed_until.as_deref(),
     ))
 }
 
 #[tauri::command]
-pub async fn set_window_title(window: Window, title: String) -> Result<(), String> {
-    window.set_title(&title).map_err(|e| e.to_string())
+pub async fn snooze_update(state: State<'_, AppState>, days: u32) -> Result<String, CommandError> {
+    if !(1..=UpdateManager::MAX_SNOOZE_DAYS).contains(&days) {
+        return Err(CommandError::invalid_input(format!(
+            "推迟天数必须在 1 到 {} 之间",
+            UpdateManager::MAX_SNOOZE_DAYS
+        )));
+    }
+
+    let mut config = state.get_config().await.map_err(CommandError::from)?;
+    let snoozed_until = UpdateManager::snooze_until(days);
+    config.update_snoozed_until = Some(snoozed_until.clone());
+    state
+        .update_config(config)
+        .await
+        .map_err(CommandError::from)?;
+
+    log::info!("[UpdateManager] 更新提醒已推迟到 {}", snoozed_until);
+    Ok(snoozed_until)
+}
+
+#[tauri::command]
+pub async fn set_window_title(window: Window, title: String) -> Result<(), CommandError> {
+    window
+        .set_title(&title)
+        .map_err(|e| CommandError::new(ErrorCode::Internal, e.to_string()))
+}
+
+#[tauri::command]
+pub async fn get_window_state(
+    state: State<'_, AppState>,
+) -> Result<Option<WindowState>, CommandError> {
+    Ok(state.get_window_state().await)
+}
+
+#[tauri::command]
+pub async fn set_window_state(
+    state: State<'_, AppState>,
+    window_state: WindowState,
+) -> Result<(), CommandError> {
+    state
+        .set_window_state(window_state)
+        .await
+        .map_err(CommandError::from)
 }
 
 // Log management commands
 #[tauri::command]
-pub async fn get_log_content() -> Result<String, String> {
-    use dirs;
+pub async fn get_log_content(app: tauri::AppHandle) -> Result<String, CommandError> {
     use std::fs;
 
-    // Tauri v2 LogDir location on macOS: ~/Library/Logs/{app_identifier}/
-    let log_file = dirs::home_dir()
-        .ok_or("无法获取用户目录")?
-        .join("Library")
-        .join("Logs")
-        .join("com.dance.app")
-        .join("dance.log");
-
+    let log_file = logging::log_file_path(&app).map_err(CommandError::from)?;
     if !log_file.exists() {
         return Ok(String::new());
     }
 
-    fs::read_to_string(&log_file).map_err(|e| format!("读取日志文件失败: {}", e))
+    fs::read_to_string(&log_file).map_err(|e| CommandError::io(format!("读取日志文件失败: {}", e)))
 }
 
 #[tauri::command]
-pub async fn clear_logs() -> Result<(), String> {
-    use dirs;
+pub async fn clear_logs(app: tauri::AppHandle) -> Result<(), CommandError> {
     use std::fs;
 
-    // Tauri v2 LogDir location on macOS: ~/Library/Logs/{app_identifier}/
-    let log_file = dirs::home_dir()
-        .ok_or("无法获取用户目录")?
-        .join("Library")
-        .join("Logs")
-        .join("com.dance.app")
-        .join("dance.log");
-
+    let log_file = logging::log_file_path(&app).map_err(CommandError::from)?;
     if log_file.exists() {
-        fs::write(&log_file, "").map_err(|e| format!("清空日志文件失败: {}", e))?;
+        fs::write(&log_file, "")
+            .map_err(|e| CommandError::io(format!("清空日志文件失败: {}", e)))?;
         log::info!("日志文件已清空");
     }
 
@@ -912,25 +1445,15 @@ pub async fn clear_logs() -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn set_log_level(level: String) -> Result<(), String> {
-    let _log_level = match level.to_lowercase().as_str() {
-        "error" => log::LevelFilter::Error,
-        "warn" => log::LevelFilter::Warn,
-        "info" => log::LevelFilter::Info,
-        "debug" => log::LevelFilter::Debug,
-        "trace" => log::LevelFilter::Trace,
-        _ => return Err("无效的日志级别".to_string()),
-    };
-
-    // Note: Tauri log plugin doesn't support runtime level changes directly
-    // This is a placeholder - the actual implementation would need to be done differently
-    log::info!("请求设置日志级别为: {}", level);
-    Ok(())
+pub async fn set_log_level(state: State<'_, AppState>, level: String) -> Result<(), CommandError> {
+    let level: LogLevel = level
+        .parse()
+        .map_err(|_| CommandError::invalid_input("无效的日志级别"))?;
+    state.set_log_level(level).await.map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_current_log_level() -> Result<String, String> {
-    // This is a simplified implementation
-    // In practice, you'd need to store the current level somewhere
-    Ok("info".to_string())
+pub async fn get_current_log_level(state: State<'_, AppState>) -> Result<String, CommandError> {
+    let config = state.get_config().await.map_err(CommandError::from)?;
+    Ok(config.log.level.as_str().to_string())
 }
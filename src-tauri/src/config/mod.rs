@@ -1,8 +1,21 @@
 use anyhow::Result;
+use regex::Regex;
+
+use crate::clipboard::content_detector::ContentSubType;
+use crate::clipboard::processor::{ImageStorage, ImageStorageFormat, DEFAULT_WEBP_QUALITY};
+use crate::clipboard::smart_paste::SmartPasteTransform;
+use crate::error::CommandError;
+use crate::logging::LogLevel;
+use crate::shortcuts::ShortcutAction;
+use crate::updater::UpdateChannel;
+use crate::utils::window_state::WindowState;
+use crate::webhook;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExcludedApp {
@@ -24,14 +37,431 @@ pub struct AppConfig {
     pub auto_update: bool,
     #[serde(default)]
     pub last_update_check: Option<String>, // ISO 8601 date string
+    #[serde(default)]
+    pub update_snoozed_until: Option<String>, // ISO 8601, no update prompts before this time
     #[serde(default = "default_language")]
     pub language: String, // Language preference (zh or en)
+    #[serde(default)]
+    pub never_dedup_subtypes: Vec<String>, // Subtypes that always create a new entry
+    #[serde(default)]
+    pub dedup_mode: DedupMode,
+    #[serde(default = "default_max_copy_count")]
+    pub max_copy_count: i32, // copy_count saturates at this value
+    #[serde(default)]
+    pub max_total_entries: u32, // 0 = unlimited
+    #[serde(default)]
+    pub monitor: MonitorConfig,
+    #[serde(default)]
+    pub excluded_content_patterns: Vec<String>, // Regexes; matching text is never stored
+    #[serde(default)]
+    pub excluded_window_title_patterns: Vec<String>, // Regexes matched against the source window title
+    #[serde(default)]
+    pub skip_sensitive_subtypes: Vec<String>, // Detected subtypes that are never stored
+    #[serde(default)]
+    pub action_shortcuts: BTreeMap<ShortcutAction, String>, // show_window lives in global_shortcut
+    #[serde(default)]
+    pub ocr_enabled: bool, // Only effective when built with the `ocr` feature
+    #[serde(default)]
+    pub language_detection_enabled: bool, // Only effective when built with the `language-detection` feature
+    #[serde(default = "default_strip_image_metadata")]
+    pub strip_image_metadata: bool, // Remove EXIF/XMP (GPS, device info) from stored images
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub webhook_url: Option<String>, // POSTed with every newly saved entry
+    #[serde(default)]
+    pub webhook_include_content: bool, // false sends the entry without its content
+    #[serde(default = "default_tracking_params")]
+    pub tracking_params: Vec<String>, // Query params removed by clean_url; `*` suffix matches a prefix
+    #[serde(default)]
+    pub copy_count_half_life_days: Option<u32>, // Decay "most copied" ranking by age; None/0 = raw count
+    #[serde(default = "default_allow_network_fetch")]
+    pub allow_network_fetch: bool, // false disables fetch_url_content and URL previews
+    #[serde(default = "default_url_preview_cache_ttl_secs")]
+    pub url_preview_cache_ttl_secs: u64, // 0 = always refetch
+    #[serde(default)]
+    pub capture_rich_text: bool, // Also store HTML/RTF flavors in metadata.rich_text
+    #[serde(default)]
+    pub image_storage_format: ImageStorageFormat,
+    #[serde(default = "default_webp_quality")]
+    pub webp_quality: u8, // 1-100, only used when image_storage_format is webp
+    #[serde(default = "default_monitoring_enabled")]
+    pub monitoring_enabled: bool, // Last start/stop state, restored on launch
+    #[serde(default)]
+    pub subtype_expiry: BTreeMap<String, ExpiryOption>, // Overrides text/image expiry for these subtypes
+    #[serde(default = "default_paste_focus_delay_ms")]
+    pub paste_focus_delay_ms: u64, // Wait after hiding the window before sending Cmd+V (macOS)
+    #[serde(default)]
+    pub window_state: Option<WindowState>, // Main window geometry, restored on launch
+    #[serde(default)]
+    pub log: LogConfig,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
+    pub smart_paste: BTreeMap<String, SmartPasteTransform>, // Subtype -> transform used by smart_paste; others paste unchanged
+    #[serde(default)]
+    pub statistics: StatisticsConfig,
+}
+
+impl AppConfig {
+    pub const MIN_PASTE_FOCUS_DELAY_MS: u64 = 50;
+    pub const MAX_PASTE_FOCUS_DELAY_MS: u64 = 5000;
+
+    /// 校验整个配置，保存配置和热重载时共用；正则表达式的编译由 ConfigManager 单独处理
+    pub fn validate(&self) -> Result<()> {
+        self.monitor.validate()?;
+        self.api.validate()?;
+        self.log.validate()?;
+        self.backup.validate()?;
+        self.statistics.validate()?;
+        self.validate_paste_focus_delay()?;
+        self.validate_smart_paste()?;
+        if let Some(window_state) = &self.window_state {
+            window_state.validate()?;
+        }
+        webhook::validate_url(self.webhook_url.as_deref())
+    }
+
+    pub fn validate_paste_focus_delay(&self) -> Result<()> {
+        if !(Self::MIN_PASTE_FOCUS_DELAY_MS..=Self::MAX_PASTE_FOCUS_DELAY_MS)
+            .contains(&self.paste_focus_delay_ms)
+        {
+            return Err(CommandError::invalid_input(format!(
+                "paste_focus_delay_ms must be between {}ms and {}ms, got {}ms",
+                Self::MIN_PASTE_FOCUS_DELAY_MS,
+                Self::MAX_PASTE_FOCUS_DELAY_MS,
+                self.paste_focus_delay_ms
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    pub fn validate_smart_paste(&self) -> Result<()> {
+        for (subtype, transform) in &self.smart_paste {
+            let parsed = ContentSubType::parse(subtype).ok_or_else(|| {
+                CommandError::invalid_input(format!(
+                    "Unknown content subtype in smart_paste: {}",
+                    subtype
+                ))
+            })?;
+            if !transform.supports(parsed) {
+                return Err(CommandError::invalid_input(format!(
+                    "smart_paste transform {:?} cannot be used for {} entries",
+                    transform, subtype
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// 所有动作的快捷键，显示窗口沿用 global_shortcut 字段
+    pub fn shortcuts(&self) -> BTreeMap<ShortcutAction, String> {
+        let mut shortcuts = self.action_shortcuts.clone();
+        shortcuts.remove(&ShortcutAction::ShowWindow);
+        if !self.global_shortcut.is_empty() {
+            shortcuts.insert(ShortcutAction::ShowWindow, self.global_shortcut.clone());
+        }
+        shortcuts
+    }
+
+    /// 保存图片时使用的格式和选项
+    pub fn image_storage(&self) -> ImageStorage {
+        ImageStorage {
+            format: self.image_storage_format,
+            webp_quality: self.webp_quality.clamp(1, 100),
+            strip_metadata: self.strip_image_metadata,
+        }
+    }
+
+    pub fn set_shortcut(&mut self, action: ShortcutAction, accelerator: Option<String>) {
+        match action {
+            ShortcutAction::ShowWindow => {
+                self.global_shortcut = accelerator.unwrap_or_default();
+            }
+            _ => match accelerator {
+                Some(accelerator) => {
+                    self.action_shortcuts.insert(action, accelerator);
+                }
+                None => {
+                    self.action_shortcuts.remove(&action);
+                }
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorConfig {
+    pub poll_interval_ms: u64,
+    pub debounce_ms: u64, // 0 disables debouncing
+}
+
+impl MonitorConfig {
+    pub const MIN_POLL_INTERVAL_MS: u64 = 50;
+
+    pub fn validate(&self) -> Result<()> {
+        if self.poll_interval_ms < Self::MIN_POLL_INTERVAL_MS {
+            return Err(CommandError::invalid_input(format!(
+                "poll_interval_ms must be at least {}ms, got {}ms",
+                Self::MIN_POLL_INTERVAL_MS,
+                self.poll_interval_ms
+            ))
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// 本地 HTTP API（仅监听 127.0.0.1），修改后需重启应用生效
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: Option<String>, // Required by write endpoints; unset disables them
+}
+
+impl ApiConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.enabled && self.port == 0 {
+            return Err(CommandError::invalid_input(
+                "api.port must be set when the API is enabled",
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// 日志配置，级别修改后立即生效，文件输出和轮转设置需重启应用生效
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogConfig {
+    #[serde(default)]
+    pub level: LogLevel,
+    #[serde(default = "default_log_to_file")]
+    pub to_file: bool, // Also write to the system log directory
+    #[serde(default = "default_log_max_file_size_mb")]
+    pub max_file_size_mb: u64, // Rotate the log file once it reaches this size
+    #[serde(default = "default_log_keep_files")]
+    pub keep_files: usize, // Rotated files kept, including the current one
+}
+
+impl LogConfig {
+    pub const MAX_FILE_SIZE_MB: u64 = 100;
+    pub const MAX_KEEP_FILES: usize = 20;
+
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=Self::MAX_FILE_SIZE_MB).contains(&self.max_file_size_mb) {
+            return Err(CommandError::invalid_input(format!(
+                "log.max_file_size_mb must be between 1 and {}, got {}",
+                Self::MAX_FILE_SIZE_MB,
+                self.max_file_size_mb
+            ))
+            .into());
+        }
+        if !(1..=Self::MAX_KEEP_FILES).contains(&self.keep_files) {
+            return Err(CommandError::invalid_input(format!(
+                "log.keep_files must be between 1 and {}, got {}",
+                Self::MAX_KEEP_FILES,
+                self.keep_files
+            ))
+            .into());
+        }
+        Ok(())
+    }
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: LogLevel::default(),
+            to_file: default_log_to_file(),
+            max_file_size_mb: default_log_max_file_size_mb(),
+            keep_files: default_log_keep_files(),
+        }
+    }
+}
+
+/// 数据库自动备份，备份文件保存在数据目录的 backups/ 下
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_backup_interval_hours")]
+    pub interval_hours: u32,
+    #[serde(default = "default_backup_keep")]
+    pub keep: usize, // Most recent backups kept, older ones are deleted
+}
+
+impl BackupConfig {
+    pub const MAX_INTERVAL_HOURS: u32 = 24 * 30;
+    pub const MAX_KEEP: usize = 100;
+
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=Self::MAX_INTERVAL_HOURS).contains(&self.interval_hours) {
+            return Err(CommandError::invalid_input(format!(
+                "backup.interval_hours must be between 1 and {}, got {}",
+                Self::MAX_INTERVAL_HOURS,
+                self.interval_hours
+            ))
+            .into());
+        }
+        if !(1..=Self::MAX_KEEP).contains(&self.keep) {
+            return Err(CommandError::invalid_input(format!(
+                "backup.keep must be between 1 and {}, got {}",
+                Self::MAX_KEEP,
+                self.keep
+            ))
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// 统计页面中各列表返回的条目数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatisticsConfig {
+    #[serde(default = "default_statistics_limit")]
+    pub most_copied_limit: usize,
+    #[serde(default = "default_statistics_limit")]
+    pub recent_apps_limit: usize,
+}
+
+impl StatisticsConfig {
+    pub const MAX_LIMIT: usize = 100;
+
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=Self::MAX_LIMIT).contains(&self.most_copied_limit) {
+            return Err(CommandError::invalid_input(format!(
+                "statistics.most_copied_limit must be between 1 and {}, got {}",
+                Self::MAX_LIMIT,
+                self.most_copied_limit
+            ))
+            .into());
+        }
+        if !(1..=Self::MAX_LIMIT).contains(&self.recent_apps_limit) {
+            return Err(CommandError::invalid_input(format!(
+                "statistics.recent_apps_limit must be between 1 and {}, got {}",
+                Self::MAX_LIMIT,
+                self.recent_apps_limit
+            ))
+            .into());
+        }
+        Ok(())
+    }
+}
+
+impl Default for StatisticsConfig {
+    fn default() -> Self {
+        Self {
+            most_copied_limit: default_statistics_limit(),
+            recent_apps_limit: default_statistics_limit(),
+        }
+    }
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: default_backup_interval_hours(),
+            keep: default_backup_keep(),
+        }
+    }
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 18321,
+            token: None,
+        }
+    }
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: 500,
+            debounce_ms: 0,
+        }
+    }
+}
+
+fn default_log_to_file() -> bool {
+    true
+}
+
+fn default_log_max_file_size_mb() -> u64 {
+    5
+}
+
+fn default_log_keep_files() -> usize {
+    3
+}
+
+fn default_backup_interval_hours() -> u32 {
+    24
+}
+
+fn default_backup_keep() -> usize {
+    7
+}
+
+fn default_statistics_limit() -> usize {
+    10
 }
 
 fn default_language() -> String {
     "system".to_string()
 }
 
+fn default_image_dedup_distance() -> Option<u32> {
+    Some(4)
+}
+
+fn default_strip_image_metadata() -> bool {
+    true
+}
+
+fn default_monitoring_enabled() -> bool {
+    true
+}
+
+fn default_webp_quality() -> u8 {
+    DEFAULT_WEBP_QUALITY
+}
+
+fn default_paste_focus_delay_ms() -> u64 {
+    200
+}
+
+fn default_allow_network_fetch() -> bool {
+    true
+}
+
+fn default_url_preview_cache_ttl_secs() -> u64 {
+    60 * 60
+}
+
+fn default_tracking_params() -> Vec<String> {
+    [
+        "utm_*", "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid",
+        "_hsenc", "_hsmi", "mkt_tok", "spm", "vero_id",
+    ]
+    .iter()
+    .map(|param| param.to_string())
+    .collect()
+}
+
+fn default_max_copy_count() -> i32 {
+    i32::MAX - 1
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub enum ExpiryOption {
     Days(u32),
@@ -46,17 +476,60 @@ impl ExpiryOption {
             ExpiryOption::Never => None,
         }
     }
+
+    /// 早于该时间（毫秒时间戳）创建的条目已过期，Never 返回 None
+    pub fn cutoff_ms(&self, now_ms: i64) -> Option<i64> {
+        self.as_days()
+            .map(|days| now_ms - (days as i64) * 24 * 60 * 60 * 1000)
+    }
+}
+
+/// 重复内容的合并方式。
+/// by_hash 把相同内容合并为一条并累加复制次数，占用最少；
+/// consecutive_only 只合并紧接着的重复复制，保留复制的先后顺序；
+/// none 每次复制都单独记录，便于审计，但重复内容（包括图片文件）会按复制次数成倍占用空间。
+/// 连续两次复制相同内容依靠剪贴板变化计数区分，Linux 没有变化计数，
+/// 此时 consecutive_only 和 none 也无法记录紧接着的重复复制
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DedupMode {
+    #[default]
+    ByHash,
+    ConsecutiveOnly,
+    None,
+}
+
+/// 文本超过 max_size_mb 时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OversizePolicy {
+    #[default]
+    Skip,
+    Truncate,
+}
+
+impl OversizePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OversizePolicy::Skip => "skip",
+            OversizePolicy::Truncate => "truncate",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TextConfig {
     pub max_size_mb: f64,
     pub expiry: ExpiryOption,
+    #[serde(default)]
+    pub on_oversize: OversizePolicy, // Skip oversized text or keep its first max_size_mb
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageConfig {
     pub expiry: ExpiryOption,
+    #[serde(default = "default_image_dedup_distance")]
+    pub dedup_distance: Option<u32>, // Max phash Hamming distance to merge; None disables
 }
 
 impl Default for AppConfig {
@@ -65,9 +538,11 @@ impl Default for AppConfig {
             text: TextConfig {
                 max_size_mb: 1.0,
                 expiry: ExpiryOption::Never,
+                on_oversize: OversizePolicy::default(),
             },
             image: ImageConfig {
                 expiry: ExpiryOption::Never,
+                dedup_distance: default_image_dedup_distance(),
             },
             excluded_apps: vec![], // Legacy format, migrate to excluded_apps_v2
             excluded_apps_v2: vec![
@@ -84,7 +559,39 @@ impl Default for AppConfig {
             auto_startup: false,
             auto_update: true,
             last_update_check: None,
+            update_snoozed_until: None,
             language: default_language(),
+            never_dedup_subtypes: vec![],
+            dedup_mode: DedupMode::default(),
+            max_copy_count: default_max_copy_count(),
+            max_total_entries: 0,
+            monitor: MonitorConfig::default(),
+            excluded_content_patterns: vec![],
+            excluded_window_title_patterns: vec![],
+            skip_sensitive_subtypes: vec![],
+            action_shortcuts: BTreeMap::new(),
+            ocr_enabled: false,
+            language_detection_enabled: false,
+            strip_image_metadata: default_strip_image_metadata(),
+            update_channel: UpdateChannel::default(),
+            api: ApiConfig::default(),
+            webhook_url: None,
+            webhook_include_content: false,
+            tracking_params: default_tracking_params(),
+            copy_count_half_life_days: None,
+            allow_network_fetch: default_allow_network_fetch(),
+            url_preview_cache_ttl_secs: default_url_preview_cache_ttl_secs(),
+            capture_rich_text: false,
+            image_storage_format: ImageStorageFormat::default(),
+            webp_quality: default_webp_quality(),
+            monitoring_enabled: default_monitoring_enabled(),
+            subtype_expiry: BTreeMap::new(),
+            paste_focus_delay_ms: default_paste_focus_delay_ms(),
+            window_state: None,
+            log: LogConfig::default(),
+            backup: BackupConfig::default(),
+            smart_paste: BTreeMap::new(),
+            statistics: StatisticsConfig::default(),
         }
     }
 }
@@ -92,23 +599,50 @@ impl Default for AppConfig {
 pub struct ConfigManager {
     config_path: PathBuf,
     pub config: AppConfig,
+    content_patterns: Vec<Regex>,
+    window_title_patterns: Vec<Regex>,
+    last_saved_content: Option<String>, // What we last wrote, to ignore our own file events
 }
 
 impl ConfigManager {
     pub async fn new() -> Result<Self> {
-        let config_path = Self::get_config_path()?;
+        Self::open(Self::get_config_path()?).await
+    }
 
+    /// 加载指定位置的配置，文件不存在时写入默认配置。
+    /// 文件损坏（如写入时崩溃留下的半截 JSON）时备份为 .bak 并使用默认配置，避免无法启动
+    pub(crate) async fn open(config_path: PathBuf) -> Result<Self> {
         // 确保配置目录存在
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent).await?;
         }
 
-        let config = if config_path.exists() {
-            Self::load_config(&config_path).await?
+        let loaded = if config_path.exists() {
+            match Self::load_config(&config_path).await {
+                Ok(config) => Some(config),
+                Err(e) => {
+                    let backup_path = config_path.with_extension("json.bak");
+                    log::error!(
+                        "[Config] 配置文件无法解析，已备份到 {:?} 并恢复默认配置: {}",
+                        backup_path,
+                        e
+                    );
+                    fs::rename(&config_path, &backup_path).await?;
+                    None
+                }
+            }
         } else {
-            let default_config = AppConfig::default();
-            Self::save_config(&config_path, &default_config).await?;
-            default_config
+            None
+        };
+
+        let mut last_saved_content = None;
+        let config = match loaded {
+            Some(config) => config,
+            None => {
+                let default_config = AppConfig::default();
+                last_saved_content = Some(Self::save_config(&config_path, &default_config).await?);
+                default_config
+            }
         };
 
         // Migrate old excluded_apps format to new format if needed
@@ -125,27 +659,148 @@ impl ConfigManager {
 
         // Always save the config after loading to ensure it's in the latest format
         if config_path.exists() || needs_migration {
-            Self::save_config(&config_path, &migrated_config).await?;
+            last_saved_content = Some(Self::save_config(&config_path, &migrated_config).await?);
         }
 
+        let content_patterns =
+            Self::compile_patterns_lenient(&migrated_config.excluded_content_patterns);
+        let window_title_patterns =
+            Self::compile_patterns_lenient(&migrated_config.excluded_window_title_patterns);
+
         Ok(Self {
             config_path,
             config: migrated_config,
+            content_patterns,
+            window_title_patterns,
+            last_saved_content,
         })
     }
 
+    #[cfg(test)]
+    pub fn from_config(config_path: PathBuf, config: AppConfig) -> Self {
+        let content_patterns = Self::compile_patterns_lenient(&config.excluded_content_patterns);
+        let window_title_patterns =
+            Self::compile_patterns_lenient(&config.excluded_window_title_patterns);
+        Self {
+            config_path,
+            config,
+            content_patterns,
+            window_title_patterns,
+            last_saved_content: None,
+        }
+    }
+
     pub async fn update_config(&mut self, new_config: AppConfig) -> Result<()> {
+        new_config.validate()?;
+        let content_patterns = Self::compile_content_patterns(
+            &new_config.excluded_content_patterns,
+        )
+        .map_err(|errors| {
+            CommandError::invalid_input(format!(
+                "Invalid excluded content patterns: {}",
+                errors.join("; ")
+            ))
+        })?;
+        let window_title_patterns = Self::compile_content_patterns(
+            &new_config.excluded_window_title_patterns,
+        )
+        .map_err(|errors| {
+            CommandError::invalid_input(format!(
+                "Invalid excluded window title patterns: {}",
+                errors.join("; ")
+            ))
+        })?;
         self.config = new_config.clone();
-        Self::save_config(&self.config_path, &new_config).await?;
+        self.content_patterns = content_patterns;
+        self.window_title_patterns = window_title_patterns;
+        self.last_saved_content = Some(Self::save_config(&self.config_path, &new_config).await?);
         Ok(())
     }
 
     #[allow(dead_code)]
     pub async fn reload(&mut self) -> Result<()> {
         self.config = Self::load_config(&self.config_path).await?;
+        self.content_patterns =
+            Self::compile_patterns_lenient(&self.config.excluded_content_patterns);
+        self.window_title_patterns =
+            Self::compile_patterns_lenient(&self.config.excluded_window_title_patterns);
         Ok(())
     }
 
+    /// 配置文件被外部修改时重新加载，内容与上次写入一致（即自身写入）时返回 false
+    pub async fn reload_if_changed(&mut self) -> Result<bool> {
+        let content = fs::read_to_string(&self.config_path).await?;
+        if self.last_saved_content.as_deref() == Some(content.as_str()) {
+            return Ok(false);
+        }
+
+        let config = Self::parse_config(&content).await?;
+        config.validate()?;
+
+        self.content_patterns = Self::compile_patterns_lenient(&config.excluded_content_patterns);
+        self.window_title_patterns =
+            Self::compile_patterns_lenient(&config.excluded_window_title_patterns);
+        self.config = config;
+        self.last_saved_content = Some(content);
+        Ok(true)
+    }
+
+    pub fn config_path(&self) -> &PathBuf {
+        &self.config_path
+    }
+
+    /// 编译排除正则（内容或窗口标题），全部有效时返回编译结果，否则返回每个无效模式的错误描述
+    pub fn compile_content_patterns(
+        patterns: &[String],
+    ) -> std::result::Result<Vec<Regex>, Vec<String>> {
+        let mut compiled = Vec::with_capacity(patterns.len());
+        let mut errors = Vec::new();
+
+        for pattern in patterns {
+            match Regex::new(pattern) {
+                Ok(regex) => compiled.push(regex),
+                Err(e) => errors.push(format!("{:?}: {}", pattern, e)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(compiled)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// 从磁盘加载的配置中可能含有手动写入的无效模式，跳过它们并记录警告
+    fn compile_patterns_lenient(patterns: &[String]) -> Vec<Regex> {
+        patterns
+            .iter()
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    log::warn!("[Config] 忽略无效的排除模式 {:?}: {}", pattern, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// 在加载完整配置之前读取日志配置，以便启动时尽早初始化日志；读取或解析失败时使用默认值
+    pub fn load_log_config() -> LogConfig {
+        #[derive(Deserialize)]
+        struct LogSection {
+            #[serde(default)]
+            log: LogConfig,
+        }
+
+        Self::get_config_path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<LogSection>(&content).ok())
+            .map(|section| section.log)
+            .filter(|log| log.validate().is_ok())
+            .unwrap_or_default()
+    }
+
     fn get_config_path() -> Result<PathBuf> {
         let config_dir =
             dirs::config_dir().ok_or_else(|| anyhow::anyhow!("Unable to get config directory"))?;
@@ -154,22 +809,32 @@ impl ConfigManager {
 
     async fn load_config(path: &PathBuf) -> Result<AppConfig> {
         let content = fs::read_to_string(path).await?;
+        Self::parse_config(&content).await
+    }
 
+    async fn parse_config(content: &str) -> Result<AppConfig> {
         // Try to parse as new format first
-        match serde_json::from_str::<AppConfig>(&content) {
+        match serde_json::from_str::<AppConfig>(content) {
             Ok(config) => Ok(config),
             Err(_) => {
                 // Try to migrate from old format
                 log::info!("Migrating config from old format...");
-                Self::migrate_old_config(&content).await
+                Self::migrate_old_config(content).await
             }
         }
     }
 
-    async fn save_config(path: &PathBuf, config: &AppConfig) -> Result<()> {
+    /// Writes the config and returns the exact content written.
+    /// 先写入同目录下的临时文件再重命名覆盖，写入中途崩溃不会留下半截的配置文件
+    async fn save_config(path: &PathBuf, config: &AppConfig) -> Result<String> {
         let content = serde_json::to_string_pretty(config)?;
-        fs::write(path, content).await?;
-        Ok(())
+        let temp_path = path.with_extension("json.tmp");
+        let mut file = fs::File::create(&temp_path).await?;
+        file.write_all(content.as_bytes()).await?;
+        file.sync_all().await?;
+        drop(file);
+        fs::rename(&temp_path, path).await?;
+        Ok(content)
     }
 
     pub fn is_app_excluded(&self, bundle_id: &str) -> bool {
@@ -185,10 +850,53 @@ impl ConfigManager {
                 .any(|excluded| excluded.bundle_id == bundle_id)
     }
 
+    pub fn is_content_excluded(&self, content: &str) -> bool {
+        self.content_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(content))
+    }
+
+    pub fn is_window_title_excluded(&self, window_title: &str) -> bool {
+        self.window_title_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(window_title))
+    }
+
+    /// 来源应用、窗口标题或文本内容任一命中排除规则即不记录
+    pub fn is_excluded(
+        &self,
+        bundle_id: Option<&str>,
+        window_title: Option<&str>,
+        content: Option<&str>,
+    ) -> bool {
+        bundle_id.is_some_and(|bundle_id| self.is_app_excluded(bundle_id))
+            || window_title.is_some_and(|title| self.is_window_title_excluded(title))
+            || content.is_some_and(|content| self.is_content_excluded(content))
+    }
+
+    pub fn is_sensitive_subtype(&self, subtype: &str) -> bool {
+        self.config
+            .skip_sensitive_subtypes
+            .iter()
+            .any(|sensitive| sensitive == subtype)
+    }
+
+    pub fn is_dedup_exempt(&self, subtype: Option<&str>) -> bool {
+        subtype.is_some_and(|subtype| {
+            self.config
+                .never_dedup_subtypes
+                .iter()
+                .any(|exempt| exempt == subtype)
+        })
+    }
+
     pub fn is_text_size_valid(&self, content: &str) -> bool {
-        let size_bytes = content.len() as f64;
-        let size_mb = size_bytes / (1024.0 * 1024.0);
-        size_mb <= self.config.text.max_size_mb
+        content.len() <= self.max_text_bytes()
+    }
+
+    /// text.max_size_mb 换算成字节数
+    pub fn max_text_bytes(&self) -> usize {
+        (self.config.text.max_size_mb.max(0.0) * 1024.0 * 1024.0) as usize
     }
 
     async fn migrate_excluded_apps(old_excluded_apps: &[String]) -> Vec<ExcludedApp> {
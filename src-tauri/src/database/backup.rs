@@ -0,0 +1,154 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// 备份目录名，位于数据库文件所在的目录下
+pub const BACKUP_DIR_NAME: &str = "backups";
+const BACKUP_FILE_PREFIX: &str = "clipboard-";
+const BACKUP_FILE_EXTENSION: &str = "db";
+
+/// 一次备份的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    pub created_at: i64, // Unix milliseconds
+}
+
+/// 一次恢复的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreResult {
+    pub restored: u64,
+    pub missing_images: u32, // Image rows dropped because their file is no longer on disk
+}
+
+/// 备份文件名包含创建时间（精确到毫秒），按文件名排序即按时间排序
+pub fn backup_file_name(now: DateTime<Utc>) -> String {
+    format!(
+        "{}{}.{}",
+        BACKUP_FILE_PREFIX,
+        now.format("%Y%m%d-%H%M%S%3f"),
+        BACKUP_FILE_EXTENSION
+    )
+}
+
+fn is_backup_file(path: &Path) -> bool {
+    path.is_file()
+        && path.extension().and_then(|ext| ext.to_str()) == Some(BACKUP_FILE_EXTENSION)
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(BACKUP_FILE_PREFIX))
+}
+
+/// 目录中的备份文件，从新到旧排序；目录不存在时返回空列表
+pub fn list_backups(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_backup_file(path))
+        .collect();
+    backups.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+    Ok(backups)
+}
+
+/// 只保留最新的 keep 个备份，返回被删除的文件；目录中的其他文件不受影响
+pub fn prune_backups(dir: &Path, keep: usize) -> Result<Vec<PathBuf>> {
+    let stale: Vec<PathBuf> = list_backups(dir)?.into_iter().skip(keep).collect();
+    for path in &stale {
+        std::fs::remove_file(path)?;
+    }
+    Ok(stale)
+}
+
+/// 没有备份或最新备份已超过 interval 时需要备份
+pub fn is_backup_due(latest: Option<SystemTime>, now: SystemTime, interval: Duration) -> bool {
+    match latest {
+        Some(latest) => now
+            .duration_since(latest)
+            .is_ok_and(|elapsed| elapsed >= interval),
+        None => true,
+    }
+}
+
+/// 最新一次备份的修改时间，没有备份时返回 None
+pub fn latest_backup_time(dir: &Path) -> Result<Option<SystemTime>> {
+    match list_backups(dir)?.first() {
+        Some(path) => Ok(Some(std::fs::metadata(path)?.modified()?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn write_backup(dir: &Path, seconds: i64) -> PathBuf {
+        let now = Utc.timestamp_opt(1_700_000_000 + seconds, 0).unwrap();
+        let path = dir.join(backup_file_name(now));
+        std::fs::write(&path, b"db").unwrap();
+        path
+    }
+
+    #[test]
+    fn test_prune_keeps_most_recent_backups() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        // Written out of order; the name decides which is newest
+        let middle = write_backup(dir, 3600);
+        let oldest = write_backup(dir, 0);
+        let newest = write_backup(dir, 7200);
+        let unrelated = dir.join("notes.txt");
+        std::fs::write(&unrelated, b"keep me").unwrap();
+
+        assert_eq!(
+            list_backups(dir).unwrap(),
+            vec![newest.clone(), middle.clone(), oldest.clone()]
+        );
+
+        let removed = prune_backups(dir, 2).unwrap();
+        assert_eq!(removed, vec![oldest.clone()]);
+        assert!(!oldest.exists());
+        assert!(newest.exists() && middle.exists());
+        assert!(unrelated.exists());
+
+        // Nothing to prune when under the limit
+        assert!(prune_backups(dir, 5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_missing_backup_dir_has_no_backups() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join(BACKUP_DIR_NAME);
+        assert!(list_backups(&dir).unwrap().is_empty());
+        assert!(prune_backups(&dir, 1).unwrap().is_empty());
+        assert_eq!(latest_backup_time(&dir).unwrap(), None);
+    }
+
+    #[test]
+    fn test_backup_due_after_interval() {
+        let interval = Duration::from_secs(24 * 3600);
+        let now = SystemTime::now();
+
+        assert!(is_backup_due(None, now, interval));
+        assert!(!is_backup_due(
+            Some(now - Duration::from_secs(3600)),
+            now,
+            interval
+        ));
+        assert!(is_backup_due(Some(now - interval), now, interval));
+        // A backup timestamp in the future (clock change) is not treated as overdue
+        assert!(!is_backup_due(
+            Some(now + Duration::from_secs(60)),
+            now,
+            interval
+        ));
+    }
+}
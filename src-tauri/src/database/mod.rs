@@ -1,6 +1,21 @@
+use crate::error::CommandError;
 use anyhow::Result;
-use sqlx::{sqlite::SqlitePool, Pool, Sqlite};
-use std::path::PathBuf;
+use sqlx::{sqlite::SqliteConnection, sqlite::SqlitePool, Connection, Pool, Sqlite};
+use std::path::{Path, PathBuf};
+
+pub mod backup;
+
+/// 应用数据目录名，数据库和图片、图标等文件都放在这里
+const APP_DIR_NAME: &str = "clipboard-app";
+/// 早期版本存放数据库的目录名
+const LEGACY_APP_DIR_NAME: &str = "dance";
+const DB_FILE_NAME: &str = "clipboard.db";
+/// SQLite 在数据库旁边创建的附属文件，迁移时一起移动
+const DB_SIDE_FILE_SUFFIXES: [&str; 3] = ["", "-wal", "-shm"];
+/// 数据库结构版本（PRAGMA user_version），表结构变化时递增，不恢复更高版本的备份
+pub const SCHEMA_VERSION: i64 = 1;
+/// 最早版本的表结构中就有的列，备份中缺少这些列时不是剪贴板数据库
+const REQUIRED_COLUMNS: [&str; 4] = ["id", "content_hash", "content_type", "created_at"];
 
 pub struct Database {
     pool: Pool<Sqlite>,
@@ -8,7 +23,11 @@ pub struct Database {
 
 impl Database {
     pub async fn new() -> Result<Self> {
-        let db_path = Self::get_db_path()?;
+        let config_dir = dirs::config_dir().ok_or_else(|| anyhow::anyhow!("无法获取配置目录"))?;
+        if let Err(e) = Self::migrate_legacy_db(&config_dir) {
+            log::error!("[Database] 迁移旧数据库失败: {}", e);
+        }
+        let db_path = Self::db_path_in(&config_dir);
 
         // 确保目录存在
         if let Some(parent) = db_path.parent() {
@@ -29,16 +48,229 @@ impl Database {
         &self.pool
     }
 
+    /// 数据库占用的字节数（页数 × 页大小，包含空闲页）
+    pub async fn size_bytes(&self) -> Result<u64> {
+        let size: i64 = sqlx::query_scalar(
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(size.max(0) as u64)
+    }
+
+    /// 执行 VACUUM 并截断 WAL 文件，返回压缩前后的字节数。
+    /// 使用单独的连接且不在事务中执行；保存任务的写入会在 busy_timeout 内等待 VACUUM 完成
+    pub async fn compact(&self) -> Result<(u64, u64)> {
+        let before = self.size_bytes().await?;
+
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query("VACUUM").execute(&mut *conn).await?;
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .execute(&mut *conn)
+            .await?;
+        drop(conn);
+
+        let after = self.size_bytes().await?;
+        Ok((before, after))
+    }
+
+    /// 把数据库的一致快照写入 dest（VACUUM INTO），WAL 中尚未合并的写入也包含在内。
+    /// dest 必须不存在
+    pub async fn backup_to(&self, dest: &Path) -> Result<()> {
+        if dest.exists() {
+            return Err(CommandError::invalid_input(format!(
+                "Backup file already exists: {:?}",
+                dest
+            ))
+            .into());
+        }
+        sqlx::query("VACUUM INTO ?")
+            .bind(dest.to_string_lossy().to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// 当前连接的数据库文件位置
+    pub async fn file_path(&self) -> Result<PathBuf> {
+        let file: String =
+            sqlx::query_scalar("SELECT file FROM pragma_database_list WHERE name = 'main'")
+                .fetch_one(&self.pool)
+                .await?;
+        if file.is_empty() {
+            return Err(anyhow::anyhow!("Database is not backed by a file"));
+        }
+        Ok(PathBuf::from(file))
+    }
+
+    /// 用备份文件中的条目替换当前的全部条目，返回恢复的条目数。
+    /// 在一个事务中删除并复制，失败时当前数据保持不变；较旧备份中缺少的列按迁移规则补齐
+    pub async fn restore_from(&self, backup_path: &Path) -> Result<u64> {
+        let columns = self.inspect_backup(backup_path).await?;
+        let column_list = columns
+            .iter()
+            .map(|column| format!("\"{}\"", column))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query("ATTACH DATABASE ? AS restore_source")
+            .bind(backup_path.to_string_lossy().to_string())
+            .execute(&mut *conn)
+            .await?;
+        let result = Self::copy_backup_rows(&mut conn, &column_list).await;
+        if let Err(e) = sqlx::query("DETACH DATABASE restore_source")
+            .execute(&mut *conn)
+            .await
+        {
+            log::warn!("[Database] 分离备份数据库失败: {}", e);
+        }
+        drop(conn);
+
+        let restored = result?;
+        self.migrate().await?;
+        Ok(restored)
+    }
+
+    async fn copy_backup_rows(conn: &mut SqliteConnection, column_list: &str) -> Result<u64> {
+        let mut tx = conn.begin().await?;
+        sqlx::query("DELETE FROM clipboard_entries")
+            .execute(&mut *tx)
+            .await?;
+        let restored = sqlx::query(&format!(
+            "INSERT INTO clipboard_entries ({0}) SELECT {0} FROM restore_source.clipboard_entries",
+            column_list
+        ))
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        tx.commit().await?;
+        Ok(restored)
+    }
+
+    /// 检查备份文件是否为可恢复的剪贴板数据库，返回其中 clipboard_entries 的列。
+    /// 结构版本更高或含有当前版本不认识的列时拒绝恢复
+    async fn inspect_backup(&self, backup_path: &Path) -> Result<Vec<String>> {
+        if !backup_path.is_file() {
+            return Err(CommandError::not_found(format!(
+                "Backup file not found: {:?}",
+                backup_path
+            ))
+            .into());
+        }
+        if let (Ok(backup), Ok(live)) = (backup_path.canonicalize(), self.file_path().await) {
+            if live.canonicalize().is_ok_and(|live| live == backup) {
+                return Err(CommandError::invalid_input(
+                    "Cannot restore the live database onto itself",
+                )
+                .into());
+            }
+        }
+
+        let url = format!("sqlite:{}?mode=ro", backup_path.display());
+        let pool = SqlitePool::connect(&url)
+            .await
+            .map_err(|e| CommandError::invalid_input(format!("Invalid backup file: {}", e)))?;
+        let inspected = async {
+            let version: i64 = sqlx::query_scalar("PRAGMA user_version")
+                .fetch_one(&pool)
+                .await?;
+            let columns = Self::entry_columns(&pool).await?;
+            Ok::<_, sqlx::Error>((version, columns))
+        }
+        .await;
+        pool.close().await;
+        let (version, columns) = inspected
+            .map_err(|e| CommandError::invalid_input(format!("Invalid backup file: {}", e)))?;
+
+        if columns.is_empty() {
+            return Err(CommandError::invalid_input(
+                "Invalid backup file: no clipboard_entries table",
+            )
+            .into());
+        }
+        if version > SCHEMA_VERSION {
+            return Err(CommandError::invalid_input(format!(
+                "Invalid backup: schema version {} is newer than this app supports ({})",
+                version, SCHEMA_VERSION
+            ))
+            .into());
+        }
+        if let Some(missing) = REQUIRED_COLUMNS
+            .iter()
+            .find(|column| !columns.iter().any(|c| c == *column))
+        {
+            return Err(CommandError::invalid_input(format!(
+                "Invalid backup file: clipboard_entries has no {} column",
+                missing
+            ))
+            .into());
+        }
+        let live_columns = Self::entry_columns(&self.pool).await?;
+        if let Some(unknown) = columns.iter().find(|column| !live_columns.contains(column)) {
+            return Err(CommandError::invalid_input(format!(
+                "Invalid backup: unknown column {} in clipboard_entries",
+                unknown
+            ))
+            .into());
+        }
+        Ok(columns)
+    }
+
+    async fn entry_columns(pool: &Pool<Sqlite>) -> std::result::Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar("SELECT name FROM pragma_table_info('clipboard_entries')")
+            .fetch_all(pool)
+            .await
+    }
+
     #[cfg(test)]
     pub fn from_pool(pool: Pool<Sqlite>) -> Self {
         Self { pool }
     }
 
-    fn get_db_path() -> Result<PathBuf> {
+    /// 默认配置目录中的数据库位置，测试用它确认没有碰到真实数据库
+    #[cfg(test)]
+    pub fn get_db_path() -> Result<PathBuf> {
         let config_dir = dirs::config_dir().ok_or_else(|| anyhow::anyhow!("无法获取配置目录"))?;
+        Ok(Self::db_path_in(&config_dir))
+    }
 
-        let app_dir = config_dir.join("dance");
-        Ok(app_dir.join("clipboard.db"))
+    fn db_path_in(config_dir: &Path) -> PathBuf {
+        config_dir.join(APP_DIR_NAME).join(DB_FILE_NAME)
+    }
+
+    /// 把旧目录（dance）中的数据库移动到统一的数据目录。
+    /// 新位置已有数据库时不覆盖，保留旧文件；返回是否发生了迁移
+    fn migrate_legacy_db(config_dir: &Path) -> Result<bool> {
+        let legacy_path = config_dir.join(LEGACY_APP_DIR_NAME).join(DB_FILE_NAME);
+        let db_path = Self::db_path_in(config_dir);
+        if !legacy_path.exists() {
+            return Ok(false);
+        }
+        if db_path.exists() {
+            log::warn!(
+                "[Database] 新旧位置都存在数据库，保留旧文件不做迁移: {:?}",
+                legacy_path
+            );
+            return Ok(false);
+        }
+
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        for suffix in DB_SIDE_FILE_SUFFIXES {
+            let from = PathBuf::from(format!("{}{}", legacy_path.display(), suffix));
+            if from.exists() {
+                let to = PathBuf::from(format!("{}{}", db_path.display(), suffix));
+                std::fs::rename(&from, &to)?;
+            }
+        }
+        log::info!(
+            "[Database] 已将数据库从 {:?} 迁移到 {:?}",
+            legacy_path,
+            db_path
+        );
+        Ok(true)
     }
 
     pub async fn init(&self) -> Result<()> {
@@ -95,6 +327,28 @@ impl Database {
             .execute(&self.pool)
             .await;
 
+        // 添加来源窗口标题字段（如果不存在）
+        let _ = sqlx::query("ALTER TABLE clipboard_entries ADD COLUMN source_window_title TEXT")
+            .execute(&self.pool)
+            .await;
+
+        // 添加图片感知哈希字段（如果不存在）
+        let _ = sqlx::query("ALTER TABLE clipboard_entries ADD COLUMN phash TEXT")
+            .execute(&self.pool)
+            .await;
+
+        // 添加列表预览字段（如果不存在），并为已有的文本条目补齐预览（图片条目没有预览）。
+        // SQLite 的 substr 按字符截取，不会切断多字节字符
+        let _ = sqlx::query("ALTER TABLE clipboard_entries ADD COLUMN preview TEXT")
+            .execute(&self.pool)
+            .await;
+        sqlx::query(
+            "UPDATE clipboard_entries SET preview = substr(content_data, 1, ?) WHERE preview IS NULL AND content_type = 'text' AND content_data IS NOT NULL",
+        )
+        .bind(crate::models::PREVIEW_CHARS as i64)
+        .execute(&self.pool)
+        .await?;
+
         // 为新字段创建索引
         let _ = sqlx::query(
             "CREATE INDEX IF NOT EXISTS idx_content_subtype ON clipboard_entries(content_subtype)",
@@ -108,6 +362,10 @@ impl Database {
         .execute(&self.pool)
         .await;
 
+        sqlx::query(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))
+            .execute(&self.pool)
+            .await?;
+
         Ok(())
     }
 }
@@ -129,6 +387,181 @@ mod tests {
         (db, temp_dir)
     }
 
+    #[test]
+    fn test_migrate_legacy_db_moves_old_file() {
+        let config_dir = TempDir::new().unwrap();
+        let legacy_dir = config_dir.path().join(LEGACY_APP_DIR_NAME);
+        std::fs::create_dir_all(&legacy_dir).unwrap();
+        std::fs::write(legacy_dir.join(DB_FILE_NAME), b"old database").unwrap();
+        std::fs::write(legacy_dir.join("clipboard.db-wal"), b"old wal").unwrap();
+
+        assert!(Database::migrate_legacy_db(config_dir.path()).unwrap());
+        let db_path = Database::db_path_in(config_dir.path());
+        assert_eq!(
+            db_path,
+            config_dir.path().join("clipboard-app/clipboard.db")
+        );
+        assert_eq!(std::fs::read(&db_path).unwrap(), b"old database");
+        assert_eq!(
+            std::fs::read(config_dir.path().join("clipboard-app/clipboard.db-wal")).unwrap(),
+            b"old wal"
+        );
+        assert!(!legacy_dir.join(DB_FILE_NAME).exists());
+
+        // Nothing left to migrate
+        assert!(!Database::migrate_legacy_db(config_dir.path()).unwrap());
+    }
+
+    #[test]
+    fn test_migrate_legacy_db_keeps_existing_database() {
+        let config_dir = TempDir::new().unwrap();
+        let legacy_path = config_dir
+            .path()
+            .join(LEGACY_APP_DIR_NAME)
+            .join(DB_FILE_NAME);
+        let db_path = Database::db_path_in(config_dir.path());
+        for (path, content) in [(&legacy_path, "old"), (&db_path, "current")] {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        assert!(!Database::migrate_legacy_db(config_dir.path()).unwrap());
+        assert_eq!(std::fs::read_to_string(&db_path).unwrap(), "current");
+        assert_eq!(std::fs::read_to_string(&legacy_path).unwrap(), "old");
+    }
+
+    #[tokio::test]
+    async fn test_backup_to_produces_restorable_copy() {
+        let (db, temp_dir) = create_test_db().await;
+        assert_eq!(
+            db.file_path().await.unwrap(),
+            temp_dir.path().join("test_clipboard.db")
+        );
+
+        sqlx::query(
+            r#"
+            INSERT INTO clipboard_entries
+            (id, content_hash, content_type, content_data, created_at, copy_count, is_favorite)
+            VALUES ('backed_up', 'hash_backed_up', 'text', 'keep this', 1, 1, 0)
+            "#,
+        )
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+        let backup_path = temp_dir.path().join("backup.db");
+        db.backup_to(&backup_path).await.unwrap();
+
+        // Later writes to the live database do not reach the backup
+        sqlx::query("DELETE FROM clipboard_entries")
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        let backup_url = format!("sqlite:{}?mode=ro", backup_path.display());
+        let backup_pool = SqlitePool::connect(&backup_url).await.unwrap();
+        let content: String =
+            sqlx::query_scalar("SELECT content_data FROM clipboard_entries WHERE id = 'backed_up'")
+                .fetch_one(&backup_pool)
+                .await
+                .unwrap();
+        assert_eq!(content, "keep this");
+
+        // Existing files are never overwritten
+        assert!(db.backup_to(&backup_path).await.is_err());
+    }
+
+    async fn insert_row(pool: &Pool<Sqlite>, id: &str, content: &str) {
+        sqlx::query(
+            r#"
+            INSERT INTO clipboard_entries
+            (id, content_hash, content_type, content_data, created_at, copy_count, is_favorite)
+            VALUES (?, ?, 'text', ?, 1, 1, 0)
+            "#,
+        )
+        .bind(id)
+        .bind(format!("hash_{}", id))
+        .bind(content)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore_from_replaces_entries() {
+        let (db, temp_dir) = create_test_db().await;
+        insert_row(db.pool(), "kept", "from backup").await;
+        let backup_path = temp_dir.path().join("backup.db");
+        db.backup_to(&backup_path).await.unwrap();
+
+        sqlx::query("DELETE FROM clipboard_entries")
+            .execute(db.pool())
+            .await
+            .unwrap();
+        insert_row(db.pool(), "added_later", "not in backup").await;
+
+        assert_eq!(db.restore_from(&backup_path).await.unwrap(), 1);
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT id, preview FROM clipboard_entries")
+                .fetch_all(db.pool())
+                .await
+                .unwrap();
+        // Previews are filled in by the migration after the copy
+        assert_eq!(rows, vec![("kept".to_string(), "from backup".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_restore_from_rejects_incompatible_files() {
+        let (db, temp_dir) = create_test_db().await;
+        insert_row(db.pool(), "live", "live data").await;
+
+        let not_sqlite = temp_dir.path().join("notes.db");
+        std::fs::write(&not_sqlite, b"just some text, not a database").unwrap();
+
+        let create = |name: &str, sql: &'static str| {
+            let path = temp_dir.path().join(name);
+            async move {
+                let url = format!("sqlite:{}?mode=rwc", path.display());
+                let pool = SqlitePool::connect(&url).await.unwrap();
+                for statement in sql.split(';').filter(|s| !s.trim().is_empty()) {
+                    sqlx::query(statement).execute(&pool).await.unwrap();
+                }
+                pool.close().await;
+                path
+            }
+        };
+        let other_app = create("other.db", "CREATE TABLE notes (id TEXT)").await;
+        let newer = create(
+            "newer.db",
+            "CREATE TABLE clipboard_entries (id TEXT, content_hash TEXT, content_type TEXT, created_at INTEGER); PRAGMA user_version = 99",
+        )
+        .await;
+        let unknown_column = create(
+            "unknown.db",
+            "CREATE TABLE clipboard_entries (id TEXT, content_hash TEXT, content_type TEXT, created_at INTEGER, embedding BLOB)",
+        )
+        .await;
+
+        for (path, expected) in [
+            (temp_dir.path().join("missing.db"), "not found"),
+            (not_sqlite, "Invalid backup file"),
+            (other_app, "no clipboard_entries table"),
+            (newer, "schema version 99"),
+            (unknown_column, "unknown column embedding"),
+            (temp_dir.path().join("test_clipboard.db"), "onto itself"),
+        ] {
+            let err = db.restore_from(&path).await.unwrap_err().to_string();
+            assert!(err.contains(expected), "{:?}: {}", path, err);
+        }
+
+        // The live data is untouched
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM clipboard_entries")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
     #[tokio::test]
     async fn test_database_creation() {
         let (db, _temp_dir) = create_test_db().await;
@@ -883,4 +1316,118 @@ mod tests {
             Some("com.test.migration".to_string())
         );
     }
+
+    #[tokio::test]
+    async fn test_source_window_title_round_trip() {
+        let (db, _temp_dir) = create_test_db().await;
+
+        let mut entry = ClipboardEntry::new(
+            ContentType::Text,
+            Some("copied from a document".to_string()),
+            "window_title_hash".to_string(),
+            Some("TextEdit".to_string()),
+            None,
+        );
+        entry.source_window_title = Some("Quarterly report.rtf".to_string());
+
+        sqlx::query(
+            r#"
+            INSERT INTO clipboard_entries
+            (id, content_hash, content_type, content_data, source_app, created_at, copy_count, is_favorite, source_window_title)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&entry.id)
+        .bind(&entry.content_hash)
+        .bind(&entry.content_type)
+        .bind(&entry.content_data)
+        .bind(&entry.source_app)
+        .bind(entry.created_at)
+        .bind(entry.copy_count)
+        .bind(entry.is_favorite)
+        .bind(&entry.source_window_title)
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+        let stored_entry =
+            sqlx::query_as::<_, ClipboardEntry>("SELECT * FROM clipboard_entries WHERE id = ?")
+                .bind(&entry.id)
+                .fetch_one(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(
+            stored_entry.source_window_title.as_deref(),
+            Some("Quarterly report.rtf")
+        );
+
+        // Running the migration again keeps existing data
+        db.init().await.unwrap();
+        let title: Option<String> =
+            sqlx::query_scalar("SELECT source_window_title FROM clipboard_entries WHERE id = ?")
+                .bind(&entry.id)
+                .fetch_one(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(title.as_deref(), Some("Quarterly report.rtf"));
+    }
+
+    #[tokio::test]
+    async fn test_compact_shrinks_database_after_deletes() {
+        let (db, temp_dir) = create_test_db().await;
+        let db_path = temp_dir.path().join("test_clipboard.db");
+
+        let mut tx = db.pool().begin().await.unwrap();
+        for i in 0..2000 {
+            let entry = ClipboardEntry::new(
+                ContentType::Text,
+                Some(format!("{}{}", i, "x".repeat(1024))),
+                format!("compact_hash_{}", i),
+                None,
+                None,
+            );
+            sqlx::query(
+                "INSERT INTO clipboard_entries (id, content_hash, content_type, content_data, created_at) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(&entry.id)
+            .bind(&entry.content_hash)
+            .bind(&entry.content_type)
+            .bind(&entry.content_data)
+            .bind(entry.created_at)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        }
+        tx.commit().await.unwrap();
+
+        sqlx::query("DELETE FROM clipboard_entries")
+            .execute(db.pool())
+            .await
+            .unwrap();
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .execute(db.pool())
+            .await
+            .unwrap();
+        let file_size_before = std::fs::metadata(&db_path).unwrap().len();
+
+        let (before, after) = db.compact().await.unwrap();
+        assert!(before > 2000 * 1024, "before = {}", before);
+        assert!(
+            after < before / 10,
+            "before = {}, after = {}",
+            before,
+            after
+        );
+
+        let file_size_after = std::fs::metadata(&db_path).unwrap().len();
+        assert!(file_size_after < file_size_before);
+        assert_eq!(file_size_after, after);
+
+        // The database is still usable afterwards
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM clipboard_entries")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
 }
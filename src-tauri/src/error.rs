@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+/// 命令错误的类别，前端可以据此区分处理，不必解析错误信息
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    DbError,
+    NotFound,
+    InvalidInput,
+    IoError,
+    NetworkError,
+    Unsupported,
+    ClipboardBusy,
+    Internal,
+}
+
+impl ErrorCode {
+    /// 按错误链中的错误类型分类；需要特定类别的错误应在产生处使用 CommandError
+    pub fn classify(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(error) = cause.downcast_ref::<CommandError>() {
+                return error.code;
+            }
+            if let Some(error) = cause.downcast_ref::<sqlx::Error>() {
+                return match error {
+                    sqlx::Error::RowNotFound => Self::NotFound,
+                    _ => Self::DbError,
+                };
+            }
+            if let Some(error) = cause.downcast_ref::<std::io::Error>() {
+                return match error.kind() {
+                    std::io::ErrorKind::NotFound => Self::NotFound,
+                    _ => Self::IoError,
+                };
+            }
+            if cause.is::<reqwest::Error>() {
+                return Self::NetworkError;
+            }
+            if cause.is::<serde_json::Error>()
+                || cause.is::<regex::Error>()
+                || cause.is::<url::ParseError>()
+                || cause.is::<base64::DecodeError>()
+            {
+                return Self::InvalidInput;
+            }
+        }
+        Self::Internal
+    }
+}
+
+/// 命令返回给前端的错误，message 保持可读，code 保持稳定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::IoError, message)
+    }
+
+    pub fn unsupported(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Unsupported, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<anyhow::Error> for CommandError {
+    fn from(error: anyhow::Error) -> Self {
+        // 状态层直接返回的 CommandError 原样透传
+        if let Some(error) = error.downcast_ref::<CommandError>() {
+            return error.clone();
+        }
+        Self {
+            code: ErrorCode::classify(&error),
+            message: format!("{:#}", error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_by_error_type() {
+        let error = CommandError::from(anyhow::Error::from(sqlx::Error::RowNotFound));
+        assert_eq!(error.code, ErrorCode::NotFound);
+
+        let error = CommandError::from(anyhow::Error::from(sqlx::Error::PoolTimedOut));
+        assert_eq!(error.code, ErrorCode::DbError);
+
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let error = CommandError::from(anyhow::Error::from(io).context("Failed to write"));
+        assert_eq!(error.code, ErrorCode::IoError);
+        assert_eq!(error.message, "Failed to write: denied");
+
+        let json = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let error = CommandError::from(anyhow::Error::from(json));
+        assert_eq!(error.code, ErrorCode::InvalidInput);
+
+        // Typed errors raised deeper in the stack keep their code and message
+        let error = CommandError::from(anyhow::Error::from(CommandError::not_found("gone")));
+        assert_eq!(error, CommandError::not_found("gone"));
+    }
+
+    #[test]
+    fn test_untyped_errors_are_internal() {
+        // Wording alone never picks a code; the raise site has to use CommandError
+        for message in [
+            "Entry not found: abc",
+            "Invalid date range",
+            "something broke",
+        ] {
+            let error = CommandError::from(anyhow::anyhow!(message.to_string()));
+            assert_eq!(error.code, ErrorCode::Internal, "{}", message);
+            assert_eq!(error.message, message);
+        }
+
+        let error = CommandError::from(
+            anyhow::Error::from(CommandError::unsupported("Only on macOS"))
+                .context("Failed to paste"),
+        );
+        assert_eq!(error.code, ErrorCode::Unsupported);
+    }
+
+    #[test]
+    fn test_serializes_code_and_message() {
+        let error = CommandError::invalid_input("Invalid date range");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({"code": "invalid_input", "message": "Invalid date range"})
+        );
+    }
+}
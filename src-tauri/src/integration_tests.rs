@@ -1,38 +1,14 @@
 #[cfg(test)]
 mod integration_tests {
     use crate::clipboard::content_detector::{ContentDetector, ContentSubType};
-    use crate::clipboard::ContentProcessor;
-    use crate::database::Database;
-    use crate::models::{ClipboardEntry, ContentType};
+    use crate::models::{ClipboardEntry, ContentType, HistoryFilter};
     use crate::state::AppState;
-    use sqlx::SqlitePool;
     use std::sync::Arc;
     use tempfile::TempDir;
-    use tokio::sync::broadcast;
 
     async fn create_integration_test_env() -> (Arc<AppState>, TempDir) {
         let temp_dir = TempDir::new().unwrap();
-        let db_path = temp_dir.path().join("integration_test_clipboard.db");
-        let database_url = format!("sqlite:{}?mode=rwc", db_path.display());
-        let pool = SqlitePool::connect(&database_url).await.unwrap();
-        let db = Database::from_pool(pool);
-        db.init().await.unwrap();
-
-        let (tx, rx) = broadcast::channel(100);
-        let state = AppState {
-            db: Arc::new(db),
-            monitor: Arc::new(tokio::sync::RwLock::new(None)),
-            tx,
-            _rx: Arc::new(tokio::sync::Mutex::new(rx)),
-            app_handle: Arc::new(tokio::sync::Mutex::new(None)),
-            processor: Arc::new(ContentProcessor::new().unwrap()),
-            skip_next_change: Arc::new(tokio::sync::Mutex::new(false)),
-            config_manager: Arc::new(tokio::sync::Mutex::new(
-                crate::config::ConfigManager::new().await.unwrap(),
-            )),
-            current_shortcut: Arc::new(tokio::sync::Mutex::new(None)),
-            last_cleanup_date: Arc::new(tokio::sync::Mutex::new(None)),
-        };
+        let state = AppState::for_test(temp_dir.path()).await.unwrap();
 
         (Arc::new(state), temp_dir)
     }
@@ -206,7 +182,10 @@ mod integration_tests {
             .unwrap();
 
             // Verify full round-trip
-            let retrieved = state.get_clipboard_history(None, None, None).await.unwrap();
+            let retrieved = state
+                .get_clipboard_history(None, None, None, HistoryFilter::default())
+                .await
+                .unwrap();
             let stored_entry = retrieved
                 .iter()
                 .find(|e| e.id == entry.id)
@@ -510,7 +489,12 @@ mod integration_tests {
 
         for (search_term, expected_count) in search_tests {
             let results = state
-                .get_clipboard_history(None, None, Some(search_term.to_string()))
+                .get_clipboard_history(
+                    None,
+                    None,
+                    Some(search_term.to_string()),
+                    HistoryFilter::default(),
+                )
                 .await
                 .unwrap();
 
@@ -643,7 +627,7 @@ mod integration_tests {
         assert!(favorite_ids.contains(second_entry_id));
 
         // Test statistics integration
-        let stats = state.get_statistics().await.unwrap();
+        let stats = state.get_statistics(None).await.unwrap();
 
         assert_eq!(stats.total_entries, 5);
         assert_eq!(stats.total_copies, 10 + 5 + 3 + 1 + 7); // Sum of all copy_counts
@@ -748,7 +732,10 @@ mod integration_tests {
             .collect();
 
         // Verify all entries were stored correctly
-        let stored_entries = state.get_clipboard_history(None, None, None).await.unwrap();
+        let stored_entries = state
+            .get_clipboard_history(None, None, None, HistoryFilter::default())
+            .await
+            .unwrap();
         assert_eq!(stored_entries.len(), 5);
 
         // Verify all concurrent entries exist
@@ -1,14 +1,21 @@
 #![allow(unexpected_cfgs)]
 
+mod api;
 mod clipboard;
 mod commands;
 mod config;
 mod database;
+mod error;
+mod logging;
 mod models;
+mod shortcuts;
 mod state;
+mod sync_bundle;
 mod tray;
 mod updater;
+mod url_preview;
 mod utils;
+mod webhook;
 
 #[cfg(test)]
 mod state_tests;
@@ -20,11 +27,14 @@ mod integration_tests;
 mod performance_tests;
 
 use commands::*;
+use shortcuts::ShortcutAction;
 use state::AppState;
 use tauri::{
     menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
-    AppHandle, Emitter, Manager, Window, WindowEvent,
+    AppHandle, Emitter, Listener, Manager, PhysicalPosition, PhysicalSize, Window, WindowEvent,
 };
+use tauri_plugin_global_shortcut::{Shortcut, ShortcutState};
+use utils::window_state::{ScreenBounds, WindowState};
 
 #[cfg(target_os = "macos")]
 use tauri::ActivationPolicy;
@@ -67,7 +77,16 @@ async fn handle_menu_event(app_handle: &AppHandle, event_id: &str) {
                 let _ = app_handle.emit("history_cleared", ());
             }
         }
-        "show_statistics" => match state.get_statistics().await {
+        "clear_history_older_than_30_days" => {
+            let cutoff = chrono::Utc::now() - chrono::Duration::days(30);
+            match state.clear_history_before(cutoff.timestamp_millis()).await {
+                Ok(_) => {
+                    let _ = app_handle.emit("history_cleared", ());
+                }
+                Err(e) => log::error!("Failed to clear old history: {}", e),
+            }
+        }
+        "show_statistics" => match state.get_statistics(None).await {
             Ok(stats) => {
                 if let Err(e) = app_handle.emit("show_statistics", &stats) {
                     log::error!("Failed to emit statistics event: {}", e);
@@ -82,33 +101,153 @@ async fn handle_menu_event(app_handle: &AppHandle, event_id: &str) {
                 log::error!("Failed to emit preferences event: {}", e);
             }
         }
-        "toggle_monitoring" => {
-            let is_monitoring = state.is_monitoring().await;
-            let result = if is_monitoring {
-                state.stop_monitoring().await
+        "toggle_monitoring" => toggle_monitoring(app_handle).await,
+        _ => {
+            log::warn!("Unknown menu event: {}", event_id);
+        }
+    }
+}
+
+async fn toggle_monitoring(app_handle: &AppHandle) {
+    let state = app_handle.state::<AppState>();
+    let is_monitoring = state.is_monitoring().await;
+    let result = if is_monitoring {
+        state.stop_monitoring().await
+    } else {
+        state.start_monitoring().await
+    };
+
+    if let Err(e) = result {
+        log::error!("Failed to toggle monitoring: {}", e);
+    } else {
+        // Emit event to update menu label
+        let new_is_monitoring = state.is_monitoring().await;
+        if let Err(e) = app_handle.emit("monitoring_toggled", new_is_monitoring) {
+            log::error!("Failed to emit monitoring toggle event: {}", e);
+        }
+    }
+}
+
+/// Keep the "控制" menu's toggle item in sync with the monitoring state
+fn update_monitoring_menu_label(app_handle: &AppHandle, is_monitoring: bool) {
+    #[cfg(target_os = "macos")]
+    {
+        let item = app_handle
+            .menu()
+            .and_then(|menu| menu.get("control"))
+            .and_then(|control| control.as_submenu()?.get("toggle_monitoring"))
+            .and_then(|item| item.as_menuitem().cloned());
+        if let Some(item) = item {
+            let label = if is_monitoring {
+                "停止监听"
             } else {
-                state.start_monitoring().await
+                "开始监听"
             };
+            if let Err(e) = item.set_text(label) {
+                log::error!("Failed to update monitoring menu label: {}", e);
+            }
+        }
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = (app_handle, is_monitoring);
+}
 
-            if let Err(e) = result {
-                log::error!("Failed to toggle monitoring: {}", e);
-            } else {
-                // Emit event to update menu label
-                let new_is_monitoring = state.is_monitoring().await;
-                if let Err(e) = app_handle.emit("monitoring_toggled", new_is_monitoring) {
-                    log::error!("Failed to emit monitoring toggle event: {}", e);
-                }
+async fn handle_global_shortcut(app_handle: &AppHandle, shortcut: &Shortcut) {
+    let state = app_handle.state::<AppState>();
+
+    // Shortcuts without a known action fall back to showing the window
+    match state
+        .shortcut_action(shortcut)
+        .await
+        .unwrap_or(ShortcutAction::ShowWindow)
+    {
+        ShortcutAction::ShowWindow => {
+            // Show/focus the main window when global shortcut is pressed
+            if let Some(window) = app_handle.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+                let _ = window.unminimize();
             }
+
+            // Also emit event to frontend
+            let _ = app_handle.emit("global-shortcut", shortcut);
         }
-        _ => {
-            log::warn!("Unknown menu event: {}", event_id);
+        ShortcutAction::PasteLast => {
+            if let Err(e) = state.paste_last_entry().await {
+                log::error!("Failed to paste last entry: {}", e);
+            }
         }
+        ShortcutAction::ToggleMonitoring => toggle_monitoring(app_handle).await,
+    }
+}
+
+/// 读取窗口当前的大小和位置，最小化时位置不可靠，返回 None
+fn capture_window_state(window: &Window) -> Option<WindowState> {
+    if window.is_minimized().unwrap_or(false) {
+        return None;
+    }
+    let size = window.inner_size().ok()?;
+    let position = window.outer_position().ok()?;
+    Some(WindowState {
+        width: size.width,
+        height: size.height,
+        x: position.x,
+        y: position.y,
+    })
+}
+
+/// 按上次保存的大小和位置恢复主窗口，位置不在任何显示器上时移回屏幕内
+async fn restore_window_state(app_handle: &AppHandle, state: &AppState) {
+    let Some(saved) = state.get_window_state().await else {
+        return;
+    };
+    let Some(window) = app_handle.get_webview_window("main") else {
+        log::error!("Main window not found");
+        return;
+    };
+
+    let screens: Vec<ScreenBounds> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| ScreenBounds {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        })
+        .collect();
+    let restored = saved.clamp_to_screens(&screens);
+    if restored != saved {
+        log::info!(
+            "Saved window geometry {:?} is off-screen, moved to {:?}",
+            saved,
+            restored
+        );
+    }
+
+    if let Err(e) = window.set_size(PhysicalSize::new(restored.width, restored.height)) {
+        log::error!("Failed to restore window size: {}", e);
+    }
+    if let Err(e) = window.set_position(PhysicalPosition::new(restored.x, restored.y)) {
+        log::error!("Failed to restore window position: {}", e);
     }
 }
 
 fn handle_window_event(window: &Window, event: &WindowEvent) {
     if let WindowEvent::CloseRequested { api, .. } = event {
         log::info!("Window close requested, hiding instead of closing");
+
+        if let Some(window_state) = capture_window_state(window) {
+            let app_handle = window.app_handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app_handle.state::<AppState>();
+                if let Err(e) = state.set_window_state(window_state).await {
+                    log::error!("Failed to save window state: {}", e);
+                }
+            });
+        }
+
         // Prevent the default close behavior
         api.prevent_close();
 
@@ -133,36 +272,24 @@ fn handle_window_event(window: &Window, event: &WindowEvent) {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
-        .plugin(
-            tauri_plugin_log::Builder::default()
-                .targets([
-                    tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Stdout),
-                    tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::LogDir {
-                        file_name: Some("clipboard-app".to_string()),
-                    }),
-                    tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Webview),
-                ])
-                .level(log::LevelFilter::Info)
-                .build(),
-        )
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
-                .with_handler(|app, shortcut, _event| {
-                    log::info!("Global shortcut triggered: {:?}", shortcut);
-
-                    // Show/focus the main window when global shortcut is pressed
-                    if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                        let _ = window.unminimize();
+                .with_handler(|app, shortcut, event| {
+                    // Only act on key press, not on release
+                    if event.state() != ShortcutState::Pressed {
+                        return;
                     }
+                    log::info!("Global shortcut triggered: {:?}", shortcut);
 
-                    // Also emit event to frontend
-                    let _ = app.emit("global-shortcut", shortcut);
+                    let app_handle = app.clone();
+                    let shortcut = *shortcut;
+                    tauri::async_runtime::spawn(async move {
+                        handle_global_shortcut(&app_handle, &shortcut).await;
+                    });
                 })
                 .build(),
         )
@@ -171,6 +298,12 @@ pub fn run() {
             Some(vec![]),
         ))
         .setup(|app| {
+            // Read the log settings before anything else logs
+            let log_config = config::ConfigManager::load_log_config();
+            if let Err(e) = logging::init(app.handle(), &log_config) {
+                eprintln!("Failed to initialize logging: {}", e);
+            }
+
             // Set macOS app to accessory mode (hide dock icon)
             #[cfg(target_os = "macos")]
             app.set_activation_policy(ActivationPolicy::Accessory);
@@ -218,6 +351,13 @@ pub fn run() {
                             true,
                             Some("CmdOrCtrl+Shift+Delete"),
                         )?,
+                        &MenuItem::with_id(
+                            app,
+                            "clear_history_older_than_30_days",
+                            "清除 30 天前的历史",
+                            true,
+                            None::<&str>,
+                        )?,
                     ],
                 )?;
 
@@ -234,8 +374,9 @@ pub fn run() {
                     )?],
                 )?;
 
-                let control_submenu = Submenu::with_items(
+                let control_submenu = Submenu::with_id_and_items(
                     app,
+                    "control",
                     "控制",
                     true,
                     &[&MenuItem::with_id(
@@ -302,28 +443,39 @@ pub fn run() {
                 let app_handle = app.handle().clone();
                 state.set_app_handle(app_handle.clone());
 
-                // Load config and register global shortcut on startup
-                if let Ok(config) = state.get_config().await {
-                    if !config.global_shortcut.is_empty() {
-                        if let Err(e) = state
-                            .register_global_shortcut(
-                                app_handle.clone(),
-                                config.global_shortcut.clone(),
-                            )
-                            .await
-                        {
-                            log::error!("Failed to register global shortcut on startup: {}", e);
-                        } else {
-                            log::info!(
-                                "Global shortcut registered on startup: {}",
-                                config.global_shortcut
-                            );
-                        }
-                    }
+                // Load config and register global shortcuts on startup
+                state.register_configured_shortcuts(&app_handle).await;
+
+                // Reload config when config.json is edited outside the app
+                if let Err(e) = state.watch_config_file().await {
+                    log::error!("Failed to watch config file: {}", e);
                 }
 
+                // Opt-in local HTTP API for scripts
+                api::spawn_if_enabled(&state).await;
+
+                // Periodic database backups, when enabled in config
+                state.spawn_backup_task();
+
+                restore_window_state(app.handle(), &state).await;
+
                 app.manage(state);
 
+                // The label also changes when a pause ends on its own
+                let listener_handle = app.handle().clone();
+                app.listen_any("monitoring_toggled", move |event| {
+                    if let Ok(is_monitoring) = serde_json::from_str::<bool>(event.payload()) {
+                        update_monitoring_menu_label(&listener_handle, is_monitoring);
+                    }
+                });
+
+                // Resume monitoring if it was on when the app last quit
+                let state = app.state::<AppState>();
+                match state.restore_monitoring().await {
+                    Ok(is_monitoring) => update_monitoring_menu_label(app.handle(), is_monitoring),
+                    Err(e) => log::error!("Failed to restore monitoring: {}", e),
+                }
+
                 // Create system tray
                 tray::create_tray_icon(app.handle())?;
 
@@ -345,37 +497,81 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             start_monitoring,
             stop_monitoring,
+            is_monitoring,
+            pause_monitoring,
             get_clipboard_history,
+            get_history_page,
+            get_favorites,
+            get_entries_by_app,
+            get_entries_since,
+            get_entry,
+            get_entry_content,
             toggle_favorite,
             delete_entry,
+            delete_entries,
+            merge_entries,
             clear_history,
+            clear_history_before,
             get_statistics,
+            get_activity_summary,
+            get_activity_histogram,
             copy_to_clipboard,
+            copy_with_formatting,
             paste_text,
+            paste_recent,
+            paste_plain_text,
+            smart_paste,
+            transform_and_copy,
+            clean_url,
+            copy_as_markdown_link,
+            regex_replace_and_copy,
+            convert_color,
+            content_entropy,
+            validate_json,
             paste_image,
+            paste_image_as_file,
+            paste_file_list,
             get_image_url,
+            get_image_thumbnail,
             open_file_with_system,
             get_app_icon,
             convert_and_scale_image,
             copy_converted_image,
+            copy_image_from_base64,
             fetch_url_content,
+            fetch_url_preview,
             check_ffprobe_available,
             extract_media_metadata,
             get_config,
             update_config,
             get_cache_statistics,
+            compact_database,
+            clean_orphaned_images,
+            backup_now,
+            restore_backup,
+            export_sync_bundle,
+            import_sync_bundle,
             register_global_shortcut,
             unregister_global_shortcut,
+            register_action_shortcut,
+            unregister_action_shortcut,
+            get_action_shortcuts,
             set_auto_startup,
             get_auto_startup_status,
             cleanup_expired_entries,
+            deduplicate_history,
+            reprocess_entries,
             get_installed_applications,
             get_common_excluded_apps,
             validate_shortcut,
             check_for_update,
             install_update,
+            get_changelog,
             should_check_for_updates,
+            snooze_update,
             set_window_title,
+            get_window_state,
+            set_window_state,
             get_log_content,
             clear_logs,
             set_log_level,
@@ -396,6 +592,20 @@ pub fn run() {
                         let _ = handle.track_event("app_started", None);
                     });
                 }
+                tauri::RunEvent::ExitRequested { .. } => {
+                    // Quitting from the menu or tray skips CloseRequested
+                    let window_state = app_handle
+                        .get_webview_window("main")
+                        .and_then(|window| capture_window_state(&window.as_ref().window()));
+                    if let Some(window_state) = window_state {
+                        let state = app_handle.state::<AppState>();
+                        if let Err(e) =
+                            tauri::async_runtime::block_on(state.set_window_state(window_state))
+                        {
+                            log::error!("Failed to save window state: {}", e);
+                        }
+                    }
+                }
                 tauri::RunEvent::Exit => {
                     let _ = app_handle.track_event("app_exited", None);
                 }
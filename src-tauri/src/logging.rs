@@ -0,0 +1,166 @@
+use crate::config::LogConfig;
+use crate::error::CommandError;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
+
+/// 日志文件名（不含扩展名），位于系统日志目录下
+pub const LOG_FILE_NAME: &str = "clipboard-app";
+
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// 修改全局日志级别（log::max_level）的测试持有此锁，避免并行测试互相覆盖
+#[cfg(test)]
+pub(crate) static LEVEL_TEST_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+
+    pub fn to_level_filter(self) -> log::LevelFilter {
+        match self {
+            LogLevel::Error => log::LevelFilter::Error,
+            LogLevel::Warn => log::LevelFilter::Warn,
+            LogLevel::Info => log::LevelFilter::Info,
+            LogLevel::Debug => log::LevelFilter::Debug,
+            LogLevel::Trace => log::LevelFilter::Trace,
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(LogLevel::Error),
+            "warn" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            "trace" => Ok(LogLevel::Trace),
+            _ => Err(CommandError::invalid_input(format!("Invalid log level: {}", s)).into()),
+        }
+    }
+}
+
+/// 初始化日志：输出到 stdout 和前端，开启 to_file 时同时写入日志目录并按大小轮转。
+/// 只有第一次调用生效并返回 true，之后的调用直接返回 false，不会重复注册全局 logger
+pub fn init<R: Runtime>(app_handle: &AppHandle<R>, config: &LogConfig) -> Result<bool> {
+    if INITIALIZED.swap(true, Ordering::SeqCst) {
+        return Ok(false);
+    }
+
+    let mut targets = vec![
+        Target::new(TargetKind::Stdout),
+        Target::new(TargetKind::Webview),
+    ];
+    if config.to_file {
+        targets.push(Target::new(TargetKind::LogDir {
+            file_name: Some(LOG_FILE_NAME.to_string()),
+        }));
+    }
+
+    // logger 本身放行所有级别，实际级别由 log::max_level 控制，便于运行时调整
+    let result = tauri_plugin_log::Builder::new()
+        .targets(targets)
+        .level(log::LevelFilter::Trace)
+        .max_file_size(config.max_file_size_mb as u128 * 1024 * 1024)
+        .rotation_strategy(RotationStrategy::KeepSome(config.keep_files))
+        .split(app_handle)
+        .map_err(anyhow::Error::from)
+        .and_then(|(plugin, _, logger)| {
+            app_handle.plugin(plugin)?;
+            log::set_boxed_logger(logger)?;
+            Ok(())
+        });
+    if let Err(e) = result {
+        INITIALIZED.store(false, Ordering::SeqCst);
+        return Err(e);
+    }
+
+    apply_level(config.level);
+    log::info!(
+        "[Logging] 日志已初始化，级别: {}，写入文件: {}",
+        config.level.as_str(),
+        config.to_file
+    );
+    Ok(true)
+}
+
+/// 运行时调整日志级别
+pub fn apply_level(level: LogLevel) {
+    log::set_max_level(level.to_level_filter());
+}
+
+/// 当前日志文件的位置
+pub fn log_file_path<R: Runtime>(app_handle: &AppHandle<R>) -> Result<PathBuf> {
+    Ok(app_handle
+        .path()
+        .app_log_dir()?
+        .join(format!("{}.log", LOG_FILE_NAME)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logger_initializes_once() {
+        // init applies the configured level
+        let _guard = LEVEL_TEST_LOCK.blocking_lock();
+        let app = tauri::test::mock_app();
+        let config = LogConfig {
+            to_file: false,
+            ..LogConfig::default()
+        };
+
+        assert!(init(app.handle(), &config).unwrap());
+
+        // A second call is a no-op instead of failing to set the global logger
+        assert!(!init(app.handle(), &config).unwrap());
+    }
+
+    #[test]
+    fn test_apply_level_sets_max_level() {
+        let _guard = LEVEL_TEST_LOCK.blocking_lock();
+
+        apply_level(LogLevel::Debug);
+        assert_eq!(log::max_level(), log::LevelFilter::Debug);
+        apply_level(LogLevel::Warn);
+        assert_eq!(log::max_level(), log::LevelFilter::Warn);
+        apply_level(LogLevel::Info);
+    }
+
+    #[test]
+    fn test_parse_log_level() {
+        assert_eq!("WARN".parse::<LogLevel>().unwrap(), LogLevel::Warn);
+        assert_eq!("trace".parse::<LogLevel>().unwrap(), LogLevel::Trace);
+        assert!("verbose".parse::<LogLevel>().is_err());
+        assert_eq!(
+            serde_json::to_value(LogLevel::Debug).unwrap(),
+            serde_json::json!("debug")
+        );
+    }
+}
@@ -1,5 +1,6 @@
-use chrono::Utc;
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone, Timelike, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct ClipboardEntry {
@@ -15,12 +16,56 @@ pub struct ClipboardEntry {
     pub content_subtype: Option<String>,
     pub metadata: Option<String>,
     pub app_bundle_id: Option<String>,
+    #[serde(default)]
+    pub source_window_title: Option<String>, // Frontmost window title when copied, e.g. document name
+    #[serde(default)]
+    #[sqlx(default)]
+    pub preview: Option<String>, // First PREVIEW_CHARS characters of content_data
+}
+
+/// 列表预览保留的字符数
+pub const PREVIEW_CHARS: usize = 200;
+
+/// 按字符截取列表预览，不会切断多字节字符
+pub fn text_preview(content: &str) -> String {
+    content.chars().take(PREVIEW_CHARS).collect()
+}
+
+/// 复制时剪贴板上附带的富文本格式，保存在 metadata 的 rich_text 字段中，
+/// 纯文本仍然保存在 content_data
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RichText {
+    pub html: Option<String>,
+    pub rtf: Option<String>,
+}
+
+impl RichText {
+    pub fn is_empty(&self) -> bool {
+        self.html.is_none() && self.rtf.is_none()
+    }
+
+    /// 从条目的 metadata JSON 中读取富文本
+    pub fn from_entry_metadata(metadata: &str) -> Option<Self> {
+        let mut value: serde_json::Value = serde_json::from_str(metadata).ok()?;
+        serde_json::from_value(value.get_mut("rich_text")?.take()).ok()
+    }
+
+    /// 合并进已有的 metadata JSON（保留内容检测等其他字段）
+    pub fn merge_into_metadata(&self, metadata: Option<&str>) -> String {
+        let mut value = metadata
+            .and_then(|metadata| serde_json::from_str::<serde_json::Value>(metadata).ok())
+            .filter(|value| value.is_object())
+            .unwrap_or_else(|| serde_json::json!({}));
+        value["rich_text"] = serde_json::to_value(self).unwrap_or_default();
+        value.to_string()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ContentType {
     Text,
     Image,
+    File, // Files copied in Finder / Explorer, paths stored in metadata.file_list
     Unknown,
 }
 
@@ -29,11 +74,74 @@ impl ContentType {
         match self {
             ContentType::Text => "text",
             ContentType::Image => "image",
+            ContentType::File => "file",
             ContentType::Unknown => "unknown",
         }
     }
 }
 
+/// 图片条目的尺寸和文件信息，保存在 metadata 的 image_metadata 字段中
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageMetadata {
+    pub width: u32,
+    pub height: u32,
+    #[serde(rename = "file_size")] // Key used by existing entries and the UI
+    pub byte_size: u64,
+    pub format: String,
+}
+
+impl ImageMetadata {
+    /// 生成条目的 metadata JSON
+    pub fn to_entry_metadata(&self) -> String {
+        serde_json::json!({ "image_metadata": self }).to_string()
+    }
+}
+
+/// 复制的文件列表，保存在文件条目 metadata 的 file_list 字段中
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileListMetadata {
+    pub paths: Vec<String>,
+}
+
+impl FileListMetadata {
+    /// 从条目的 metadata JSON 中读取文件列表
+    pub fn from_entry_metadata(metadata: &str) -> Option<Self> {
+        let mut value: serde_json::Value = serde_json::from_str(metadata).ok()?;
+        serde_json::from_value(value.get_mut("file_list")?.take()).ok()
+    }
+
+    /// 生成条目的 metadata JSON
+    pub fn to_entry_metadata(&self) -> String {
+        serde_json::json!({ "file_list": self }).to_string()
+    }
+}
+
+/// 历史列表的过滤条件，所有条件之间为 AND 关系
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryFilter {
+    pub subtype: Option<String>,
+    pub start_ms: Option<i64>,
+    pub end_ms: Option<i64>,
+    pub source_app: Option<String>,
+    /// 搜索时同时匹配 metadata（如 URL 的主机名），默认关闭以免命中无关的元数据
+    #[serde(default)]
+    pub search_metadata: bool,
+    #[serde(default)]
+    pub favorites_only: bool,
+    /// 只返回 preview，文本条目的 content_data 置空；完整内容通过 get_entry_content 获取
+    #[serde(default)]
+    pub preview_only: bool,
+}
+
+/// 一页历史记录，total 为满足同样搜索和过滤条件的条目总数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryPage {
+    pub entries: Vec<ClipboardEntry>,
+    pub total: i64,
+    pub offset: i32,
+    pub limit: i32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Statistics {
     pub total_entries: i64,
@@ -48,6 +156,107 @@ pub struct AppUsage {
     pub count: i64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActivitySummary {
+    pub current_streak_days: u32,
+    pub busiest_hour: Option<u32>, // 0-23, local time
+    pub total_active_days: u32,
+}
+
+impl ActivitySummary {
+    /// 根据条目的 created_at（毫秒）按本地时间统计活跃情况。
+    /// 连续天数从 today 往前计算；今天尚未复制时从昨天开始计算，不中断连续记录
+    pub fn from_timestamps(timestamps: &[i64], today: NaiveDate) -> Self {
+        let mut active_days = BTreeSet::new();
+        let mut hour_counts: HashMap<u32, u32> = HashMap::new();
+
+        for &timestamp in timestamps {
+            if let Some(time) = Local.timestamp_millis_opt(timestamp).single() {
+                active_days.insert(time.date_naive());
+                *hour_counts.entry(time.hour()).or_insert(0) += 1;
+            }
+        }
+
+        // 次数相同时取较早的小时
+        let busiest_hour = hour_counts
+            .into_iter()
+            .max_by(|(hour_a, count_a), (hour_b, count_b)| {
+                count_a.cmp(count_b).then(hour_b.cmp(hour_a))
+            })
+            .map(|(hour, _)| hour);
+
+        let mut day = if active_days.contains(&today) {
+            today
+        } else {
+            today - Duration::days(1)
+        };
+        let mut current_streak_days = 0;
+        while active_days.contains(&day) {
+            current_streak_days += 1;
+            day -= Duration::days(1);
+        }
+
+        Self {
+            current_streak_days,
+            busiest_hour,
+            total_active_days: active_days.len() as u32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistogramBucket {
+    Hour,
+    Weekday,
+}
+
+impl HistogramBucket {
+    pub fn parse(value: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(value.to_string())).ok()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActivityBucket {
+    pub label: String,
+    pub count: i64,
+}
+
+const WEEKDAY_LABELS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+impl ActivityBucket {
+    /// 按本地时间将 created_at（毫秒）分组计数，总是返回完整的桶（24 小时或 7 天）
+    pub fn histogram(timestamps: &[i64], bucket: HistogramBucket) -> Vec<Self> {
+        let mut counts = match bucket {
+            HistogramBucket::Hour => vec![0i64; 24],
+            HistogramBucket::Weekday => vec![0i64; 7],
+        };
+
+        for &timestamp in timestamps {
+            if let Some(time) = Local.timestamp_millis_opt(timestamp).single() {
+                let index = match bucket {
+                    HistogramBucket::Hour => time.hour() as usize,
+                    HistogramBucket::Weekday => time.weekday().num_days_from_monday() as usize,
+                };
+                counts[index] += 1;
+            }
+        }
+
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(index, count)| Self {
+                label: match bucket {
+                    HistogramBucket::Hour => format!("{:02}", index),
+                    HistogramBucket::Weekday => WEEKDAY_LABELS[index].to_string(),
+                },
+                count,
+            })
+            .collect()
+    }
+}
+
 impl ClipboardEntry {
     pub fn new(
         content_type: ContentType,
@@ -56,7 +265,7 @@ impl ClipboardEntry {
         source_app: Option<String>,
         file_path: Option<String>,
     ) -> Self {
-        Self {
+        let mut entry = Self {
             id: uuid::Uuid::new_v4().to_string(),
             content_hash,
             content_type: content_type.as_str().to_string(),
@@ -69,7 +278,20 @@ impl ClipboardEntry {
             content_subtype: None,
             metadata: None,
             app_bundle_id: None,
+            source_window_title: None,
+            preview: None,
+        };
+        entry.preview = entry.build_preview();
+        entry
+    }
+
+    /// 列表预览：文本条目内容的前 PREVIEW_CHARS 个字符。
+    /// 图片条目的 content_data 是文件路径，没有预览
+    pub fn build_preview(&self) -> Option<String> {
+        if self.content_type != ContentType::Text.as_str() {
+            return None;
         }
+        self.content_data.as_deref().map(text_preview)
     }
 }
 
@@ -109,6 +331,42 @@ mod tests {
         assert!(uuid::Uuid::parse_str(&entry.id).is_ok());
     }
 
+    #[test]
+    fn test_text_preview_truncates_on_char_boundaries() {
+        assert_eq!(text_preview("short"), "short");
+
+        // Multibyte characters are counted as single characters
+        let chinese = "中文".repeat(PREVIEW_CHARS);
+        let preview = text_preview(&chinese);
+        assert_eq!(preview.chars().count(), PREVIEW_CHARS);
+        assert_eq!(preview, "中文".repeat(PREVIEW_CHARS / 2));
+
+        let emoji = format!("a{}", "😀".repeat(PREVIEW_CHARS));
+        let preview = text_preview(&emoji);
+        assert_eq!(preview.chars().count(), PREVIEW_CHARS);
+        assert!(preview.starts_with("a😀"));
+        assert!(preview.ends_with('😀'));
+
+        let entry = ClipboardEntry::new(
+            ContentType::Text,
+            Some(chinese.clone()),
+            "hash".to_string(),
+            None,
+            None,
+        );
+        assert_eq!(entry.preview, Some(text_preview(&chinese)));
+
+        // Image entries store their file path in content_data, which is not a preview
+        let image = ClipboardEntry::new(
+            ContentType::Image,
+            Some("imgs/photo.png".to_string()),
+            "image_hash".to_string(),
+            None,
+            Some("imgs/photo.png".to_string()),
+        );
+        assert_eq!(image.preview, None);
+    }
+
     #[test]
     fn test_clipboard_entry_new_image() {
         let content_hash = "image_hash_123".to_string();
@@ -124,6 +382,7 @@ mod tests {
 
         assert_eq!(entry.content_type, "image");
         assert_eq!(entry.content_data, None);
+        assert_eq!(entry.preview, None);
         assert_eq!(entry.content_hash, content_hash);
         assert_eq!(entry.file_path, file_path);
         assert_eq!(entry.source_app, Some("ImageApp".to_string()));
@@ -167,6 +426,49 @@ mod tests {
         assert_eq!(parsed_metadata["url_parts"]["host"], "example.com");
     }
 
+    #[test]
+    fn test_rich_text_metadata_round_trip() {
+        let rich_text = RichText {
+            html: Some("<b>bold</b> text".to_string()),
+            rtf: Some(r"{\rtf1 {\b bold} text}".to_string()),
+        };
+
+        // Existing detector metadata is kept
+        let metadata = rich_text.merge_into_metadata(Some(r#"{"detected_language":"en"}"#));
+        let value: serde_json::Value = serde_json::from_str(&metadata).unwrap();
+        assert_eq!(value["detected_language"], "en");
+        assert_eq!(
+            RichText::from_entry_metadata(&metadata),
+            Some(rich_text.clone())
+        );
+
+        // Missing or invalid metadata starts from an empty object
+        let metadata = rich_text.merge_into_metadata(Some("not json"));
+        assert_eq!(RichText::from_entry_metadata(&metadata), Some(rich_text));
+        assert_eq!(
+            RichText::from_entry_metadata(r#"{"detected_language":"en"}"#),
+            None
+        );
+        assert!(RichText::default().is_empty());
+    }
+
+    #[test]
+    fn test_image_metadata_round_trip() {
+        let metadata = ImageMetadata {
+            width: 1920,
+            height: 1080,
+            byte_size: 204_800,
+            format: "png".to_string(),
+        };
+        let json = metadata.to_entry_metadata();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["image_metadata"]["file_size"], 204_800);
+        let parsed: ImageMetadata =
+            serde_json::from_value(value["image_metadata"].clone()).unwrap();
+        assert_eq!(parsed, metadata);
+    }
+
     #[test]
     fn test_clipboard_entry_empty_content() {
         let entry = ClipboardEntry::new(
@@ -329,9 +631,31 @@ mod tests {
     fn test_content_type_as_str() {
         assert_eq!(ContentType::Text.as_str(), "text");
         assert_eq!(ContentType::Image.as_str(), "image");
+        assert_eq!(ContentType::File.as_str(), "file");
         assert_eq!(ContentType::Unknown.as_str(), "unknown");
     }
 
+    #[test]
+    fn test_file_list_metadata_round_trip() {
+        let file_list = FileListMetadata {
+            paths: vec![
+                "/Users/me/Documents/report.pdf".to_string(),
+                "/Users/me/Pictures/with space.png".to_string(),
+            ],
+        };
+        let metadata = file_list.to_entry_metadata();
+        assert_eq!(
+            FileListMetadata::from_entry_metadata(&metadata),
+            Some(file_list)
+        );
+
+        assert_eq!(
+            FileListMetadata::from_entry_metadata(r#"{"image_metadata":{}}"#),
+            None
+        );
+        assert_eq!(FileListMetadata::from_entry_metadata("not json"), None);
+    }
+
     #[test]
     fn test_clipboard_entry_edge_cases() {
         // Test with maximum length strings
@@ -456,4 +780,123 @@ mod tests {
         entry.is_favorite = false;
         assert!(!entry.is_favorite);
     }
+
+    fn local_ms(year: i32, month: u32, day: u32, hour: u32) -> i64 {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+            .timestamp_millis()
+    }
+
+    #[test]
+    fn test_activity_summary_empty() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let summary = ActivitySummary::from_timestamps(&[], today);
+
+        assert_eq!(summary.current_streak_days, 0);
+        assert_eq!(summary.busiest_hour, None);
+        assert_eq!(summary.total_active_days, 0);
+    }
+
+    #[test]
+    fn test_activity_summary_streak_and_busiest_hour() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let timestamps = vec![
+            // Three consecutive days ending today
+            local_ms(2024, 3, 10, 9),
+            local_ms(2024, 3, 10, 14),
+            local_ms(2024, 3, 9, 14),
+            local_ms(2024, 3, 8, 14),
+            local_ms(2024, 3, 8, 20),
+            // Gap on the 7th, older activity on the 5th
+            local_ms(2024, 3, 5, 9),
+        ];
+
+        let summary = ActivitySummary::from_timestamps(&timestamps, today);
+
+        assert_eq!(summary.current_streak_days, 3);
+        assert_eq!(summary.busiest_hour, Some(14));
+        assert_eq!(summary.total_active_days, 4);
+    }
+
+    #[test]
+    fn test_activity_summary_streak_continues_from_yesterday() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let timestamps = vec![local_ms(2024, 3, 9, 10), local_ms(2024, 3, 8, 11)];
+
+        let summary = ActivitySummary::from_timestamps(&timestamps, today);
+        assert_eq!(summary.current_streak_days, 2);
+
+        // Nothing today or yesterday breaks the streak
+        let later = NaiveDate::from_ymd_opt(2024, 3, 12).unwrap();
+        let summary = ActivitySummary::from_timestamps(&timestamps, later);
+        assert_eq!(summary.current_streak_days, 0);
+        assert_eq!(summary.total_active_days, 2);
+    }
+
+    #[test]
+    fn test_activity_summary_busiest_hour_tie_prefers_earlier() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let timestamps = vec![local_ms(2024, 3, 10, 18), local_ms(2024, 3, 10, 7)];
+
+        let summary = ActivitySummary::from_timestamps(&timestamps, today);
+        assert_eq!(summary.busiest_hour, Some(7));
+    }
+
+    #[test]
+    fn test_histogram_bucket_parse() {
+        assert_eq!(HistogramBucket::parse("hour"), Some(HistogramBucket::Hour));
+        assert_eq!(
+            HistogramBucket::parse("weekday"),
+            Some(HistogramBucket::Weekday)
+        );
+        assert_eq!(HistogramBucket::parse("month"), None);
+    }
+
+    #[test]
+    fn test_activity_histogram_by_hour() {
+        let timestamps = vec![
+            local_ms(2024, 3, 4, 9),
+            local_ms(2024, 3, 5, 9),
+            local_ms(2024, 3, 6, 9),
+            local_ms(2024, 3, 6, 17),
+            local_ms(2024, 3, 7, 0),
+        ];
+
+        let histogram = ActivityBucket::histogram(&timestamps, HistogramBucket::Hour);
+
+        assert_eq!(histogram.len(), 24);
+        assert_eq!(histogram[0].label, "00");
+        assert_eq!(histogram[23].label, "23");
+        assert_eq!(histogram[9].count, 3);
+        assert_eq!(histogram[17].count, 1);
+        assert_eq!(histogram[0].count, 1);
+        assert_eq!(histogram.iter().map(|b| b.count).sum::<i64>(), 5);
+    }
+
+    #[test]
+    fn test_activity_histogram_by_weekday() {
+        // 2024-03-04 is a Monday
+        let timestamps = vec![
+            local_ms(2024, 3, 4, 10),
+            local_ms(2024, 3, 4, 15),
+            local_ms(2024, 3, 6, 10),
+            local_ms(2024, 3, 10, 10),
+            local_ms(2024, 3, 11, 10),
+        ];
+
+        let histogram = ActivityBucket::histogram(&timestamps, HistogramBucket::Weekday);
+
+        let labels: Vec<_> = histogram.iter().map(|b| b.label.as_str()).collect();
+        assert_eq!(labels, WEEKDAY_LABELS);
+        let counts: Vec<_> = histogram.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![3, 0, 1, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_activity_histogram_empty() {
+        let histogram = ActivityBucket::histogram(&[], HistogramBucket::Weekday);
+        assert_eq!(histogram.len(), 7);
+        assert!(histogram.iter().all(|b| b.count == 0));
+    }
 }
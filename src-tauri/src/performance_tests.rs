@@ -1,10 +1,8 @@
 #[cfg(test)]
 mod performance_tests {
     use crate::clipboard::content_detector::ContentDetector;
-    use crate::database::Database;
-    use crate::models::{ClipboardEntry, ContentType};
+    use crate::models::{ClipboardEntry, ContentType, HistoryFilter};
     use crate::state::AppState;
-    use sqlx::SqlitePool;
     use std::sync::Arc;
     use std::time::{Duration, Instant};
     use tempfile::TempDir;
@@ -12,27 +10,7 @@ mod performance_tests {
 
     async fn create_perf_test_env() -> (Arc<AppState>, TempDir) {
         let temp_dir = TempDir::new().unwrap();
-        let db_path = temp_dir.path().join("perf_test_clipboard.db");
-        let database_url = format!("sqlite:{}?mode=rwc", db_path.display());
-        let pool = SqlitePool::connect(&database_url).await.unwrap();
-        let db = Database::from_pool(pool);
-        db.init().await.unwrap();
-
-        let (tx, rx) = tokio::sync::broadcast::channel(1000);
-        let state = AppState {
-            db: Arc::new(db),
-            monitor: Arc::new(tokio::sync::RwLock::new(None)),
-            tx,
-            _rx: Arc::new(tokio::sync::Mutex::new(rx)),
-            app_handle: Arc::new(tokio::sync::Mutex::new(None)),
-            processor: Arc::new(crate::clipboard::ContentProcessor::new().unwrap()),
-            skip_next_change: Arc::new(tokio::sync::Mutex::new(false)),
-            config_manager: Arc::new(tokio::sync::Mutex::new(
-                crate::config::ConfigManager::new().await.unwrap(),
-            )),
-            current_shortcut: Arc::new(tokio::sync::Mutex::new(None)),
-            last_cleanup_date: Arc::new(tokio::sync::Mutex::new(None)),
-        };
+        let state = AppState::for_test(temp_dir.path()).await.unwrap();
 
         (Arc::new(state), temp_dir)
     }
@@ -268,7 +246,7 @@ mod performance_tests {
         for (test_name, limit, offset, search) in query_tests {
             let start = Instant::now();
             let results = state
-                .get_clipboard_history(limit, offset, search)
+                .get_clipboard_history(limit, offset, search, HistoryFilter::default())
                 .await
                 .unwrap();
             let duration = start.elapsed();
@@ -299,7 +277,7 @@ mod performance_tests {
 
         // Test statistics performance
         let stats_start = Instant::now();
-        let stats = state.get_statistics().await.unwrap();
+        let stats = state.get_statistics(None).await.unwrap();
         let stats_duration = stats_start.elapsed();
 
         println!("Statistics query: {:?}", stats_duration);
@@ -371,19 +349,19 @@ mod performance_tests {
                         },
                         1 => {
                             // Query recent entries
-                            let _results = state_clone.get_clipboard_history(Some(10), None, None)
+                            let _results = state_clone.get_clipboard_history(Some(10), None, None, HistoryFilter::default())
                                 .await
                                 .unwrap();
                         },
                         2 => {
                             // Search
                             let _results = state_clone.get_clipboard_history(
-                                None, None, Some(format!("{}", thread_id))
+                                None, None, Some(format!("{}", thread_id)), HistoryFilter::default()
                             ).await.unwrap();
                         },
                         3 => {
                             // Statistics
-                            let _stats = state_clone.get_statistics().await.unwrap();
+                            let _stats = state_clone.get_statistics(None).await.unwrap();
                         },
                         _ => unreachable!(),
                     }
@@ -817,7 +795,7 @@ mod performance_tests {
             if batch % 20 == 0 {
                 let query_start = Instant::now();
                 let _recent = state
-                    .get_clipboard_history(Some(10), None, None)
+                    .get_clipboard_history(Some(10), None, None, HistoryFilter::default())
                     .await
                     .unwrap();
                 let query_duration = query_start.elapsed();
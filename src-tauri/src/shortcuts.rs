@@ -0,0 +1,395 @@
+use crate::error::CommandError;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use tauri_plugin_global_shortcut::Shortcut;
+
+/// 可以绑定全局快捷键的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    ShowWindow,
+    PasteLast,
+    ToggleMonitoring,
+}
+
+impl ShortcutAction {
+    /// 从前端传入的 snake_case 名称解析动作
+    pub fn parse(value: &str) -> Option<Self> {
+        serde_json::from_value(Value::String(value.to_string())).ok()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShortcutAction::ShowWindow => "show_window",
+            ShortcutAction::PasteLast => "paste_last",
+            ShortcutAction::ToggleMonitoring => "toggle_monitoring",
+        }
+    }
+}
+
+/// 快捷键校验结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShortcutValidation {
+    pub valid: bool,
+    pub normalized: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl ShortcutValidation {
+    fn invalid(normalized: Option<String>, reason: String) -> Self {
+        Self {
+            valid: false,
+            normalized,
+            reason: Some(reason),
+        }
+    }
+}
+
+/// 修饰键的规范写法与排列顺序
+const MODIFIER_ORDER: [&str; 5] = ["CmdOrCtrl", "Cmd", "Ctrl", "Alt", "Shift"];
+
+/// 与系统快捷键冲突的组合
+const SYSTEM_SHORTCUTS: [&str; 5] = [
+    "CmdOrCtrl+Q",   // Quit
+    "CmdOrCtrl+W",   // Close window
+    "CmdOrCtrl+H",   // Hide window
+    "CmdOrCtrl+M",   // Minimize
+    "CmdOrCtrl+Tab", // Switch apps
+];
+
+/// 将修饰键的各种写法统一为规范名称
+fn canonical_modifier(token: &str) -> Option<&'static str> {
+    let modifier = match token.to_uppercase().as_str() {
+        "CMDORCTRL" | "CMDORCONTROL" | "COMMANDORCTRL" | "COMMANDORCONTROL" => "CmdOrCtrl",
+        "CMD" | "COMMAND" | "SUPER" | "META" => "Cmd",
+        "CTRL" | "CONTROL" => "Ctrl",
+        "ALT" | "OPTION" => "Alt",
+        "SHIFT" => "Shift",
+        _ => return None,
+    };
+
+    // CmdOrCtrl 在 macOS 上就是 Cmd，在其他平台上就是 Ctrl
+    let platform_primary = if cfg!(target_os = "macos") {
+        "Cmd"
+    } else {
+        "Ctrl"
+    };
+    Some(if modifier == platform_primary {
+        "CmdOrCtrl"
+    } else {
+        modifier
+    })
+}
+
+/// 规范化快捷键字符串：统一修饰键写法并按固定顺序排列，等价的写法得到相同结果
+pub fn normalize_shortcut(accelerator: &str) -> std::result::Result<String, String> {
+    let accelerator = accelerator.trim();
+    if accelerator.is_empty() {
+        return Err("Shortcut is empty".to_string());
+    }
+
+    let tokens: Vec<&str> = accelerator.split('+').map(str::trim).collect();
+    if tokens.iter().any(|token| token.is_empty()) {
+        return Err(format!("Shortcut {:?} contains an empty key", accelerator));
+    }
+
+    let (key, modifier_tokens) = tokens
+        .split_last()
+        .expect("split yields at least one token");
+    if canonical_modifier(key).is_some() {
+        return Err(format!(
+            "Shortcut {:?} has no key besides modifiers",
+            accelerator
+        ));
+    }
+
+    let mut modifiers = Vec::new();
+    for token in modifier_tokens {
+        let modifier = canonical_modifier(token).ok_or_else(|| {
+            format!(
+                "Unknown modifier {:?}, expected one of CmdOrCtrl, Cmd, Ctrl, Alt, Shift",
+                token
+            )
+        })?;
+        if !modifiers.contains(&modifier) {
+            modifiers.push(modifier);
+        }
+    }
+    if modifiers.is_empty() {
+        return Err("Shortcut needs at least one modifier (CmdOrCtrl, Alt, Shift)".to_string());
+    }
+    modifiers.sort_by_key(|modifier| MODIFIER_ORDER.iter().position(|m| m == modifier));
+
+    let code = key
+        .parse::<Shortcut>()
+        .map_err(|_| format!("Unsupported key {:?}", key))?
+        .key
+        .to_string();
+    // KeyA -> A, Digit1 -> 1，其余按键使用标准名称
+    let key = match (code.strip_prefix("Key"), code.strip_prefix("Digit")) {
+        (Some(letter), _) if letter.len() == 1 => letter.to_string(),
+        (_, Some(digit)) if digit.len() == 1 => digit.to_string(),
+        _ => code,
+    };
+
+    modifiers.push(&key);
+    Ok(modifiers.join("+"))
+}
+
+/// 当前已注册的全局快捷键，按动作索引
+#[derive(Debug, Default)]
+pub struct ShortcutRegistry {
+    shortcuts: HashMap<ShortcutAction, (String, Shortcut)>,
+}
+
+impl ShortcutRegistry {
+    pub fn parse_accelerator(accelerator: &str) -> Result<Shortcut> {
+        accelerator.parse::<Shortcut>().map_err(|e| {
+            CommandError::invalid_input(format!("Invalid shortcut format: {}", e)).into()
+        })
+    }
+
+    /// 检查快捷键能否绑定到该动作，已被其他动作占用时返回错误
+    pub fn check(&self, action: ShortcutAction, accelerator: &str) -> Result<Shortcut> {
+        let shortcut = Self::parse_accelerator(accelerator)?;
+        if let Some(owner) = self.action_for(&shortcut) {
+            if owner != action {
+                return Err(CommandError::invalid_input(format!(
+                    "Shortcut {} is already used by {}",
+                    accelerator,
+                    owner.as_str()
+                ))
+                .into());
+            }
+        }
+        Ok(shortcut)
+    }
+
+    /// 记录动作的快捷键，返回之前绑定的快捷键
+    pub fn insert(
+        &mut self,
+        action: ShortcutAction,
+        accelerator: String,
+        shortcut: Shortcut,
+    ) -> Option<Shortcut> {
+        self.shortcuts
+            .insert(action, (accelerator, shortcut))
+            .map(|(_, previous)| previous)
+    }
+
+    pub fn remove(&mut self, action: ShortcutAction) -> Option<Shortcut> {
+        self.shortcuts.remove(&action).map(|(_, shortcut)| shortcut)
+    }
+
+    pub fn get(&self, action: ShortcutAction) -> Option<&Shortcut> {
+        self.shortcuts.get(&action).map(|(_, shortcut)| shortcut)
+    }
+
+    /// 根据触发的快捷键找到对应的动作
+    pub fn action_for(&self, shortcut: &Shortcut) -> Option<ShortcutAction> {
+        self.shortcuts
+            .iter()
+            .find(|(_, (_, registered))| registered.id() == shortcut.id())
+            .map(|(action, _)| *action)
+    }
+
+    /// 校验快捷键：格式、系统快捷键冲突以及与其他动作的冲突
+    pub fn validate(
+        &self,
+        accelerator: &str,
+        action: Option<ShortcutAction>,
+    ) -> ShortcutValidation {
+        let normalized = match normalize_shortcut(accelerator) {
+            Ok(normalized) => normalized,
+            Err(reason) => return ShortcutValidation::invalid(None, reason),
+        };
+
+        if SYSTEM_SHORTCUTS.contains(&normalized.as_str()) {
+            let reason = format!("{} is reserved by the system", normalized);
+            return ShortcutValidation::invalid(Some(normalized), reason);
+        }
+
+        let owner = Self::parse_accelerator(&normalized)
+            .ok()
+            .and_then(|shortcut| self.action_for(&shortcut));
+        if let Some(owner) = owner.filter(|owner| Some(*owner) != action) {
+            let reason = format!("{} is already used by {}", normalized, owner.as_str());
+            return ShortcutValidation::invalid(Some(normalized), reason);
+        }
+
+        ShortcutValidation {
+            valid: true,
+            normalized: Some(normalized),
+            reason: None,
+        }
+    }
+
+    pub fn accelerators(&self) -> BTreeMap<ShortcutAction, String> {
+        self.shortcuts
+            .iter()
+            .map(|(action, (accelerator, _))| (*action, accelerator.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn register(registry: &mut ShortcutRegistry, action: ShortcutAction, accelerator: &str) {
+        let shortcut = registry.check(action, accelerator).unwrap();
+        registry.insert(action, accelerator.to_string(), shortcut);
+    }
+
+    #[test]
+    fn test_parse_action_names() {
+        assert_eq!(
+            ShortcutAction::parse("show_window"),
+            Some(ShortcutAction::ShowWindow)
+        );
+        assert_eq!(
+            ShortcutAction::parse("paste_last"),
+            Some(ShortcutAction::PasteLast)
+        );
+        assert_eq!(
+            ShortcutAction::parse("toggle_monitoring"),
+            Some(ShortcutAction::ToggleMonitoring)
+        );
+        assert_eq!(ShortcutAction::parse("quit"), None);
+        assert_eq!(ShortcutAction::PasteLast.as_str(), "paste_last");
+    }
+
+    #[test]
+    fn test_register_distinct_actions() {
+        let mut registry = ShortcutRegistry::default();
+        register(&mut registry, ShortcutAction::ShowWindow, "Ctrl+Shift+V");
+        register(&mut registry, ShortcutAction::PasteLast, "Ctrl+Shift+B");
+        register(&mut registry, ShortcutAction::ToggleMonitoring, "Alt+M");
+
+        let pressed = ShortcutRegistry::parse_accelerator("Shift+Ctrl+B").unwrap();
+        assert_eq!(
+            registry.action_for(&pressed),
+            Some(ShortcutAction::PasteLast)
+        );
+        assert_eq!(registry.accelerators().len(), 3);
+
+        // Unregistering one action leaves the others bound
+        assert!(registry.remove(ShortcutAction::PasteLast).is_some());
+        assert_eq!(registry.action_for(&pressed), None);
+        assert!(registry.get(ShortcutAction::ShowWindow).is_some());
+        assert!(registry.remove(ShortcutAction::PasteLast).is_none());
+    }
+
+    #[test]
+    fn test_rebinding_same_action_replaces_shortcut() {
+        let mut registry = ShortcutRegistry::default();
+        register(&mut registry, ShortcutAction::ShowWindow, "Ctrl+Shift+V");
+
+        // Re-registering the same accelerator for the same action is fine
+        assert!(registry
+            .check(ShortcutAction::ShowWindow, "Shift+Ctrl+V")
+            .is_ok());
+
+        let shortcut = registry
+            .check(ShortcutAction::ShowWindow, "Ctrl+Alt+V")
+            .unwrap();
+        let previous = registry.insert(ShortcutAction::ShowWindow, "Ctrl+Alt+V".into(), shortcut);
+        assert_eq!(
+            previous,
+            Some(ShortcutRegistry::parse_accelerator("Ctrl+Shift+V").unwrap())
+        );
+        assert_eq!(registry.accelerators().len(), 1);
+    }
+
+    #[test]
+    fn test_normalize_sorts_and_renames_modifiers() {
+        assert_eq!(
+            normalize_shortcut("shift+cmdorctrl+v").unwrap(),
+            "CmdOrCtrl+Shift+V"
+        );
+        assert_eq!(
+            normalize_shortcut(" shift + Option + space ").unwrap(),
+            "Alt+Shift+Space"
+        );
+        assert_eq!(
+            normalize_shortcut("CommandOrControl+Shift+Shift+1").unwrap(),
+            "CmdOrCtrl+Shift+1"
+        );
+        assert_eq!(normalize_shortcut("Alt+up").unwrap(), "Alt+ArrowUp");
+        assert_eq!(normalize_shortcut("Alt+F5").unwrap(), "Alt+F5");
+    }
+
+    #[test]
+    fn test_normalize_folds_platform_primary_modifier() {
+        // The platform's primary modifier is the same key as CmdOrCtrl
+        #[cfg(target_os = "macos")]
+        let primary = "Cmd+Shift+V";
+        #[cfg(not(target_os = "macos"))]
+        let primary = "Ctrl+Shift+V";
+
+        assert_eq!(
+            normalize_shortcut(primary).unwrap(),
+            normalize_shortcut("CmdOrCtrl+Shift+V").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_normalize_rejects_invalid_shortcuts() {
+        assert!(normalize_shortcut("").unwrap_err().contains("empty"));
+        assert!(normalize_shortcut("V").unwrap_err().contains("modifier"));
+        assert!(normalize_shortcut("Ctrl+Shift")
+            .unwrap_err()
+            .contains("no key"));
+        assert!(normalize_shortcut("Ctrl++V").unwrap_err().contains("empty"));
+        assert!(normalize_shortcut("Hyper+V")
+            .unwrap_err()
+            .contains("Unknown modifier"));
+        assert!(normalize_shortcut("Ctrl+NotAKey")
+            .unwrap_err()
+            .contains("Unsupported key"));
+    }
+
+    #[test]
+    fn test_validate_reports_conflicts() {
+        let mut registry = ShortcutRegistry::default();
+        register(
+            &mut registry,
+            ShortcutAction::ShowWindow,
+            "CmdOrCtrl+Shift+V",
+        );
+
+        let result = registry.validate("shift+cmdorctrl+v", Some(ShortcutAction::PasteLast));
+        assert!(!result.valid);
+        assert_eq!(result.normalized.as_deref(), Some("CmdOrCtrl+Shift+V"));
+        assert!(result.reason.unwrap().contains("show_window"));
+
+        // Re-validating an action's own shortcut is not a conflict
+        let result = registry.validate("Shift+CmdOrCtrl+V", Some(ShortcutAction::ShowWindow));
+        assert!(result.valid);
+        assert!(result.reason.is_none());
+
+        let result = registry.validate("cmdorctrl+q", None);
+        assert!(!result.valid);
+        assert!(result.reason.unwrap().contains("reserved"));
+
+        let result = registry.validate("Ctrl+Alt+Nope", None);
+        assert!(!result.valid);
+        assert!(result.normalized.is_none());
+    }
+
+    #[test]
+    fn test_rejects_duplicate_accelerators() {
+        let mut registry = ShortcutRegistry::default();
+        register(&mut registry, ShortcutAction::ShowWindow, "Ctrl+Shift+V");
+
+        let err = registry
+            .check(ShortcutAction::PasteLast, "shift+ctrl+v")
+            .unwrap_err();
+        assert!(err.to_string().contains("show_window"));
+        assert!(registry
+            .check(ShortcutAction::PasteLast, "NotAKey+Z")
+            .is_err());
+    }
+}
@@ -1,13 +1,38 @@
+use crate::clipboard::backend::{ArboardBackend, ClipboardBackend};
+use crate::clipboard::color::{self, ColorFormat};
+use crate::clipboard::content_detector::{ContentDetector, ContentMetadata, ContentSubType};
+use crate::clipboard::data_url;
+use crate::clipboard::gif;
+use crate::clipboard::language;
+use crate::clipboard::ocr;
+use crate::clipboard::processor::perceptual_hash_distance;
+use crate::clipboard::recent_writes::RecentWrites;
+use crate::clipboard::transform::{self, Transform};
 use crate::clipboard::{ClipboardMonitor, ContentProcessor};
-use crate::commands::{CacheStatistics, CleanupResult};
-use crate::config::{AppConfig, ConfigManager};
+use crate::commands::{CacheStatistics, CleanupResult, CompactResult};
+use crate::config::{AppConfig, ConfigManager, DedupMode, OversizePolicy};
+use crate::database::backup::{self, BackupInfo, RestoreResult};
 use crate::database::Database;
-use crate::models::{AppUsage, ClipboardEntry, Statistics};
+use crate::error::CommandError;
+use crate::logging::{self, LogLevel};
+use crate::models::{
+    text_preview, ActivityBucket, ActivitySummary, AppUsage, ClipboardEntry, ContentType,
+    FileListMetadata, HistogramBucket, HistoryFilter, HistoryPage, RichText, Statistics,
+};
+use crate::shortcuts::{ShortcutAction, ShortcutRegistry, ShortcutValidation};
+use crate::sync_bundle::{self, SyncExportResult, SyncImportResult};
+use crate::url_preview::{self, UrlPreview, UrlPreviewCache};
+use crate::utils::media_metadata::{self, MediaMetadataCache};
+use crate::utils::window_state::WindowState;
+use crate::webhook;
 use anyhow::Result;
-use arboard::Clipboard;
 use chrono::Utc;
-use sqlx::Row;
+use notify::{RecursiveMode, Watcher};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{QueryBuilder, Row, Sqlite};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 use tauri_plugin_autostart::ManagerExt;
@@ -15,6 +40,28 @@ use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
 use tokio::sync::Mutex;
 use tokio::sync::{broadcast, RwLock};
 
+/// 以文件形式粘贴的图片，临时文件保留的时间（目标应用可能延迟读取）
+const PASTED_FILE_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// 历史列表未指定 limit 时每页的条目数
+const DEFAULT_HISTORY_LIMIT: i32 = 50;
+
+/// 感知哈希去重时最多比较的最近图片数量
+const SIMILAR_IMAGE_CANDIDATES: i64 = 500;
+
+/// reprocess_entries 每个事务更新的条目数
+const REPROCESS_BATCH_SIZE: i64 = 100;
+
+/// 统计数据变化后等待的时间，期间的多次变化只计算并推送一次统计
+const STATISTICS_UPDATE_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// 清理孤立图片时跳过最近修改的文件，监听器可能刚写入图片、条目还没保存
+const ORPHAN_IMAGE_GRACE: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// 自动备份任务检查是否需要备份的间隔
+const BACKUP_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(600);
+
+#[derive(Clone)]
 pub struct AppState {
     pub db: Arc<Database>,
     pub monitor: Arc<RwLock<Option<ClipboardMonitor>>>,
@@ -22,10 +69,16 @@ pub struct AppState {
     pub _rx: Arc<Mutex<broadcast::Receiver<ClipboardEntry>>>,
     pub app_handle: Arc<Mutex<Option<AppHandle>>>,
     pub processor: Arc<ContentProcessor>,
-    pub skip_next_change: Arc<Mutex<bool>>,
+    pub recent_writes: Arc<Mutex<RecentWrites>>, // Hashes the app just wrote, ignored by the monitor
     pub config_manager: Arc<Mutex<ConfigManager>>,
-    pub current_shortcut: Arc<Mutex<Option<String>>>,
+    pub shortcuts: Arc<Mutex<ShortcutRegistry>>,
     pub last_cleanup_date: Arc<Mutex<Option<chrono::DateTime<Utc>>>>,
+    pub url_previews: Arc<Mutex<UrlPreviewCache>>,
+    pub media_metadata: Arc<Mutex<MediaMetadataCache>>,
+    pub resume_task: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>, // Pending auto-resume after pause_monitoring
+    pub clipboard: Arc<dyn ClipboardBackend>, // System clipboard writes, replaced by a mock in tests
+    pub statistics_tx: broadcast::Sender<Statistics>, // Throttled statistics-updated snapshots
+    pub statistics_pending: Arc<AtomicBool>,  // A statistics update is already scheduled
 }
 
 impl AppState {
@@ -42,10 +95,16 @@ impl AppState {
             _rx: Arc::new(Mutex::new(rx)),
             app_handle: Arc::new(Mutex::new(None)),
             processor,
-            skip_next_change: Arc::new(Mutex::new(false)),
+            recent_writes: Arc::new(Mutex::new(RecentWrites::default())),
             config_manager,
-            current_shortcut: Arc::new(Mutex::new(None)),
+            shortcuts: Arc::new(Mutex::new(ShortcutRegistry::default())),
             last_cleanup_date: Arc::new(Mutex::new(None)),
+            url_previews: Arc::new(Mutex::new(UrlPreviewCache::default())),
+            media_metadata: Arc::new(Mutex::new(MediaMetadataCache::default())),
+            resume_task: Arc::new(Mutex::new(None)),
+            clipboard: Arc::new(ArboardBackend::default()),
+            statistics_tx: broadcast::channel(16).0,
+            statistics_pending: Arc::new(AtomicBool::new(false)),
         };
 
         // 初始化清理日期
@@ -54,6 +113,40 @@ impl AppState {
         Ok(instance)
     }
 
+    /// 测试用的状态：数据库、图片目录和配置文件都放在 dir 下，使用默认配置和模拟剪贴板
+    #[cfg(test)]
+    pub async fn for_test(dir: &std::path::Path) -> Result<Self> {
+        let database_url = format!(
+            "sqlite:{}?mode=rwc",
+            dir.join("test_state_clipboard.db").display()
+        );
+        let db = Database::from_pool(sqlx::SqlitePool::connect(&database_url).await?);
+        db.init().await?;
+        let (tx, rx) = broadcast::channel(100);
+
+        Ok(Self {
+            db: Arc::new(db),
+            monitor: Arc::new(RwLock::new(None)),
+            tx,
+            _rx: Arc::new(Mutex::new(rx)),
+            app_handle: Arc::new(Mutex::new(None)),
+            processor: Arc::new(ContentProcessor::with_dir(dir.join("imgs"))?),
+            recent_writes: Arc::new(Mutex::new(RecentWrites::default())),
+            config_manager: Arc::new(Mutex::new(ConfigManager::from_config(
+                dir.join("config.json"),
+                AppConfig::default(),
+            ))),
+            shortcuts: Arc::new(Mutex::new(ShortcutRegistry::default())),
+            last_cleanup_date: Arc::new(Mutex::new(None)),
+            url_previews: Arc::new(Mutex::new(UrlPreviewCache::default())),
+            media_metadata: Arc::new(Mutex::new(MediaMetadataCache::default())),
+            resume_task: Arc::new(Mutex::new(None)),
+            clipboard: Arc::new(crate::clipboard::backend::MockClipboard::default()),
+            statistics_tx: broadcast::channel(16).0,
+            statistics_pending: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
     pub fn set_app_handle(&self, handle: AppHandle) {
         let app_handle = Arc::clone(&self.app_handle);
         tauri::async_runtime::spawn(async move {
@@ -63,28 +156,124 @@ impl AppState {
     }
 
     pub async fn start_monitoring(&self) -> Result<()> {
+        self.cancel_scheduled_resume().await;
+        self.start_monitor().await?;
+        self.persist_monitoring_enabled(true).await
+    }
+
+    pub async fn stop_monitoring(&self) -> Result<()> {
+        self.cancel_scheduled_resume().await;
+        self.stop_monitor().await;
+        self.persist_monitoring_enabled(false).await
+    }
+
+    /// 暂停监听 minutes 分钟，到时自动恢复；暂停不改变保存的监听开关
+    pub async fn pause_monitoring(&self, minutes: u32) -> Result<()> {
+        if minutes == 0 {
+            return Err(
+                CommandError::invalid_input("Pause duration must be at least 1 minute").into(),
+            );
+        }
+        self.pause_monitoring_for(std::time::Duration::from_secs(minutes as u64 * 60))
+            .await
+    }
+
+    pub async fn pause_monitoring_for(&self, duration: std::time::Duration) -> Result<()> {
+        // 监听已关闭（而不是暂停中）时不安排恢复，否则到时会打开用户关闭的监听
+        if !self.is_monitoring().await && self.resume_task.lock().await.is_none() {
+            log::info!("[AppState] 监听未开启，忽略暂停");
+            return Ok(());
+        }
+
+        self.stop_monitor().await;
+        self.emit_monitoring_toggled(false).await;
+        log::info!("[AppState] 暂停监听 {:?}", duration);
+
+        let state = self.clone();
+        let task = tokio::spawn(async move {
+            tokio::time::sleep(duration).await;
+            // 先取出自己的句柄，之后的手动开始不会再取消已经执行的恢复
+            state.resume_task.lock().await.take();
+            match state.start_monitor().await {
+                Ok(()) => {
+                    log::info!("[AppState] 暂停结束，已恢复监听");
+                    state.emit_monitoring_toggled(true).await;
+                }
+                Err(e) => log::error!("[AppState] 暂停结束后恢复监听失败: {}", e),
+            }
+        });
+
+        if let Some(previous) = self.resume_task.lock().await.replace(task) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    /// 取消 pause_monitoring 安排的自动恢复
+    async fn cancel_scheduled_resume(&self) {
+        if let Some(task) = self.resume_task.lock().await.take() {
+            task.abort();
+            log::debug!("[AppState] 已取消暂停后的自动恢复");
+        }
+    }
+
+    async fn start_monitor(&self) -> Result<()> {
         let mut monitor_guard = self.monitor.write().await;
 
         if monitor_guard.is_none() {
+            let monitor_config = self.config_manager.lock().await.config.monitor.clone();
             let monitor = ClipboardMonitor::new(
                 self.tx.clone(),
                 Arc::clone(&self.processor),
                 Arc::clone(&self.config_manager),
+                Arc::clone(&self.recent_writes),
+                Arc::clone(&self.clipboard),
+                monitor_config,
             )?;
             monitor.start_monitoring().await;
-            *monitor_guard = Some(monitor);
 
-            // 启动数据库保存任务
-            self.start_database_save_task().await;
+            // 启动数据库保存任务，随监控器一起停止，避免重新开始后重复保存
+            monitor.attach_task(self.start_database_save_task());
+            *monitor_guard = Some(monitor);
         }
 
         Ok(())
     }
 
-    pub async fn stop_monitoring(&self) -> Result<()> {
+    async fn stop_monitor(&self) {
         let mut monitor_guard = self.monitor.write().await;
         *monitor_guard = None;
-        Ok(())
+    }
+
+    /// 启动时按上次保存的状态恢复监听，返回是否已开始监听
+    pub async fn restore_monitoring(&self) -> Result<bool> {
+        self.restore_monitoring_with(|| self.start_monitoring())
+            .await
+    }
+
+    /// restore_monitoring 的实现，start 为实际开始监听的操作（测试中可替换）
+    pub async fn restore_monitoring_with<F, Fut>(&self, start: F) -> Result<bool>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<()>>,
+    {
+        let enabled = self.config_manager.lock().await.config.monitoring_enabled;
+        if enabled {
+            log::info!("[AppState] 恢复上次的监听状态");
+            start().await?;
+        }
+        Ok(enabled)
+    }
+
+    /// 记录监听开关，状态未变化时不写配置文件
+    async fn persist_monitoring_enabled(&self, enabled: bool) -> Result<()> {
+        let mut config_manager = self.config_manager.lock().await;
+        if config_manager.config.monitoring_enabled == enabled {
+            return Ok(());
+        }
+        let mut config = config_manager.config.clone();
+        config.monitoring_enabled = enabled;
+        config_manager.update_config(config).await
     }
 
     pub async fn is_monitoring(&self) -> bool {
@@ -92,141 +281,524 @@ impl AppState {
         monitor_guard.is_some()
     }
 
-    async fn start_database_save_task(&self) {
-        let db = Arc::clone(&self.db);
+    fn start_database_save_task(&self) -> tokio::task::JoinHandle<()> {
+        let state = self.clone();
         let mut rx = self.tx.subscribe();
-        let app_handle = Arc::clone(&self.app_handle);
 
         tokio::spawn(async move {
             log::info!("[DatabaseTask] 启动数据库保存任务");
             while let Ok(entry) = rx.recv().await {
+                let Some(updated_entry) = state.save_entry(entry).await else {
+                    continue;
+                };
+
+                // 发送更新后的条目到前端
+                if let Some(handle) = state.app_handle.lock().await.as_ref() {
+                    match handle.emit("clipboard-update", &updated_entry) {
+                        Ok(_) => log::trace!("[DatabaseTask] 成功发送更新事件到前端"),
+                        Err(e) => log::error!("[DatabaseTask] 发送更新事件失败: {}", e),
+                    }
+                } else {
+                    log::warn!("[DatabaseTask] 无法获取应用句柄，跳过前端更新");
+                }
+            }
+        })
+    }
+
+    /// 保存一条监听到的条目：重复内容更新复制次数，否则插入新记录。
+    /// 返回需要推送给前端的条目，命中排除规则时返回 None
+    pub async fn save_entry(&self, mut entry: ClipboardEntry) -> Option<ClipboardEntry> {
+        let db = &self.db;
+        log::debug!(
+            "[DatabaseTask] 收到新条目: {} ({:?})",
+            &entry.content_hash[..8],
+            entry.content_type
+        );
+
+        let (
+            dedup_exempt,
+            dedup_mode,
+            max_copy_count,
+            sensitive_subtype,
+            image_dedup_distance,
+            oversize,
+            detect_language,
+        ) = {
+            let config_manager = self.config_manager.lock().await;
+            let text_content = if entry.content_type == ContentType::Text.as_str() {
+                entry.content_data.as_deref()
+            } else {
+                None
+            };
+            if config_manager.is_excluded(
+                entry.app_bundle_id.as_deref(),
+                entry.source_window_title.as_deref(),
+                text_content,
+            ) {
+                log::debug!("[DatabaseTask] 条目命中排除规则，跳过保存");
+                return None;
+            }
+
+            // 重新检测文本子类型，敏感子类型的内容不落盘
+            let sensitive_subtype = text_content
+                .filter(|_| !config_manager.config.skip_sensitive_subtypes.is_empty())
+                .map(|content| ContentDetector::detect(content).0)
+                .filter(|subtype| config_manager.is_sensitive_subtype(subtype.as_str()));
+            let oversize = text_content
+                .filter(|content| !config_manager.is_text_size_valid(content))
+                .map(|content| {
+                    (
+                        content.len(),
+                        config_manager.max_text_bytes(),
+                        config_manager.config.text.on_oversize,
+                    )
+                });
+            (
+                config_manager.is_dedup_exempt(entry.content_subtype.as_deref()),
+                config_manager.config.dedup_mode,
+                config_manager.config.max_copy_count,
+                sensitive_subtype,
+                config_manager.config.image.dedup_distance,
+                oversize,
+                language::AVAILABLE && config_manager.config.language_detection_enabled,
+            )
+        };
+
+        if let Some(subtype) = sensitive_subtype {
+            log::info!(
+                "[DatabaseTask] 检测到敏感内容 ({})，跳过保存",
+                subtype.as_str()
+            );
+            self.emit_sensitive_skipped(&entry, subtype).await;
+            return None;
+        }
+
+        // 超过 text.max_size_mb 的文本按 on_oversize 跳过或截断
+        if let Some((size_bytes, limit_bytes, policy)) = oversize {
+            log::warn!(
+                "[DatabaseTask] 文本大小超限 ({} > {} 字节)，处理方式: {}",
+                size_bytes,
+                limit_bytes,
+                policy.as_str()
+            );
+            self.emit_text_oversize(&entry, size_bytes, limit_bytes, policy)
+                .await;
+            match policy {
+                OversizePolicy::Skip => return None,
+                OversizePolicy::Truncate => Self::truncate_text_entry(&mut entry, limit_bytes),
+            }
+        }
+
+        // 纯文本附带自然语言识别结果，供搜索和筛选使用
+        if detect_language
+            && entry.content_subtype.as_deref() == Some(ContentSubType::PlainText.as_str())
+        {
+            if let Some(language) = entry.content_data.as_deref().and_then(language::detect) {
+                log::debug!(
+                    "[DatabaseTask] 识别到自然语言: {} ({:.2})",
+                    language.code,
+                    language.confidence
+                );
+                entry.metadata = Some(language.merge_into_metadata(entry.metadata.as_deref()));
+            }
+        }
+
+        // 检查是否已存在相同内容（豁免去重的子类型和 dedup_mode 为 none 时总是插入新记录）
+        let skip_dedup = dedup_exempt || dedup_mode == DedupMode::None;
+        let existing = if skip_dedup {
+            log::debug!(
+                "[DatabaseTask] 子类型 {:?} 不参与去重（{:?}），直接插入",
+                entry.content_subtype,
+                dedup_mode
+            );
+            Ok(None)
+        } else if dedup_mode == DedupMode::ConsecutiveOnly {
+            // 只与最新的一条比较，连续的重复复制才合并
+            sqlx::query(
+                "SELECT id, copy_count, file_path, content_data, metadata FROM clipboard_entries WHERE content_hash = ? AND id = (SELECT id FROM clipboard_entries ORDER BY created_at DESC LIMIT 1)",
+            )
+            .bind(&entry.content_hash)
+            .fetch_optional(db.pool())
+            .await
+        } else {
+            sqlx::query(
+                "SELECT id, copy_count, file_path, content_data, metadata FROM clipboard_entries WHERE content_hash = ?",
+            )
+            .bind(&entry.content_hash)
+            .fetch_optional(db.pool())
+            .await
+        };
+
+        // 图片的感知哈希，字节不同但看起来相同的图片（缩放、重新编码）也视为重复
+        let phash = match &entry.file_path {
+            Some(file_path) if entry.content_type == ContentType::Image.as_str() => {
+                match self.processor.perceptual_hash(file_path).await {
+                    Ok(phash) => Some(phash),
+                    Err(e) => {
+                        log::warn!("[DatabaseTask] 计算图片感知哈希失败: {}", e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        let existing = match (existing, &phash, image_dedup_distance) {
+            (Ok(None), Some(phash), Some(max_distance))
+                if !dedup_exempt && dedup_mode == DedupMode::ByHash =>
+            {
+                self.find_similar_image(phash, max_distance).await
+            }
+            (existing, _, _) => existing,
+        };
+
+        let mut updated_entry = entry.clone();
+
+        match existing {
+            Ok(Some(row)) => {
+                // 更新复制次数
+                let id: String = row.get("id");
+                let count: i32 = row.get("copy_count");
+                // 达到上限后保持不变，避免 i32 溢出
+                let new_count = count.saturating_add(1).min(max_copy_count.max(1));
+
                 log::debug!(
-                    "[DatabaseTask] 收到新条目: {} ({:?})",
-                    &entry.content_hash[..8],
-                    entry.content_type
+                    "[DatabaseTask] 找到重复内容，更新复制次数: {} -> {}",
+                    count,
+                    new_count
                 );
 
-                // 检查是否已存在相同内容
-                let existing = sqlx::query(
-                    "SELECT id, copy_count FROM clipboard_entries WHERE content_hash = ?",
+                match sqlx::query(
+                    "UPDATE clipboard_entries SET copy_count = ?, created_at = ? WHERE id = ?",
+                )
+                .bind(new_count)
+                .bind(entry.created_at)
+                .bind(&id)
+                .execute(db.pool())
+                .await
+                {
+                    Ok(_) => log::debug!("[DatabaseTask] 成功更新重复条目复制次数"),
+                    Err(e) => log::error!("[DatabaseTask] 更新复制次数失败: {}", e),
+                }
+
+                // 重复图片沿用已有的文件，删除刚保存的副本
+                let existing_file: Option<String> = row.get("file_path");
+                if let (Some(new_file), Some(existing_file)) = (&entry.file_path, &existing_file) {
+                    if new_file != existing_file {
+                        self.remove_image_file(new_file);
+                        updated_entry.file_path = Some(existing_file.clone());
+                        updated_entry.content_data = row.get("content_data");
+                        updated_entry.metadata = row.get("metadata");
+                    }
+                }
+
+                // 更新条目信息以便发送正确的数据到前端
+                updated_entry.id = id;
+                updated_entry.copy_count = new_count;
+            }
+            Ok(None) => {
+                // 插入新记录 - 新记录的copy_count应该是1
+                updated_entry.copy_count = 1;
+
+                log::debug!("[DatabaseTask] 插入新条目到数据库");
+
+                match sqlx::query(
+                    r#"
+                    INSERT INTO clipboard_entries 
+                    (id, content_hash, content_type, content_data, source_app, 
+                     created_at, copy_count, file_path, is_favorite, content_subtype, metadata, app_bundle_id,
+                     source_window_title, phash, preview)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
                 )
+                .bind(&entry.id)
                 .bind(&entry.content_hash)
-                .fetch_optional(db.pool())
-                .await;
+                .bind(&entry.content_type)
+                .bind(&entry.content_data)
+                .bind(&entry.source_app)
+                .bind(entry.created_at)
+                .bind(1) // 新记录的copy_count设为1
+                .bind(&entry.file_path)
+                .bind(entry.is_favorite as i32)
+                .bind(&entry.content_subtype)
+                .bind(&entry.metadata)
+                .bind(&entry.app_bundle_id)
+                .bind(&entry.source_window_title)
+                .bind(&phash)
+                .bind(entry.build_preview())
+                .execute(db.pool())
+                .await {
+                    Ok(_) => {
+                        log::info!("[DatabaseTask] 成功保存新条目到数据库");
+
+                        // 图片文字识别较慢，放到后台执行
+                        if let Some(file_path) = &entry.file_path {
+                            if ocr::AVAILABLE
+                                && entry.content_type == ContentType::Image.as_str()
+                                && self.config_manager.lock().await.config.ocr_enabled
+                            {
+                                self.spawn_image_ocr(entry.id.clone(), file_path.clone());
+                            }
+                        }
 
-                let mut updated_entry = entry.clone();
-
-                match existing {
-                    Ok(Some(row)) => {
-                        // 更新复制次数
-                        let id: String = row.get("id");
-                        let count: i32 = row.get("copy_count");
-                        let new_count = count + 1;
-
-                        log::debug!(
-                            "[DatabaseTask] 找到重复内容，更新复制次数: {} -> {}",
-                            count,
-                            new_count
-                        );
-
-                        match sqlx::query(
-                            "UPDATE clipboard_entries SET copy_count = ?, created_at = ? WHERE id = ?"
-                        )
-                        .bind(new_count)
-                        .bind(entry.created_at)
-                        .bind(&id)
-                        .execute(db.pool())
-                        .await {
-                            Ok(_) => log::debug!("[DatabaseTask] 成功更新重复条目复制次数"),
-                            Err(e) => log::error!("[DatabaseTask] 更新复制次数失败: {}", e),
+                        // 通知外部自动化，失败不影响保存
+                        let webhook = {
+                            let config = &self.config_manager.lock().await.config;
+                            config
+                                .webhook_url
+                                .clone()
+                                .map(|url| (url, config.webhook_include_content))
+                        };
+                        if let Some((url, include_content)) = webhook {
+                            webhook::spawn_delivery(url, &updated_entry, include_content);
                         }
 
-                        // 更新条目信息以便发送正确的数据到前端
-                        updated_entry.id = id;
-                        updated_entry.copy_count = new_count;
-                    }
-                    Ok(None) => {
-                        // 插入新记录 - 新记录的copy_count应该是1
-                        updated_entry.copy_count = 1;
-
-                        log::debug!("[DatabaseTask] 插入新条目到数据库");
-
-                        match sqlx::query(
-                            r#"
-                            INSERT INTO clipboard_entries 
-                            (id, content_hash, content_type, content_data, source_app, 
-                             created_at, copy_count, file_path, is_favorite, content_subtype, metadata, app_bundle_id)
-                            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                            "#,
-                        )
-                        .bind(&entry.id)
-                        .bind(&entry.content_hash)
-                        .bind(&entry.content_type)
-                        .bind(&entry.content_data)
-                        .bind(&entry.source_app)
-                        .bind(entry.created_at)
-                        .bind(1) // 新记录的copy_count设为1
-                        .bind(&entry.file_path)
-                        .bind(entry.is_favorite as i32)
-                        .bind(&entry.content_subtype)
-                        .bind(&entry.metadata)
-                        .bind(&entry.app_bundle_id)
-                        .execute(db.pool())
-                        .await {
-                            Ok(_) => log::info!("[DatabaseTask] 成功保存新条目到数据库"),
-                            Err(e) => log::error!("[DatabaseTask] 保存新条目失败: {}", e),
+                        // 超出历史总数上限时删除最旧的条目
+                        if let Err(e) = self.trim_history_to_limit().await {
+                            log::error!("[DatabaseTask] 清理超出上限的历史失败: {}", e);
                         }
                     }
-                    Err(e) => log::error!("[DatabaseTask] 数据库查询错误: {}", e),
+                    Err(e) => log::error!("[DatabaseTask] 保存新条目失败: {}", e),
                 }
+            }
+            Err(e) => log::error!("[DatabaseTask] 数据库查询错误: {}", e),
+        }
 
-                // 发送更新后的条目到前端
-                if let Some(handle) = app_handle.lock().await.as_ref() {
-                    match handle.emit("clipboard-update", &updated_entry) {
-                        Ok(_) => log::trace!("[DatabaseTask] 成功发送更新事件到前端"),
-                        Err(e) => log::error!("[DatabaseTask] 发送更新事件失败: {}", e),
+        self.notify_statistics_changed();
+        Some(updated_entry)
+    }
+
+    /// 在最近的图片中查找感知哈希距离不超过 max_distance 的条目，优先返回最新的
+    async fn find_similar_image(
+        &self,
+        phash: &str,
+        max_distance: u32,
+    ) -> sqlx::Result<Option<SqliteRow>> {
+        let candidates = sqlx::query(
+            r#"
+            SELECT id, copy_count, file_path, content_data, metadata, phash FROM clipboard_entries
+            WHERE content_type = 'image' AND phash IS NOT NULL
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(SIMILAR_IMAGE_CANDIDATES)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(candidates.into_iter().find(|row| {
+            let candidate: String = row.get("phash");
+            perceptual_hash_distance(phash, &candidate)
+                .is_some_and(|distance| distance <= max_distance)
+        }))
+    }
+
+    /// 删除 imgs 目录下的图片文件及其缩略图，失败时只记录日志
+    fn remove_image_file(&self, relative_path: &str) {
+        let full_path = self.processor.resolve_path(relative_path);
+        if let Err(e) = std::fs::remove_file(&full_path) {
+            log::warn!("[AppState] 删除图片文件失败 {:?}: {}", full_path, e);
+        }
+        self.processor.remove_thumbnails(relative_path);
+    }
+
+    /// 在后台识别新图片中的文字，结果写入条目元数据后通知前端
+    fn spawn_image_ocr(&self, id: String, file_path: String) {
+        let state = self.clone();
+        tokio::spawn(async move {
+            match state.processor.recognize_image_text(&file_path).await {
+                Ok(Some(text)) => match state.store_ocr_text(&id, &text).await {
+                    Ok(Some(entry)) => {
+                        log::info!("[OCR] 识别到 {} 个字符", text.chars().count());
+                        state.emit_clipboard_update(&entry).await;
                     }
-                } else {
-                    log::warn!("[DatabaseTask] 无法获取应用句柄，跳过前端更新");
-                }
+                    Ok(None) => log::debug!("[OCR] 条目已被删除，丢弃识别结果"),
+                    Err(e) => log::error!("[OCR] 保存识别结果失败: {}", e),
+                },
+                Ok(None) => log::debug!("[OCR] 图片中没有识别到文字"),
+                Err(e) => log::warn!("[OCR] 识别图片文字失败: {}", e),
             }
         });
     }
 
+    /// 将识别出的文字合并进条目元数据的 ocr_text 字段，条目不存在时返回 None
+    pub async fn store_ocr_text(&self, id: &str, text: &str) -> Result<Option<ClipboardEntry>> {
+        let Some(mut entry) = self.get_entry(id.to_string()).await? else {
+            return Ok(None);
+        };
+
+        let mut metadata = entry
+            .metadata
+            .as_deref()
+            .and_then(|metadata| serde_json::from_str::<serde_json::Value>(metadata).ok())
+            .filter(|metadata| metadata.is_object())
+            .unwrap_or_else(|| serde_json::json!({}));
+        metadata["ocr_text"] = serde_json::Value::String(text.to_string());
+        let metadata = metadata.to_string();
+
+        sqlx::query("UPDATE clipboard_entries SET metadata = ? WHERE id = ?")
+            .bind(&metadata)
+            .bind(id)
+            .execute(self.db.pool())
+            .await?;
+
+        entry.metadata = Some(metadata);
+        Ok(Some(entry))
+    }
+
+    /// 历史总数超过 max_total_entries 时删除最旧的非收藏条目（及其图片），返回删除数量
+    pub async fn trim_history_to_limit(&self) -> Result<u32> {
+        let max_total_entries = self.config_manager.lock().await.config.max_total_entries;
+        if max_total_entries == 0 {
+            return Ok(0);
+        }
+
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM clipboard_entries")
+            .fetch_one(self.db.pool())
+            .await?;
+        let excess = total - max_total_entries as i64;
+        if excess <= 0 {
+            return Ok(0);
+        }
+
+        let ids: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM clipboard_entries WHERE is_favorite = 0 ORDER BY created_at ASC LIMIT ?",
+        )
+        .bind(excess)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let removed = self.delete_entries(ids).await?;
+        log::info!(
+            "[AppState] 历史超过上限 {}，已删除 {} 条最旧条目",
+            max_total_entries,
+            removed
+        );
+        Ok(removed)
+    }
+
     pub async fn get_clipboard_history(
         &self,
         limit: Option<i32>,
         offset: Option<i32>,
         search: Option<String>,
+        filter: HistoryFilter,
     ) -> Result<Vec<ClipboardEntry>> {
-        let limit = limit.unwrap_or(50);
+        let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
         let offset = offset.unwrap_or(0);
 
-        let query = if let Some(search_term) = search {
-            sqlx::query_as::<_, ClipboardEntry>(
-                r#"
-                SELECT * FROM clipboard_entries 
-                WHERE content_data LIKE ? OR source_app LIKE ?
-                ORDER BY created_at DESC 
-                LIMIT ? OFFSET ?
-                "#,
-            )
-            .bind(format!("%{}%", search_term))
-            .bind(format!("%{}%", search_term))
-            .bind(limit)
-            .bind(offset)
+        if let Some(subtype) = &filter.subtype {
+            if ContentSubType::parse(subtype).is_none() {
+                return Err(CommandError::invalid_input(format!(
+                    "Unknown content subtype: {}",
+                    subtype
+                ))
+                .into());
+            }
+        }
+
+        if let (Some(start), Some(end)) = (filter.start_ms, filter.end_ms) {
+            if start > end {
+                return Err(CommandError::invalid_input(format!(
+                    "Invalid date range: start_ms ({}) is after end_ms ({})",
+                    start, end
+                ))
+                .into());
+            }
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(if filter.preview_only {
+            PREVIEW_COLUMNS
         } else {
-            sqlx::query_as::<_, ClipboardEntry>(
-                "SELECT * FROM clipboard_entries ORDER BY created_at DESC LIMIT ? OFFSET ?",
-            )
-            .bind(limit)
-            .bind(offset)
-        };
+            "SELECT * FROM clipboard_entries"
+        });
+        push_history_filters(&mut query, search, filter);
+        query
+            .push(" ORDER BY created_at DESC LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+
+        let entries = query
+            .build_query_as::<ClipboardEntry>()
+            .fetch_all(self.db.pool())
+            .await?;
+        Ok(entries)
+    }
+
+    /// 一页历史记录及满足同样搜索和过滤条件的条目总数，供前端显示页码
+    pub async fn get_history_page(
+        &self,
+        limit: Option<i32>,
+        offset: Option<i32>,
+        search: Option<String>,
+        filter: HistoryFilter,
+    ) -> Result<HistoryPage> {
+        let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+        let offset = offset.unwrap_or(0);
+
+        // 计数和列表使用同一组过滤条件
+        let mut count_query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM clipboard_entries");
+        push_history_filters(&mut count_query, search.clone(), filter.clone());
+
+        let entries = self
+            .get_clipboard_history(Some(limit), Some(offset), search, filter)
+            .await?;
+        let total: i64 = count_query
+            .build_query_scalar()
+            .fetch_one(self.db.pool())
+            .await?;
+
+        Ok(HistoryPage {
+            entries,
+            total,
+            offset,
+            limit,
+        })
+    }
+
+    /// 条目的完整文本内容，配合 preview_only 的历史列表按需加载
+    pub async fn get_entry_content(&self, id: String) -> Result<Option<String>> {
+        let content: Option<Option<String>> =
+            sqlx::query_scalar("SELECT content_data FROM clipboard_entries WHERE id = ?")
+                .bind(&id)
+                .fetch_optional(self.db.pool())
+                .await?;
+        content.ok_or_else(|| CommandError::not_found(format!("Entry not found: {}", id)).into())
+    }
+
+    pub async fn get_entries_by_app(&self, app: String) -> Result<Vec<ClipboardEntry>> {
+        let entries = sqlx::query_as::<_, ClipboardEntry>(
+            "SELECT * FROM clipboard_entries WHERE source_app = ? ORDER BY created_at DESC",
+        )
+        .bind(&app)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// created_at 晚于 since_ms 的条目，按时间升序返回，供前端增量刷新
+    pub async fn get_entries_since(&self, since_ms: i64) -> Result<Vec<ClipboardEntry>> {
+        let entries = sqlx::query_as::<_, ClipboardEntry>(
+            "SELECT * FROM clipboard_entries WHERE created_at > ? ORDER BY created_at ASC",
+        )
+        .bind(since_ms)
+        .fetch_all(self.db.pool())
+        .await?;
 
-        let entries = query.fetch_all(self.db.pool()).await?;
         Ok(entries)
     }
 
+    pub async fn get_entry(&self, id: String) -> Result<Option<ClipboardEntry>> {
+        let entry =
+            sqlx::query_as::<_, ClipboardEntry>("SELECT * FROM clipboard_entries WHERE id = ?")
+                .bind(&id)
+                .fetch_optional(self.db.pool())
+                .await?;
+
+        Ok(entry)
+    }
+
     pub async fn toggle_favorite(&self, id: String) -> Result<()> {
         sqlx::query("UPDATE clipboard_entries SET is_favorite = NOT is_favorite WHERE id = ?")
             .bind(&id)
@@ -236,80 +808,703 @@ impl AppState {
         Ok(())
     }
 
-    pub async fn delete_entry(&self, id: String) -> Result<()> {
-        sqlx::query("DELETE FROM clipboard_entries WHERE id = ?")
-            .bind(&id)
-            .execute(self.db.pool())
-            .await?;
+    pub async fn delete_entry(&self, id: String) -> Result<()> {
+        sqlx::query("DELETE FROM clipboard_entries WHERE id = ?")
+            .bind(&id)
+            .execute(self.db.pool())
+            .await?;
+
+        self.notify_statistics_changed();
+        Ok(())
+    }
+
+    /// 在一个事务中批量删除条目，并删除图片条目对应的文件，返回实际删除的条目数
+    pub async fn delete_entries(&self, ids: Vec<String>) -> Result<u32> {
+        let mut tx = self.db.pool().begin().await?;
+        let mut deleted = 0;
+        let mut image_files = Vec::new();
+
+        for id in &ids {
+            let file_path: Option<Option<String>> =
+                sqlx::query_scalar("SELECT file_path FROM clipboard_entries WHERE id = ?")
+                    .bind(id)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+
+            // 条目不存在（或 id 重复）时跳过
+            let Some(file_path) = file_path else {
+                continue;
+            };
+
+            sqlx::query("DELETE FROM clipboard_entries WHERE id = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            deleted += 1;
+
+            if let Some(relative_path) = file_path {
+                image_files.push(relative_path);
+            }
+        }
+
+        tx.commit().await?;
+        if deleted > 0 {
+            self.notify_statistics_changed();
+        }
+
+        // 事务提交后再删除图片文件，避免回滚后文件已丢失
+        for relative_path in image_files {
+            let full_path = self.resolve_image_path(&relative_path)?;
+            if full_path.exists() {
+                if let Err(e) = std::fs::remove_file(&full_path) {
+                    log::warn!("[AppState] 删除图片文件失败 {:?}: {}", full_path, e);
+                }
+            }
+            self.processor.remove_thumbnails(&relative_path);
+        }
+
+        Ok(deleted)
+    }
+
+    /// 把 other_ids 的文本按顺序拼接到主条目之后（默认用换行分隔），
+    /// 保留主条目的元数据，累加复制次数，并在同一事务中删除被合并的条目
+    pub async fn merge_entries(
+        &self,
+        primary_id: String,
+        other_ids: Vec<String>,
+        separator: Option<String>,
+    ) -> Result<ClipboardEntry> {
+        let separator = separator.unwrap_or_else(|| "\n".to_string());
+        let max_copy_count = self.config_manager.lock().await.config.max_copy_count;
+
+        let mut tx = self.db.pool().begin().await?;
+        let mut entries = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for id in std::iter::once(&primary_id).chain(&other_ids) {
+            if !seen.insert(id.as_str()) {
+                continue;
+            }
+            let entry =
+                sqlx::query_as::<_, ClipboardEntry>("SELECT * FROM clipboard_entries WHERE id = ?")
+                    .bind(id)
+                    .fetch_optional(&mut *tx)
+                    .await?
+                    .ok_or_else(|| CommandError::not_found(format!("Entry not found: {}", id)))?;
+            if entry.content_type == ContentType::Image.as_str() {
+                return Err(CommandError::invalid_input(format!(
+                    "Cannot merge image entry: {}",
+                    id
+                ))
+                .into());
+            }
+            entries.push(entry);
+        }
+        if entries.len() < 2 {
+            return Err(CommandError::invalid_input(
+                "At least two distinct entries are required to merge",
+            )
+            .into());
+        }
+
+        let merged_content = entries
+            .iter()
+            .map(|entry| entry.content_data.as_deref().unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(&separator);
+        let total_copies: i64 = entries.iter().map(|entry| entry.copy_count as i64).sum();
+
+        sqlx::query(
+            "UPDATE clipboard_entries SET content_data = ?, preview = ?, content_hash = ?, copy_count = ? WHERE id = ?",
+        )
+        .bind(&merged_content)
+        .bind(text_preview(&merged_content))
+        .bind(ClipboardMonitor::calculate_hash(merged_content.trim().as_bytes()))
+        .bind(total_copies.min(max_copy_count.max(1) as i64) as i32)
+        .bind(&primary_id)
+        .execute(&mut *tx)
+        .await?;
+
+        for entry in &entries[1..] {
+            sqlx::query("DELETE FROM clipboard_entries WHERE id = ?")
+                .bind(&entry.id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        self.notify_statistics_changed();
+
+        log::info!(
+            "[AppState] 已将 {} 条记录合并到 {}",
+            entries.len() - 1,
+            primary_id
+        );
+        self.get_entry(primary_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Merged entry disappeared"))
+    }
+
+    pub async fn clear_history(&self) -> Result<()> {
+        sqlx::query("DELETE FROM clipboard_entries")
+            .execute(self.db.pool())
+            .await?;
+
+        self.notify_statistics_changed();
+        Ok(())
+    }
+
+    /// 删除 before_ms 之前创建的非收藏条目（及其图片），返回删除数量
+    pub async fn clear_history_before(&self, before_ms: i64) -> Result<u32> {
+        let ids: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM clipboard_entries WHERE created_at < ? AND is_favorite = 0",
+        )
+        .bind(before_ms)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let removed = self.delete_entries(ids).await?;
+        log::info!("[AppState] 已清除 {} 之前的 {} 条历史", before_ms, removed);
+        Ok(removed)
+    }
+
+    /// 统计数据；传入 since_ms 时只统计该时间之后的条目
+    pub async fn get_statistics(&self, since_ms: Option<i64>) -> Result<Statistics> {
+        let since = since_ms.unwrap_or(i64::MIN);
+
+        // 总条目数
+        let total_entries: i64 =
+            sqlx::query("SELECT COUNT(*) as count FROM clipboard_entries WHERE created_at >= ?")
+                .bind(since)
+                .fetch_one(self.db.pool())
+                .await?
+                .get("count");
+
+        // 总复制次数
+        let total_copies: i64 = sqlx::query(
+            "SELECT SUM(copy_count) as sum FROM clipboard_entries WHERE created_at >= ?",
+        )
+        .bind(since)
+        .fetch_one(self.db.pool())
+        .await?
+        .try_get("sum")
+        .unwrap_or(0);
+
+        // 最多复制的条目；配置了半衰期时按新旧程度衰减后排序，copy_count 本身不变
+        let (half_life_days, limits) = {
+            let config_manager = self.config_manager.lock().await;
+            (
+                config_manager
+                    .config
+                    .copy_count_half_life_days
+                    .filter(|days| *days > 0),
+                config_manager.config.statistics.clone(),
+            )
+        };
+        let most_copied = match half_life_days {
+            None => {
+                sqlx::query_as::<_, ClipboardEntry>(
+                    "SELECT * FROM clipboard_entries WHERE created_at >= ? ORDER BY copy_count DESC LIMIT ?",
+                )
+                .bind(since)
+                .bind(limits.most_copied_limit as i64)
+                .fetch_all(self.db.pool())
+                .await?
+            }
+            Some(half_life_days) => {
+                let now = Utc::now().timestamp_millis();
+                let mut scored: Vec<(f64, String)> = sqlx::query(
+                    "SELECT id, copy_count, created_at FROM clipboard_entries WHERE created_at >= ?",
+                )
+                .bind(since)
+                .fetch_all(self.db.pool())
+                .await?
+                .into_iter()
+                .map(|row| {
+                    let score = decayed_copy_score(
+                        row.get("copy_count"),
+                        now - row.get::<i64, _>("created_at"),
+                        half_life_days,
+                    );
+                    (score, row.get("id"))
+                })
+                .collect();
+                scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+                scored.truncate(limits.most_copied_limit);
+
+                // 一次查询取回排名靠前的条目，再按分数顺序排列
+                let mut query =
+                    QueryBuilder::<Sqlite>::new("SELECT * FROM clipboard_entries WHERE id IN (");
+                let mut ids = query.separated(", ");
+                for (_, id) in &scored {
+                    ids.push_bind(id.clone());
+                }
+                ids.push_unseparated(")");
+                let mut entries: std::collections::HashMap<String, ClipboardEntry> =
+                    if scored.is_empty() {
+                        std::collections::HashMap::new()
+                    } else {
+                        query
+                            .build_query_as::<ClipboardEntry>()
+                            .fetch_all(self.db.pool())
+                            .await?
+                            .into_iter()
+                            .map(|entry| (entry.id.clone(), entry))
+                            .collect()
+                    };
+                scored
+                    .into_iter()
+                    .filter_map(|(_, id)| entries.remove(&id))
+                    .collect()
+            }
+        };
+
+        // 最近使用的应用
+        let recent_apps = sqlx::query(
+            r#"
+            SELECT source_app, COUNT(*) as count 
+            FROM clipboard_entries 
+            WHERE source_app IS NOT NULL AND created_at >= ?
+            GROUP BY source_app 
+            ORDER BY count DESC 
+            LIMIT ?
+            "#,
+        )
+        .bind(since)
+        .bind(limits.recent_apps_limit as i64)
+        .fetch_all(self.db.pool())
+        .await?
+        .into_iter()
+        .map(|row| AppUsage {
+            app_name: row.get("source_app"),
+            count: row.get("count"),
+        })
+        .collect();
+
+        Ok(Statistics {
+            total_entries,
+            total_copies,
+            most_copied,
+            recent_apps,
+        })
+    }
+
+    pub async fn get_activity_summary(&self) -> Result<ActivitySummary> {
+        let timestamps: Vec<i64> = sqlx::query_scalar("SELECT created_at FROM clipboard_entries")
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(ActivitySummary::from_timestamps(
+            &timestamps,
+            chrono::Local::now().date_naive(),
+        ))
+    }
+
+    pub async fn get_activity_histogram(&self, bucket: String) -> Result<Vec<ActivityBucket>> {
+        let bucket = HistogramBucket::parse(&bucket).ok_or_else(|| {
+            CommandError::invalid_input(format!("Unknown histogram bucket: {}", bucket))
+        })?;
+
+        let timestamps: Vec<i64> = sqlx::query_scalar("SELECT created_at FROM clipboard_entries")
+            .fetch_all(self.db.pool())
+            .await?;
+
+        Ok(ActivityBucket::histogram(&timestamps, bucket))
+    }
+
+    pub async fn copy_to_clipboard(&self, content: String) -> Result<()> {
+        self.set_text_as_recopy(content).await
+    }
+
+    /// 将已有文本重新写入剪贴板，并把这次复制记到对应条目上
+    async fn set_text_as_recopy(&self, content: String) -> Result<()> {
+        let hash = ClipboardMonitor::calculate_hash(content.trim().as_bytes());
+        self.skip_monitor_detection(&hash).await;
+
+        if let Err(e) = self.set_plain_text(content.clone()).await {
+            self.forget_recent_write(&hash).await;
+            return Err(e);
+        }
+
+        if let Some(entry) = self.bump_copy_count_for_content(&content).await? {
+            self.emit_clipboard_update(&entry).await;
+        }
+
+        Ok(())
+    }
+
+    /// 带格式复制条目：写入保存的 HTML/RTF 和纯文本，没有富文本时只写纯文本
+    pub async fn copy_with_formatting(&self, id: String) -> Result<()> {
+        let entry = self
+            .get_entry(id.clone())
+            .await?
+            .ok_or_else(|| CommandError::not_found(format!("Entry not found: {}", id)))?;
+        if entry.content_type != ContentType::Text.as_str() {
+            return Err(CommandError::invalid_input(
+                "Only text entries can be copied with formatting",
+            )
+            .into());
+        }
+        let content = entry.content_data.unwrap_or_default();
+        let rich_text = entry
+            .metadata
+            .as_deref()
+            .and_then(RichText::from_entry_metadata)
+            .unwrap_or_default();
+        if rich_text.is_empty() {
+            return self.set_text_as_recopy(content).await;
+        }
+
+        // 监听器读取到的是纯文本，按纯文本的 Hash 跳过
+        let hash = ClipboardMonitor::calculate_hash(content.trim().as_bytes());
+        self.skip_monitor_detection(&hash).await;
+        let clipboard = self.clipboard.clone();
+        let plain_text = content.clone();
+        let result =
+            tokio::task::spawn_blocking(move || clipboard.set_rich_text(&rich_text, &plain_text))
+                .await?;
+        if let Err(e) = result {
+            self.forget_recent_write(&hash).await;
+            return Err(e);
+        }
+
+        if let Some(entry) = self.bump_copy_count_for_content(&content).await? {
+            self.emit_clipboard_update(&entry).await;
+        }
+        Ok(())
+    }
+
+    /// 对条目（按 id）或直接传入的文本应用转换并写入剪贴板，返回转换结果。
+    /// record 为 true 时将结果保存为一条新记录
+    pub async fn transform_and_copy(
+        &self,
+        id_or_content: String,
+        transform: String,
+        record: bool,
+    ) -> Result<String> {
+        let transform = Transform::parse(&transform).ok_or_else(|| {
+            CommandError::invalid_input(format!("Unknown transform: {}", transform))
+        })?;
+
+        let source = match self.get_entry(id_or_content.clone()).await? {
+            Some(entry) if entry.content_type == ContentType::Text.as_str() => {
+                entry.content_data.unwrap_or_default()
+            }
+            Some(_) => {
+                return Err(
+                    CommandError::invalid_input("Only text entries can be transformed").into(),
+                )
+            }
+            None => id_or_content,
+        };
+
+        let result = transform.apply(&source)?;
+        self.copy_generated_text(&result, record).await?;
+        Ok(result)
+    }
+
+    /// 移除 URL 中配置的跟踪参数，copy 为 true 时将结果写入剪贴板并记录
+    pub async fn clean_url(&self, url: String, copy: bool) -> Result<String> {
+        let tracking_params = self.get_config().await?.tracking_params;
+        let cleaned = transform::clean_url(&url, &tracking_params)?;
+        if copy {
+            self.copy_generated_text(&cleaned, true).await?;
+        }
+        Ok(cleaned)
+    }
+
+    /// 按正则表达式替换 content 中的文本（flags 见 transform::regex_replace），结果写入剪贴板并记录
+    pub async fn regex_replace_and_copy(
+        &self,
+        content: String,
+        pattern: String,
+        replacement: String,
+        flags: String,
+    ) -> Result<String> {
+        let replaced = transform::regex_replace(&content, &pattern, &replacement, &flags)?;
+        self.copy_generated_text(&replaced, true).await?;
+        Ok(replaced)
+    }
+
+    /// 把 URL 条目以 Markdown 链接 [标题](url) 写入剪贴板并记录。标题来自网页预览，
+    /// 禁止联网或获取失败时使用域名作为链接文本
+    pub async fn copy_as_markdown_link(&self, id: String) -> Result<String> {
+        let entry = self
+            .get_entry(id.clone())
+            .await?
+            .ok_or_else(|| CommandError::not_found(format!("Entry not found: {}", id)))?;
+        let url = entry
+            .content_data
+            .as_deref()
+            .map(str::trim)
+            .filter(|_| entry.content_type == ContentType::Text.as_str())
+            .filter(|content| ContentDetector::detect(content).0 == ContentSubType::Url)
+            .ok_or_else(|| CommandError::invalid_input("Only URL entries can be copied as a link"))?
+            .to_string();
+
+        let title = match self.fetch_url_preview(url.clone()).await {
+            Ok(preview) => preview.title.filter(|title| !title.trim().is_empty()),
+            Err(e) => {
+                log::debug!("[AppState] 无法获取网页标题，使用域名作为链接文本: {}", e);
+                None
+            }
+        };
+        let text = title.unwrap_or_else(|| {
+            url::Url::parse(&url)
+                .ok()
+                .and_then(|parsed| parsed.host_str().map(str::to_string))
+                .unwrap_or_else(|| url.clone())
+        });
+
+        let link = transform::markdown_link(&text, &url);
+        self.copy_generated_text(&link, true).await?;
+        Ok(link)
+    }
+
+    /// 把颜色转换为 target 格式（hex、rgb、rgba、hsl），copy 为 true 时写入剪贴板并记录
+    pub async fn convert_color(&self, value: String, target: String, copy: bool) -> Result<String> {
+        let target = ColorFormat::parse(&target).ok_or_else(|| {
+            CommandError::invalid_input(format!("Unknown color format: {}", target))
+        })?;
+        let converted = color::convert(&value, target)?;
+        if copy {
+            self.copy_generated_text(&converted, true).await?;
+        }
+        Ok(converted)
+    }
+
+    /// 读取媒体文件信息；本地文件的结果按路径和修改时间缓存
+    pub async fn extract_media_metadata(&self, url: &str) -> Result<serde_json::Value> {
+        let cache_key = media_metadata::local_path(url).and_then(|path| {
+            let modified = media_metadata::modified_time(&path)?;
+            Some((path, modified))
+        });
+        if let Some((path, modified)) = &cache_key {
+            if let Some(metadata) = self.media_metadata.lock().await.get(path, *modified) {
+                log::debug!("[AppState] 使用缓存的媒体元数据: {:?}", path);
+                return Ok(metadata);
+            }
+        }
+
+        let url = url.to_string();
+        let metadata = tokio::task::spawn_blocking(move || media_metadata::probe(&url)).await??;
+        log::info!("[AppState] 成功提取媒体元数据: {:?}", metadata);
+
+        if let Some((path, modified)) = cache_key {
+            self.media_metadata
+                .lock()
+                .await
+                .insert(path, modified, metadata.clone());
+        }
+        Ok(metadata)
+    }
+
+    /// 获取网页预览（标题、描述、图标），结果按配置的时长缓存
+    pub async fn fetch_url_preview(&self, url: String) -> Result<UrlPreview> {
+        let config = self.get_config().await?;
+        if !config.allow_network_fetch {
+            return Err(CommandError::unsupported("Network fetches are disabled").into());
+        }
+
+        let ttl = std::time::Duration::from_secs(config.url_preview_cache_ttl_secs);
+        if let Some(preview) = self.url_previews.lock().await.get(&url, ttl) {
+            log::debug!("[AppState] 使用缓存的网页预览: {}", url);
+            return Ok(preview);
+        }
+
+        let preview = url_preview::fetch(&url).await?;
+        if !ttl.is_zero() {
+            self.url_previews.lock().await.insert(url, preview.clone());
+        }
+        Ok(preview)
+    }
+
+    /// 将应用生成的文本写入剪贴板（不经过监听器），record 为 true 时保存为条目
+    async fn copy_generated_text(&self, text: &str, record: bool) -> Result<()> {
+        let hash = ClipboardMonitor::calculate_hash(text.trim().as_bytes());
+        self.skip_monitor_detection(&hash).await;
+        if let Err(e) = self.set_plain_text(text.to_string()).await {
+            self.forget_recent_write(&hash).await;
+            return Err(e);
+        }
+
+        if record && !text.trim().is_empty() {
+            let entry = Self::build_text_entry(text);
+            if let Some(saved) = self.save_entry(entry).await {
+                self.emit_clipboard_update(&saved).await;
+            }
+        }
 
         Ok(())
     }
 
-    pub async fn clear_history(&self) -> Result<()> {
-        sqlx::query("DELETE FROM clipboard_entries")
-            .execute(self.db.pool())
-            .await?;
+    /// 为应用自身生成的文本构建条目，并检测子类型和元数据
+    fn build_text_entry(content: &str) -> ClipboardEntry {
+        let trimmed = content.trim();
+        let (subtype, metadata) = ContentDetector::detect(trimmed);
+
+        let mut entry = ClipboardEntry::new(
+            ContentType::Text,
+            Some(trimmed.to_string()),
+            ClipboardMonitor::calculate_hash(trimmed.as_bytes()),
+            None,
+            None,
+        );
+        entry.content_subtype = Some(subtype.as_str().to_string());
+        entry.metadata = metadata.and_then(|m| serde_json::to_string(&m).ok());
+        entry
+    }
 
+    /// 清空剪贴板后只写入纯文本，确保不残留其他格式
+    pub async fn set_plain_text(&self, content: String) -> Result<()> {
+        let clipboard = self.clipboard.clone();
+        tokio::task::spawn_blocking(move || clipboard.set_text(&content)).await??;
         Ok(())
     }
 
-    pub async fn get_statistics(&self) -> Result<Statistics> {
-        // 总条目数
-        let total_entries: i64 = sqlx::query("SELECT COUNT(*) as count FROM clipboard_entries")
-            .fetch_one(self.db.pool())
-            .await?
-            .get("count");
+    /// 查找与内容Hash匹配的最新条目，增加复制次数并刷新时间
+    pub async fn bump_copy_count_for_content(
+        &self,
+        content: &str,
+    ) -> Result<Option<ClipboardEntry>> {
+        let hash = ClipboardMonitor::calculate_hash(content.trim().as_bytes());
+        let id: Option<String> = sqlx::query_scalar(
+            "SELECT id FROM clipboard_entries WHERE content_hash = ? ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(&hash)
+        .fetch_optional(self.db.pool())
+        .await?;
 
-        // 总复制次数
-        let total_copies: i64 = sqlx::query("SELECT SUM(copy_count) as sum FROM clipboard_entries")
-            .fetch_one(self.db.pool())
-            .await?
-            .try_get("sum")
-            .unwrap_or(0);
+        match id {
+            Some(id) => self.bump_copy_count(id).await,
+            None => Ok(None),
+        }
+    }
 
-        // 最多复制的条目
-        let most_copied = sqlx::query_as::<_, ClipboardEntry>(
-            "SELECT * FROM clipboard_entries ORDER BY copy_count DESC LIMIT 10",
+    /// 增加条目的复制次数（不超过 max_copy_count）并刷新时间
+    async fn bump_copy_count(&self, id: String) -> Result<Option<ClipboardEntry>> {
+        let max_copy_count = self.config_manager.lock().await.config.max_copy_count;
+        sqlx::query(
+            "UPDATE clipboard_entries SET copy_count = MIN(copy_count + 1, ?), created_at = ? WHERE id = ?",
         )
-        .fetch_all(self.db.pool())
+        .bind(max_copy_count.max(1))
+        .bind(Utc::now().timestamp_millis())
+        .bind(&id)
+        .execute(self.db.pool())
         .await?;
 
-        // 最近使用的应用
-        let recent_apps = sqlx::query(
-            r#"
-            SELECT source_app, COUNT(*) as count 
-            FROM clipboard_entries 
-            WHERE source_app IS NOT NULL 
-            GROUP BY source_app 
-            ORDER BY count DESC 
-            LIMIT 10
-            "#,
-        )
-        .fetch_all(self.db.pool())
-        .await?
-        .into_iter()
-        .map(|row| AppUsage {
-            app_name: row.get("source_app"),
-            count: row.get("count"),
-        })
-        .collect();
+        self.get_entry(id).await
+    }
 
-        Ok(Statistics {
-            total_entries,
-            total_copies,
-            most_copied,
-            recent_apps,
-        })
+    /// 若监听器会把这次写入当作新内容（尚未见过该内容，或会记录重复复制），
+    /// 则记为应用自身的写入，监听器读到时跳过，避免重复计数
+    async fn skip_monitor_detection(&self, hash: &str) {
+        let last_hash = match self.monitor.read().await.as_ref() {
+            Some(monitor) => monitor.last_hash().await,
+            None => None,
+        };
+        let dedup_mode = self.config_manager.lock().await.config.dedup_mode;
+        if last_hash.as_deref() != Some(hash)
+            || ClipboardMonitor::records_repeated_copies(dedup_mode, self.clipboard.change_count())
+        {
+            self.remember_recent_write(hash.to_string()).await;
+        }
     }
 
-    pub async fn copy_to_clipboard(&self, content: String) -> Result<()> {
-        tokio::task::spawn_blocking(move || -> Result<()> {
-            let mut clipboard = Clipboard::new()?;
-            clipboard.set_text(content)?;
-            Ok(())
-        })
-        .await??;
-        Ok(())
+    /// 通知前端有敏感内容未被记录
+    async fn emit_sensitive_skipped(&self, entry: &ClipboardEntry, subtype: ContentSubType) {
+        if let Some(handle) = self.app_handle.lock().await.as_ref() {
+            let payload = serde_json::json!({
+                "subtype": subtype.as_str(),
+                "source_app": entry.source_app,
+            });
+            if let Err(e) = handle.emit("sensitive_skipped", payload) {
+                log::error!("[AppState] 发送敏感内容跳过事件失败: {}", e);
+            }
+        }
+    }
+
+    async fn emit_text_oversize(
+        &self,
+        entry: &ClipboardEntry,
+        size_bytes: usize,
+        limit_bytes: usize,
+        policy: OversizePolicy,
+    ) {
+        if let Some(handle) = self.app_handle.lock().await.as_ref() {
+            let payload = serde_json::json!({
+                "size_bytes": size_bytes,
+                "limit_bytes": limit_bytes,
+                "policy": policy.as_str(),
+                "source_app": entry.source_app,
+            });
+            if let Err(e) = handle.emit("text_oversize", payload) {
+                log::error!("[AppState] 发送文本超限事件失败: {}", e);
+            }
+        }
+    }
+
+    /// 把文本截断到 max_bytes 以内（不拆开多字节字符），并重新计算哈希、子类型和元数据
+    fn truncate_text_entry(entry: &mut ClipboardEntry, max_bytes: usize) {
+        let Some(content) = entry.content_data.as_mut() else {
+            return;
+        };
+        let mut end = max_bytes.min(content.len());
+        while !content.is_char_boundary(end) {
+            end -= 1;
+        }
+        content.truncate(end);
+        entry.preview = Some(text_preview(content));
+
+        let (subtype, metadata) = ContentDetector::detect(content);
+        entry.content_hash = ClipboardMonitor::calculate_hash(content.trim().as_bytes());
+        entry.content_subtype = Some(subtype.as_str().to_string());
+        entry.metadata = metadata.and_then(|m| serde_json::to_string(&m).ok());
+    }
+
+    /// 标记统计数据已变化。等待 STATISTICS_UPDATE_DELAY 后计算一次统计，
+    /// 通过 statistics-updated 事件推送给前端并广播给 statistics_tx 的订阅者；
+    /// 等待期间的其他变化合并到这一次，不会重复计算
+    fn notify_statistics_changed(&self) {
+        if self.statistics_pending.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let state = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(STATISTICS_UPDATE_DELAY).await;
+            // 先清除标记，计算期间发生的变化会安排下一次推送
+            state.statistics_pending.store(false, Ordering::SeqCst);
+            let statistics = match state.get_statistics(None).await {
+                Ok(statistics) => statistics,
+                Err(e) => {
+                    log::warn!("[AppState] 计算统计数据失败: {}", e);
+                    return;
+                }
+            };
+
+            if let Some(handle) = state.app_handle.lock().await.as_ref() {
+                if let Err(e) = handle.emit("statistics-updated", &statistics) {
+                    log::error!("[AppState] 发送统计更新事件失败: {}", e);
+                }
+            }
+            // 没有订阅者时发送失败，可以忽略
+            let _ = state.statistics_tx.send(statistics);
+        });
+    }
+
+    async fn emit_monitoring_toggled(&self, is_monitoring: bool) {
+        if let Some(handle) = self.app_handle.lock().await.as_ref() {
+            if let Err(e) = handle.emit("monitoring_toggled", is_monitoring) {
+                log::error!("[AppState] 发送监听状态事件失败: {}", e);
+            }
+        }
+    }
+
+    async fn emit_clipboard_update(&self, entry: &ClipboardEntry) {
+        if let Some(handle) = self.app_handle.lock().await.as_ref() {
+            if let Err(e) = handle.emit("clipboard-update", entry) {
+                log::error!("[AppState] 发送更新事件失败: {}", e);
+            }
+        }
     }
 
     pub async fn copy_image_to_clipboard(&self, _file_path: String) -> Result<()> {
@@ -341,6 +1536,7 @@ impl AppState {
         #[cfg(target_os = "windows")]
         {
             // Windows图片复制支持 - 使用arboard
+            let clipboard = self.clipboard.clone();
             tokio::task::spawn_blocking(move || -> Result<()> {
                 use image::ImageReader;
                 use std::fs;
@@ -350,34 +1546,32 @@ impl AppState {
                     .with_guessed_format()?
                     .decode()?;
 
-                let rgba_img = img.to_rgba8();
-                let (width, height) = rgba_img.dimensions();
-
-                let img_data = arboard::ImageData {
-                    width: width as usize,
-                    height: height as usize,
-                    bytes: rgba_img.into_raw().into(),
-                };
-
-                let mut clipboard = Clipboard::new()?;
-                clipboard.set_image(img_data)?;
-
-                Ok(())
+                clipboard.set_image(rgba_image_data(&img))
             })
             .await??;
         }
 
         #[cfg(not(any(target_os = "macos", target_os = "windows")))]
         {
-            return Err(anyhow::anyhow!("Image copy not supported on this platform"));
+            return Err(
+                CommandError::unsupported("Image copy not supported on this platform").into(),
+            );
         }
 
         Ok(())
     }
 
-    pub async fn set_skip_next_clipboard_change(&self, skip: bool) {
-        let mut skip_guard = self.skip_next_change.lock().await;
-        *skip_guard = skip;
+    /// 记录应用即将写入剪贴板的内容 Hash，监听器在 RECENT_WRITE_TTL 内读到该内容时不再记录
+    pub async fn remember_recent_write(&self, hash: String) {
+        self.recent_writes
+            .lock()
+            .await
+            .insert(hash, std::time::Instant::now());
+    }
+
+    /// 写入剪贴板失败时撤销记录，避免之后外部复制的相同内容被跳过
+    pub async fn forget_recent_write(&self, hash: &str) {
+        self.recent_writes.lock().await.remove(hash);
     }
 
     pub async fn paste_text(
@@ -385,71 +1579,108 @@ impl AppState {
         content: String,
         _app_handle: Option<tauri::AppHandle>,
     ) -> Result<()> {
-        tokio::task::spawn_blocking(move || -> Result<()> {
-            let mut clipboard = Clipboard::new()?;
-            clipboard.set_text(content)?;
-            Ok(())
-        })
-        .await??;
+        self.paste_plain_text(content).await
+    }
+
+    /// 清空剪贴板后只写入 text/plain 再粘贴，剪贴板中原有的 RTF/HTML、图片等格式都会被清除
+    pub async fn paste_plain_text(&self, content: String) -> Result<()> {
+        self.set_text_as_recopy(content).await?;
+        self.trigger_system_paste().await
+    }
+
+    /// 按条目子类型应用配置的转换后粘贴（如颜色转为 hex、时间戳转为 ISO 8601），未配置时原样粘贴。
+    /// 转换结果不另存为新条目，只增加原条目的复制次数；返回实际粘贴的文本
+    pub async fn smart_paste(&self, id: String) -> Result<String> {
+        let entry = self
+            .get_entry(id.clone())
+            .await?
+            .ok_or_else(|| CommandError::not_found(format!("Entry not found: {}", id)))?;
+        if entry.content_type != ContentType::Text.as_str() {
+            return Err(CommandError::unsupported(
+                "Smart paste is only supported for text entries",
+            )
+            .into());
+        }
+
+        let smart_paste = self.get_config().await?.smart_paste;
+        let transform = entry
+            .content_subtype
+            .as_deref()
+            .and_then(|subtype| smart_paste.get(subtype).copied());
+        let content = entry.content_data.unwrap_or_default();
+        let pasted = match transform {
+            Some(transform) => transform.apply(&content)?,
+            None => content,
+        };
 
+        self.copy_generated_text(&pasted, false).await?;
+        if let Some(updated) = self.bump_copy_count(entry.id).await? {
+            self.emit_clipboard_update(&updated).await;
+        }
+        self.trigger_system_paste().await?;
+        Ok(pasted)
+    }
+
+    /// 本应用在系统中的进程名，用于粘贴前隐藏窗口；未设置 AppHandle 时返回 None
+    #[cfg(target_os = "macos")]
+    async fn app_process_name(&self) -> Option<String> {
+        self.app_handle
+            .lock()
+            .await
+            .as_ref()
+            .map(|handle| handle.package_info().name.clone())
+    }
+
+    /// 切换回之前的应用并发送粘贴快捷键
+    async fn trigger_system_paste(&self) -> Result<()> {
         // 切换应用焦点并粘贴（macOS）
         #[cfg(target_os = "macos")]
         {
-            tokio::task::spawn_blocking(move || -> Result<()> {
-                use std::process::Command;
-
+            let focus_delay_ms = self.config_manager.lock().await.config.paste_focus_delay_ms;
+            let process_name = self.app_process_name().await;
+            tokio::task::spawn_blocking(move || {
                 log::info!("[paste_text] 开始执行粘贴流程");
 
-                // 隐藏clipboard-app窗口，让下层应用自动获得焦点
-                let hide_and_paste_script = r#"
-                    tell application "System Events"
-                        -- 隐藏clipboard-app窗口（不是最小化）
-                        set visible of process "clipboard-app" to false
-                        
-                        -- 等待一小段时间让焦点切换
-                        delay 0.2
-                        
-                        -- 获取当前前台应用
-                        set frontApp to first application process whose frontmost is true
-                        set frontAppName to name of frontApp
-                        
-                        -- 执行粘贴（如果不是clipboard-app）
-                        if frontAppName is not "clipboard-app" then
-                            keystroke "v" using {command down}
-                            return "Pasted to: " & frontAppName
-                        else
-                            return "Failed: still on clipboard-app"
-                        end if
-                    end tell
-                "#;
-
-                let result = Command::new("osascript")
-                    .arg("-e")
-                    .arg(hide_and_paste_script)
-                    .output();
-
-                match result {
-                    Ok(output) => {
-                        if output.status.success() {
-                            let result_msg =
-                                String::from_utf8_lossy(&output.stdout).trim().to_string();
-                            log::info!("[paste_text] {}", result_msg);
-                        } else {
-                            let stderr = String::from_utf8_lossy(&output.stderr);
-                            log::error!("[paste_text] AppleScript错误: {}", stderr);
-                        }
-                    }
-                    Err(e) => log::error!("[paste_text] 执行失败: {}", e),
-                }
-
-                Ok(())
+                // 隐藏本应用窗口，让下层应用自动获得焦点后粘贴
+                crate::utils::paste_script::paste_into_frontmost_app(
+                    process_name.as_deref(),
+                    focus_delay_ms,
+                    "Pasted to",
+                    "paste_text",
+                );
             })
-            .await??;
+            .await?;
         }
 
         Ok(())
     }
 
+    /// 把 data URL 中的图片直接写入剪贴板，不经过磁盘文件；写入的内容不会被监听器记录为新条目
+    pub async fn copy_image_from_base64(&self, data_url: String) -> Result<()> {
+        let image_data = data_url::decode_image_data_url(&data_url)?;
+        let img = image::load_from_memory(&image_data)
+            .map_err(|e| CommandError::invalid_input(format!("Invalid image data: {}", e)))?;
+
+        let hash = ClipboardMonitor::calculate_image_hash(&image_data);
+        if let Some(hash) = &hash {
+            self.remember_recent_write(hash.clone()).await;
+        }
+        let clipboard = self.clipboard.clone();
+        let result =
+            tokio::task::spawn_blocking(move || clipboard.set_image(rgba_image_data(&img))).await?;
+        if let Err(e) = result {
+            if let Some(hash) = &hash {
+                self.forget_recent_write(hash).await;
+            }
+            return Err(e.context("Failed to set image to clipboard"));
+        }
+        log::info!(
+            "[copy_image_from_base64] 已将 {} 字节的图片写入剪贴板",
+            image_data.len()
+        );
+        Ok(())
+    }
+
     pub async fn paste_image(
         &self,
         file_path: String,
@@ -469,157 +1700,481 @@ impl AppState {
         };
 
         if !absolute_path.exists() {
-            return Err(anyhow::anyhow!("File not found: {:?}", absolute_path));
+            return Err(
+                CommandError::not_found(format!("File not found: {:?}", absolute_path)).into(),
+            );
         }
 
+        let clipboard = self.clipboard.clone();
         tokio::task::spawn_blocking(move || -> Result<()> {
             let image_data = fs::read(&absolute_path)?;
 
-            // 使用arboard设置图片到剪切板
-            let mut clipboard = Clipboard::new()?;
+            // 动图直接写入原始数据以保留动画，不支持的平台退回到只粘贴第一帧
+            if gif::is_animated(&image_data) {
+                match gif::set_clipboard_gif(&image_data) {
+                    Ok(()) => return Ok(()),
+                    Err(e) => {
+                        log::warn!("[paste_image] 无法以 GIF 写入剪贴板，仅粘贴第一帧: {}", e)
+                    }
+                }
+            }
 
             // 确定图片格式
             let img = image::load_from_memory(&image_data)
                 .map_err(|e| anyhow::anyhow!("Failed to load image: {}", e))?;
 
-            // 转换为RGB格式
-            let rgba_img = img.to_rgba8();
-            let (width, height) = rgba_img.dimensions();
-
-            let img_data = arboard::ImageData {
-                width: width as usize,
-                height: height as usize,
-                bytes: rgba_img.into_raw().into(),
-            };
-
+            // 转换为 RGBA 后设置到剪切板
             clipboard
-                .set_image(img_data)
-                .map_err(|e| anyhow::anyhow!("Failed to set image to clipboard: {}", e))?;
-
-            Ok(())
+                .set_image(rgba_image_data(&img))
+                .map_err(|e| e.context("Failed to set image to clipboard"))
         })
         .await??;
 
         // 切换应用焦点并粘贴（macOS）
         #[cfg(target_os = "macos")]
         {
-            tokio::task::spawn_blocking(move || -> Result<()> {
-                use std::process::Command;
-
+            let focus_delay_ms = self.config_manager.lock().await.config.paste_focus_delay_ms;
+            let process_name = self.app_process_name().await;
+            tokio::task::spawn_blocking(move || {
                 log::info!("[paste_image] 开始执行图片粘贴流程");
 
-                // 隐藏clipboard-app窗口，让下层应用自动获得焦点
-                let hide_and_paste_script = r#"
-                    tell application "System Events"
-                        -- 隐藏clipboard-app窗口（不是最小化）
-                        set visible of process "clipboard-app" to false
-                        
-                        -- 等待一小段时间让焦点切换
-                        delay 0.2
-                        
-                        -- 获取当前前台应用
-                        set frontApp to first application process whose frontmost is true
-                        set frontAppName to name of frontApp
-                        
-                        -- 执行粘贴（如果不是clipboard-app）
-                        if frontAppName is not "clipboard-app" then
-                            keystroke "v" using {command down}
-                            return "Pasted image to: " & frontAppName
-                        else
-                            return "Failed: still on clipboard-app"
-                        end if
-                    end tell
-                "#;
-
-                let result = Command::new("osascript")
-                    .arg("-e")
-                    .arg(hide_and_paste_script)
-                    .output();
-
-                match result {
-                    Ok(output) => {
-                        if output.status.success() {
-                            let result_msg =
-                                String::from_utf8_lossy(&output.stdout).trim().to_string();
-                            log::info!("[paste_image] {}", result_msg);
-                        } else {
-                            let stderr = String::from_utf8_lossy(&output.stderr);
-                            log::error!("[paste_image] AppleScript错误: {}", stderr);
-                        }
-                    }
-                    Err(e) => log::error!("[paste_image] 执行失败: {}", e),
-                }
-
-                Ok(())
+                // 隐藏本应用窗口，让下层应用自动获得焦点后粘贴
+                crate::utils::paste_script::paste_into_frontmost_app(
+                    process_name.as_deref(),
+                    focus_delay_ms,
+                    "Pasted image to",
+                    "paste_image",
+                );
             })
-            .await??;
+            .await?;
         }
 
         Ok(())
     }
 
+    /// 将图片复制为临时文件，以文件引用的形式写入剪贴板后粘贴，
+    /// 用于只接受文件拖放、不支持直接粘贴像素的应用。临时文件在一段时间后删除
+    pub async fn paste_image_as_file(&self, file_path: String) -> Result<()> {
+        let source_path = self.resolve_image_path(&file_path)?;
+        if !source_path.exists() {
+            return Err(
+                CommandError::not_found(format!("File not found: {:?}", source_path)).into(),
+            );
+        }
+
+        let extension = source_path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("png");
+        let temp_path = std::env::temp_dir().join(format!(
+            "clipboard-image-{}.{}",
+            uuid::Uuid::new_v4(),
+            extension
+        ));
+        tokio::fs::copy(&source_path, &temp_path).await?;
+
+        let clipboard = self.clipboard.clone();
+        let references = vec![temp_path.clone()];
+        let result =
+            tokio::task::spawn_blocking(move || clipboard.set_file_list(&references)).await?;
+        if let Err(e) = result {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(e);
+        }
+        log::info!(
+            "[paste_image_as_file] 已将图片以文件形式写入剪贴板: {:?}",
+            temp_path
+        );
+
+        tokio::spawn(async move {
+            tokio::time::sleep(PASTED_FILE_TTL).await;
+            if let Err(e) = tokio::fs::remove_file(&temp_path).await {
+                log::debug!(
+                    "[paste_image_as_file] 删除临时文件失败 {:?}: {}",
+                    temp_path,
+                    e
+                );
+            }
+        });
+
+        self.trigger_system_paste().await
+    }
+
+    /// 把文件条目以文件引用的形式写回剪贴板并粘贴，已不存在的文件会被跳过
+    pub async fn paste_file_list(&self, id: String) -> Result<()> {
+        let entry = self
+            .get_entry(id.clone())
+            .await?
+            .ok_or_else(|| CommandError::not_found(format!("Entry not found: {}", id)))?;
+        let file_list = entry
+            .metadata
+            .as_deref()
+            .filter(|_| entry.content_type == ContentType::File.as_str())
+            .and_then(FileListMetadata::from_entry_metadata)
+            .ok_or_else(|| {
+                CommandError::invalid_input("Only file entries can be pasted as files")
+            })?;
+
+        let paths: Vec<String> = file_list
+            .paths
+            .into_iter()
+            .filter(|path| std::path::Path::new(path).exists())
+            .collect();
+        if paths.is_empty() {
+            return Err(CommandError::not_found("Copied files not found on disk").into());
+        }
+
+        // 监听器读到的是实际写入的文件列表
+        let hash = ClipboardMonitor::file_list_hash(&paths);
+        self.skip_monitor_detection(&hash).await;
+        let clipboard = self.clipboard.clone();
+        let references: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+        let result =
+            tokio::task::spawn_blocking(move || clipboard.set_file_list(&references)).await?;
+        if let Err(e) = result {
+            self.forget_recent_write(&hash).await;
+            return Err(e);
+        }
+        log::info!("[paste_file_list] 已将 {} 个文件写入剪贴板", paths.len());
+
+        if let Some(entry) = self.bump_copy_count(entry.id).await? {
+            self.emit_clipboard_update(&entry).await;
+        }
+        self.trigger_system_paste().await
+    }
+
     // Configuration management methods
     pub async fn get_config(&self) -> Result<AppConfig> {
         let config_manager = self.config_manager.lock().await;
         Ok(config_manager.config.clone())
     }
 
-    pub async fn update_config(&self, config: AppConfig) -> Result<()> {
-        let mut config_manager = self.config_manager.lock().await;
-        config_manager.update_config(config).await?;
+    pub async fn update_config(&self, config: AppConfig) -> Result<()> {
+        let mut config_manager = self.config_manager.lock().await;
+        let level = config.log.level;
+        let level_changed = config_manager.config.log.level != level;
+        config_manager.update_config(config).await?;
+        // 只在级别变化时修改全局日志级别
+        if level_changed {
+            logging::apply_level(level);
+        }
+        Ok(())
+    }
+
+    /// 修改并保存日志级别，立即生效
+    pub async fn set_log_level(&self, level: LogLevel) -> Result<()> {
+        let mut config_manager = self.config_manager.lock().await;
+        let mut config = config_manager.config.clone();
+        config.log.level = level;
+        config_manager.update_config(config).await?;
+        logging::apply_level(level);
+        log::info!("[Logging] 日志级别已设置为 {}", level.as_str());
+        Ok(())
+    }
+
+    pub async fn get_window_state(&self) -> Option<WindowState> {
+        self.config_manager.lock().await.config.window_state
+    }
+
+    /// 记录主窗口的大小和位置，未变化时不写配置文件
+    pub async fn set_window_state(&self, window_state: WindowState) -> Result<()> {
+        let mut config_manager = self.config_manager.lock().await;
+        if config_manager.config.window_state == Some(window_state) {
+            return Ok(());
+        }
+        let mut config = config_manager.config.clone();
+        config.window_state = Some(window_state);
+        config_manager.update_config(config).await
+    }
+
+    /// 监听配置文件，外部编辑后自动重新加载
+    pub async fn watch_config_file(&self) -> Result<()> {
+        let config_path = self.config_manager.lock().await.config_path().clone();
+        let watch_dir = config_path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Config path has no parent directory"))?
+            .to_path_buf();
+
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                if let Ok(event) = res {
+                    let _ = event_tx.send(event);
+                }
+            })?;
+        // 监听目录而不是文件，编辑器常通过替换文件的方式保存
+        watcher.watch(&watch_dir, RecursiveMode::NonRecursive)?;
+
+        let state = self.clone();
+        tokio::spawn(async move {
+            // 任务持有 watcher，保证其生命周期
+            let _watcher = watcher;
+            log::info!("[ConfigWatcher] 开始监听配置文件: {:?}", config_path);
+
+            while let Some(event) = event_rx.recv().await {
+                let touches_config = event
+                    .paths
+                    .iter()
+                    .any(|path| path.file_name() == config_path.file_name());
+                if !touches_config || !(event.kind.is_modify() || event.kind.is_create()) {
+                    continue;
+                }
+
+                // 一次保存通常触发多个事件，稍等片刻后合并处理
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                while event_rx.try_recv().is_ok() {}
+
+                if let Err(e) = state.reload_config_from_disk().await {
+                    log::warn!("[ConfigWatcher] 重新加载配置失败，保留当前配置: {}", e);
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// 从磁盘重新加载配置，有变化时重新注册快捷键并通知前端
+    pub async fn reload_config_from_disk(&self) -> Result<bool> {
+        let (previous_shortcuts, previous_level, config) = {
+            let mut config_manager = self.config_manager.lock().await;
+            let previous_shortcuts = config_manager.config.shortcuts();
+            let previous_level = config_manager.config.log.level;
+            if !config_manager.reload_if_changed().await? {
+                return Ok(false);
+            }
+            (
+                previous_shortcuts,
+                previous_level,
+                config_manager.config.clone(),
+            )
+        };
+
+        log::info!("[ConfigWatcher] 配置文件已在外部修改，已重新加载");
+        if config.log.level != previous_level {
+            logging::apply_level(config.log.level);
+        }
+
+        let app_handle = self.app_handle.lock().await.clone();
+        let current_shortcuts = config.shortcuts();
+        for action in previous_shortcuts.keys().chain(current_shortcuts.keys()) {
+            let result = match (
+                previous_shortcuts.get(action),
+                current_shortcuts.get(action),
+            ) {
+                (previous, current) if previous == current => continue,
+                (_, Some(accelerator)) => {
+                    self.bind_shortcut(app_handle.as_ref(), *action, accelerator.clone())
+                        .await
+                }
+                (_, None) => self.unbind_shortcut(app_handle.as_ref(), *action).await,
+            };
+            if let Err(e) = result {
+                log::error!(
+                    "[ConfigWatcher] 重新注册快捷键 {} 失败: {}",
+                    action.as_str(),
+                    e
+                );
+            }
+        }
+
+        if let Some(handle) = app_handle {
+            if let Err(e) = handle.emit("config_reloaded", &config) {
+                log::error!("[ConfigWatcher] 发送配置重载事件失败: {}", e);
+            }
+        }
+
+        Ok(true)
+    }
+
+    // Global shortcut methods
+    pub async fn register_global_shortcut(
+        &self,
+        app_handle: AppHandle,
+        shortcut: String,
+    ) -> Result<()> {
+        self.bind_shortcut(Some(&app_handle), ShortcutAction::ShowWindow, shortcut)
+            .await
+    }
+
+    pub async fn unregister_global_shortcut(&self) -> Result<()> {
+        let app_handle = self.app_handle.lock().await.clone();
+        self.unbind_shortcut(app_handle.as_ref(), ShortcutAction::ShowWindow)
+            .await
+    }
+
+    /// 为动作注册全局快捷键并保存到配置
+    pub async fn register_action_shortcut(
+        &self,
+        action: ShortcutAction,
+        accelerator: String,
+    ) -> Result<()> {
+        let app_handle = self.app_handle.lock().await.clone();
+        self.bind_shortcut(app_handle.as_ref(), action, accelerator.clone())
+            .await?;
+        self.persist_shortcut(action, Some(accelerator)).await
+    }
+
+    pub async fn unregister_action_shortcut(&self, action: ShortcutAction) -> Result<()> {
+        let app_handle = self.app_handle.lock().await.clone();
+        self.unbind_shortcut(app_handle.as_ref(), action).await?;
+        self.persist_shortcut(action, None).await
+    }
+
+    pub async fn get_action_shortcuts(&self) -> BTreeMap<ShortcutAction, String> {
+        self.shortcuts.lock().await.accelerators()
+    }
+
+    /// 校验快捷键格式，并检查与系统及已注册快捷键的冲突
+    pub async fn validate_shortcut(
+        &self,
+        accelerator: &str,
+        action: Option<ShortcutAction>,
+    ) -> ShortcutValidation {
+        self.shortcuts.lock().await.validate(accelerator, action)
+    }
+
+    /// 查找触发的快捷键所绑定的动作
+    pub async fn shortcut_action(&self, shortcut: &Shortcut) -> Option<ShortcutAction> {
+        self.shortcuts.lock().await.action_for(shortcut)
+    }
+
+    /// 启动时注册配置中的所有快捷键
+    pub async fn register_configured_shortcuts(&self, app_handle: &AppHandle) {
+        let shortcuts = self.config_manager.lock().await.config.shortcuts();
+        for (action, accelerator) in shortcuts {
+            match self
+                .bind_shortcut(Some(app_handle), action, accelerator.clone())
+                .await
+            {
+                Ok(()) => log::info!(
+                    "Global shortcut registered on startup: {} -> {}",
+                    action.as_str(),
+                    accelerator
+                ),
+                Err(e) => log::error!(
+                    "Failed to register {} shortcut on startup: {}",
+                    action.as_str(),
+                    e
+                ),
+            }
+        }
+    }
+
+    /// 绑定动作的快捷键；没有应用句柄时（如测试中）只更新记录
+    async fn bind_shortcut(
+        &self,
+        app_handle: Option<&AppHandle>,
+        action: ShortcutAction,
+        accelerator: String,
+    ) -> Result<()> {
+        let mut shortcuts = self.shortcuts.lock().await;
+        let shortcut = shortcuts.check(action, &accelerator)?;
+        let previous = shortcuts.get(action).copied();
+
+        if let Some(handle) = app_handle {
+            if previous != Some(shortcut) {
+                let global_shortcut_manager = handle.global_shortcut();
+
+                // Unregister the action's existing shortcut if any
+                if let Some(previous) = previous {
+                    global_shortcut_manager
+                        .unregister(previous)
+                        .map_err(|e| anyhow::anyhow!("Failed to unregister shortcut: {}", e))?;
+                }
+
+                // Register new shortcut - the API only takes the shortcut, callback is handled via events
+                global_shortcut_manager
+                    .register(shortcut)
+                    .map_err(|e| anyhow::anyhow!("Failed to register shortcut: {}", e))?;
+            }
+        }
+
+        shortcuts.insert(action, accelerator, shortcut);
         Ok(())
     }
 
-    // Global shortcut methods
-    pub async fn register_global_shortcut(
+    async fn unbind_shortcut(
         &self,
-        app_handle: AppHandle,
-        shortcut: String,
+        app_handle: Option<&AppHandle>,
+        action: ShortcutAction,
     ) -> Result<()> {
-        let parsed_shortcut = shortcut
-            .parse::<Shortcut>()
-            .map_err(|e| anyhow::anyhow!("Invalid shortcut format: {}", e))?;
-
-        let global_shortcut_manager = app_handle.global_shortcut();
-
-        // Unregister existing shortcut if any
-        if let Some(current) = self.current_shortcut.lock().await.as_ref() {
-            let current_shortcut = current
-                .parse::<Shortcut>()
-                .map_err(|e| anyhow::anyhow!("Invalid current shortcut: {}", e))?;
-            global_shortcut_manager
-                .unregister(current_shortcut)
+        let mut shortcuts = self.shortcuts.lock().await;
+        if let (Some(handle), Some(shortcut)) = (app_handle, shortcuts.get(action)) {
+            handle
+                .global_shortcut()
+                .unregister(*shortcut)
                 .map_err(|e| anyhow::anyhow!("Failed to unregister shortcut: {}", e))?;
         }
+        shortcuts.remove(action);
+        Ok(())
+    }
 
-        // Register new shortcut - the API only takes the shortcut, callback is handled via events
-        global_shortcut_manager
-            .register(parsed_shortcut)
-            .map_err(|e| anyhow::anyhow!("Failed to register shortcut: {}", e))?;
+    async fn persist_shortcut(
+        &self,
+        action: ShortcutAction,
+        accelerator: Option<String>,
+    ) -> Result<()> {
+        let mut config_manager = self.config_manager.lock().await;
+        let mut config = config_manager.config.clone();
+        config.set_shortcut(action, accelerator);
+        config_manager.update_config(config).await
+    }
 
-        // Update stored shortcut
-        let mut current_shortcut = self.current_shortcut.lock().await;
-        *current_shortcut = Some(shortcut);
+    /// 粘贴最近的一条记录
+    pub async fn paste_last_entry(&self) -> Result<()> {
+        match self.recent_entry(0).await {
+            Ok(entry) => self.paste_entry(entry).await,
+            Err(_) => {
+                log::debug!("[AppState] 没有历史记录可粘贴");
+                Ok(())
+            }
+        }
+    }
 
-        Ok(())
+    /// 粘贴第 index 条历史（从 0 开始，最新的在前）
+    pub async fn paste_recent(&self, index: u32) -> Result<()> {
+        let entry = self.recent_entry(index).await?;
+        self.paste_entry(entry).await
     }
 
-    pub async fn unregister_global_shortcut(&self) -> Result<()> {
-        if let Some(app_handle) = self.app_handle.lock().await.as_ref() {
-            if let Some(current) = self.current_shortcut.lock().await.as_ref() {
-                let current_shortcut = current
-                    .parse::<Shortcut>()
-                    .map_err(|e| anyhow::anyhow!("Invalid current shortcut: {}", e))?;
-                let global_shortcut_manager = app_handle.global_shortcut();
-                global_shortcut_manager
-                    .unregister(current_shortcut)
-                    .map_err(|e| anyhow::anyhow!("Failed to unregister shortcut: {}", e))?;
+    /// 按时间倒序取第 index 条历史，超出范围时报错
+    pub async fn recent_entry(&self, index: u32) -> Result<ClipboardEntry> {
+        let entry = self
+            .get_clipboard_history(
+                Some(1),
+                Some(index.min(i32::MAX as u32) as i32),
+                None,
+                HistoryFilter::default(),
+            )
+            .await?
+            .into_iter()
+            .next();
 
-                let mut current_shortcut_guard = self.current_shortcut.lock().await;
-                *current_shortcut_guard = None;
+        match entry {
+            Some(entry) => Ok(entry),
+            None => {
+                let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM clipboard_entries")
+                    .fetch_one(self.db.pool())
+                    .await?;
+                Err(CommandError::not_found(format!(
+                    "History index {} is out of range ({} entries)",
+                    index, total
+                ))
+                .into())
             }
         }
-        Ok(())
+    }
+
+    async fn paste_entry(&self, entry: ClipboardEntry) -> Result<()> {
+        if entry.content_type == ContentType::Image.as_str() {
+            let file_path = entry
+                .file_path
+                .ok_or_else(|| anyhow::anyhow!("Image entry has no file path"))?;
+            self.paste_image(file_path, None).await
+        } else {
+            self.paste_text(entry.content_data.unwrap_or_default(), None)
+                .await
+        }
     }
 
     // Auto startup methods
@@ -654,7 +2209,7 @@ impl AppState {
     // Cache statistics
     pub async fn get_cache_statistics(&self) -> Result<CacheStatistics> {
         // Get database size
-        let db_path = self.get_db_path()?;
+        let db_path = self.get_db_path().await?;
         let db_size = std::fs::metadata(&db_path).map(|m| m.len()).unwrap_or(0);
 
         // Get images directory size
@@ -685,12 +2240,407 @@ impl AppState {
         .await?
         .get("count");
 
+        // 按子类型分组计数，没有子类型的条目（图片、旧数据）归为 unknown
+        let subtype_counts = sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT COALESCE(content_subtype, 'unknown') AS subtype, COUNT(*) AS count
+            FROM clipboard_entries
+            GROUP BY subtype
+            ORDER BY count DESC, subtype ASC
+            "#,
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
         Ok(CacheStatistics {
             db_size_bytes: db_size,
             images_size_bytes: images_size,
             total_entries,
             text_entries,
             image_entries,
+            subtype_counts,
+            media_metadata: self.media_metadata.lock().await.stats(),
+        })
+    }
+
+    /// 压缩数据库，回收大量删除后留下的空闲页
+    pub async fn compact_database(&self) -> Result<CompactResult> {
+        let (before, after) = self.db.compact().await?;
+        log::info!("[AppState] 数据库压缩完成: {} -> {} 字节", before, after);
+        Ok(CompactResult {
+            db_size_before_bytes: before,
+            db_size_after_bytes: after,
+        })
+    }
+
+    /// 删除图片目录中没有被任何条目引用的文件（如崩溃时遗留的图片），以及原图已不存在的缩略图，
+    /// 返回释放的字节数。最近写入的文件可能还在保存中，不会被删除
+    pub async fn clean_orphaned_images(&self) -> Result<u64> {
+        let imgs_dir = self.get_images_path()?;
+        if !imgs_dir.exists() {
+            return Ok(0);
+        }
+
+        let referenced: std::collections::HashSet<PathBuf> = sqlx::query_scalar::<_, String>(
+            "SELECT file_path FROM clipboard_entries WHERE file_path IS NOT NULL",
+        )
+        .fetch_all(self.db.pool())
+        .await?
+        .iter()
+        .map(|file_path| self.processor.resolve_path(file_path))
+        .collect();
+
+        let now = std::time::SystemTime::now();
+        let is_stale = |metadata: &std::fs::Metadata| {
+            metadata
+                .modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age >= ORPHAN_IMAGE_GRACE)
+        };
+        let mut removed = 0;
+        let mut freed_bytes = 0;
+        let mut remove =
+            |path: &std::path::Path, metadata: &std::fs::Metadata| match std::fs::remove_file(path)
+            {
+                Ok(()) => {
+                    removed += 1;
+                    freed_bytes += metadata.len();
+                }
+                Err(e) => log::warn!("[AppState] 删除孤立图片失败 {:?}: {}", path, e),
+            };
+
+        let mut remaining_stems = std::collections::HashSet::new();
+        for dir_entry in std::fs::read_dir(&imgs_dir)?.flatten() {
+            let path = dir_entry.path();
+            let Ok(metadata) = dir_entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            if referenced.contains(&path) || !is_stale(&metadata) {
+                if let Some(stem) = path.file_stem() {
+                    remaining_stems.insert(stem.to_os_string());
+                }
+                continue;
+            }
+            remove(&path, &metadata);
+        }
+
+        // 缩略图按原图文件名缓存在 thumbs/<尺寸>/ 下
+        let size_dirs = std::fs::read_dir(self.processor.thumbs_dir())
+            .into_iter()
+            .flatten()
+            .flatten();
+        for size_dir in size_dirs {
+            let Ok(thumbnails) = std::fs::read_dir(size_dir.path()) else {
+                continue;
+            };
+            for dir_entry in thumbnails.flatten() {
+                let path = dir_entry.path();
+                let Ok(metadata) = dir_entry.metadata() else {
+                    continue;
+                };
+                let source_exists = path
+                    .file_stem()
+                    .is_some_and(|stem| remaining_stems.contains(stem));
+                if metadata.is_file() && !source_exists && is_stale(&metadata) {
+                    remove(&path, &metadata);
+                }
+            }
+        }
+
+        log::info!(
+            "[AppState] 清理了 {} 个孤立图片和缩略图，释放 {} 字节",
+            removed,
+            freed_bytes
+        );
+        Ok(freed_bytes)
+    }
+
+    /// 把全部条目（含收藏标记、元数据）和引用的图片文件打包成一个 tar 归档，
+    /// 用于在设备之间迁移历史。图片文件丢失的条目不导出
+    pub async fn export_sync_bundle(&self, path: PathBuf) -> Result<SyncExportResult> {
+        let entries = sqlx::query_as::<_, ClipboardEntry>(
+            "SELECT * FROM clipboard_entries ORDER BY created_at ASC",
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut bundled = Vec::with_capacity(entries.len());
+        let mut images = Vec::new();
+        for mut entry in entries {
+            if let Some(file_path) = entry.file_path.clone() {
+                let source = self.processor.resolve_path(&file_path);
+                let Some(file_name) = source
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map(str::to_string)
+                    .filter(|_| source.exists())
+                else {
+                    log::warn!("[SyncBundle] 图片文件不存在，跳过条目 {}", entry.id);
+                    continue;
+                };
+                entry.file_path = Some(sync_bundle::image_archive_path(&file_name));
+                images.push((file_name, source));
+            }
+            bundled.push(entry);
+        }
+
+        let manifest = sync_bundle::Manifest {
+            version: sync_bundle::BUNDLE_VERSION,
+            exported_at: Utc::now().timestamp_millis(),
+            entries: bundled,
+        };
+        let result = SyncExportResult {
+            entries: manifest.entries.len() as u32,
+            images: images.len() as u32,
+        };
+        tokio::task::spawn_blocking(move || sync_bundle::write(&path, &manifest, &images))
+            .await??;
+
+        log::info!(
+            "[SyncBundle] 已导出 {} 条记录, {} 张图片",
+            result.entries,
+            result.images
+        );
+        Ok(result)
+    }
+
+    /// 导入同步包：按 content_hash 合并，已有的条目只合并收藏标记、复制次数和时间，
+    /// 新条目连同图片一起导入
+    pub async fn import_sync_bundle(&self, path: PathBuf) -> Result<SyncImportResult> {
+        let manifest = {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || sync_bundle::read_manifest(&path)).await??
+        };
+        let max_copy_count = self.config_manager.lock().await.config.max_copy_count;
+
+        let existing_hashes: std::collections::HashSet<String> =
+            sqlx::query_scalar("SELECT content_hash FROM clipboard_entries")
+                .fetch_all(self.db.pool())
+                .await?
+                .into_iter()
+                .collect();
+        let wanted: std::collections::HashSet<String> = manifest
+            .entries
+            .iter()
+            .filter(|entry| !existing_hashes.contains(&entry.content_hash))
+            .filter_map(|entry| entry.file_path.as_deref())
+            .map(|file_path| file_path.trim_start_matches("imgs/").to_string())
+            .collect();
+        let extracted = {
+            let imgs_dir = self.processor.imgs_dir().clone();
+            tokio::task::spawn_blocking(move || {
+                sync_bundle::extract_images(&path, &wanted, &imgs_dir)
+            })
+            .await??
+        };
+
+        let mut result = SyncImportResult {
+            imported: 0,
+            merged: 0,
+            images: extracted.len() as u32,
+        };
+        let mut tx = self.db.pool().begin().await?;
+        let mut inserted_hashes = std::collections::HashSet::new();
+        for mut entry in manifest.entries {
+            if existing_hashes.contains(&entry.content_hash)
+                || !inserted_hashes.insert(entry.content_hash.clone())
+            {
+                sqlx::query(
+                    r#"
+                    UPDATE clipboard_entries
+                    SET is_favorite = is_favorite OR ?,
+                        copy_count = MIN(MAX(copy_count, ?), ?),
+                        created_at = MAX(created_at, ?)
+                    WHERE content_hash = ?
+                    "#,
+                )
+                .bind(entry.is_favorite)
+                .bind(entry.copy_count)
+                .bind(max_copy_count.max(1))
+                .bind(entry.created_at)
+                .bind(&entry.content_hash)
+                .execute(&mut *tx)
+                .await?;
+                result.merged += 1;
+                continue;
+            }
+
+            let mut phash = None;
+            if let Some(file_path) = &entry.file_path {
+                if !extracted.contains(file_path.trim_start_matches("imgs/")) {
+                    log::warn!("[SyncBundle] 同步包中缺少图片 {}，跳过", file_path);
+                    continue;
+                }
+                phash = self.processor.perceptual_hash(file_path).await.ok();
+            }
+
+            // 两台设备上的 id 都是随机生成的，碰撞时换一个新 id
+            let id_taken: bool =
+                sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM clipboard_entries WHERE id = ?)")
+                    .bind(&entry.id)
+                    .fetch_one(&mut *tx)
+                    .await?;
+            if id_taken {
+                entry.id = uuid::Uuid::new_v4().to_string();
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO clipboard_entries
+                (id, content_hash, content_type, content_data, source_app,
+                 created_at, copy_count, file_path, is_favorite, content_subtype, metadata, app_bundle_id,
+                 source_window_title, phash, preview)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&entry.id)
+            .bind(&entry.content_hash)
+            .bind(&entry.content_type)
+            .bind(&entry.content_data)
+            .bind(&entry.source_app)
+            .bind(entry.created_at)
+            .bind(entry.copy_count.clamp(1, max_copy_count.max(1)))
+            .bind(&entry.file_path)
+            .bind(entry.is_favorite)
+            .bind(&entry.content_subtype)
+            .bind(&entry.metadata)
+            .bind(&entry.app_bundle_id)
+            .bind(&entry.source_window_title)
+            .bind(&phash)
+            .bind(entry.build_preview())
+            .execute(&mut *tx)
+            .await?;
+            result.imported += 1;
+        }
+        tx.commit().await?;
+        if result.imported > 0 || result.merged > 0 {
+            self.notify_statistics_changed();
+        }
+
+        log::info!(
+            "[SyncBundle] 导入完成: 新增 {} 条, 合并 {} 条, 图片 {} 张",
+            result.imported,
+            result.merged,
+            result.images
+        );
+        Ok(result)
+    }
+
+    /// 备份目录，位于数据库文件旁边
+    async fn backups_dir(&self) -> Result<PathBuf> {
+        let db_path = self.get_db_path().await?;
+        let data_dir = db_path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Database path has no parent directory"))?;
+        Ok(data_dir.join(backup::BACKUP_DIR_NAME))
+    }
+
+    /// 立即备份数据库，完成后按配置只保留最新的 keep 个备份
+    pub async fn backup_now(&self) -> Result<BackupInfo> {
+        let keep = self.get_config().await?.backup.keep;
+        let dir = self.backups_dir().await?;
+        std::fs::create_dir_all(&dir)?;
+
+        let now = Utc::now();
+        let path = dir.join(backup::backup_file_name(now));
+        self.db.backup_to(&path).await?;
+        let size_bytes = std::fs::metadata(&path)?.len();
+
+        let pruned = backup::prune_backups(&dir, keep)?;
+        log::info!(
+            "[Backup] 已备份数据库到 {:?}（{} 字节），删除了 {} 个旧备份",
+            path,
+            size_bytes,
+            pruned.len()
+        );
+
+        Ok(BackupInfo {
+            path: path.to_string_lossy().to_string(),
+            size_bytes,
+            created_at: now.timestamp_millis(),
+        })
+    }
+
+    /// 用备份文件替换全部历史记录，完成后通过 history_restored 事件通知前端重新加载。
+    /// 备份只包含数据库，图片文件已不存在的图片条目在恢复后删除
+    pub async fn restore_backup(&self, path: PathBuf) -> Result<RestoreResult> {
+        let restored = self.db.restore_from(&path).await?;
+        let missing_images = self.remove_entries_with_missing_images().await?;
+        let restored = restored.saturating_sub(missing_images as u64);
+        log::info!("[Backup] 已从 {:?} 恢复 {} 条记录", path, restored);
+        if missing_images > 0 {
+            log::warn!(
+                "[Backup] {} 条图片记录的文件已不存在，已从恢复的历史中删除",
+                missing_images
+            );
+        }
+
+        if let Some(handle) = self.app_handle.lock().await.as_ref() {
+            if let Err(e) = handle.emit("history_restored", restored) {
+                log::error!("[Backup] 发送历史恢复事件失败: {}", e);
+            }
+        }
+        self.notify_statistics_changed();
+        Ok(RestoreResult {
+            restored,
+            missing_images,
+        })
+    }
+
+    /// 删除引用的图片文件已不存在的条目，返回删除的数量
+    async fn remove_entries_with_missing_images(&self) -> Result<u32> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT id, file_path FROM clipboard_entries WHERE file_path IS NOT NULL",
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut tx = self.db.pool().begin().await?;
+        let mut removed = 0u32;
+        for (id, file_path) in rows {
+            if self.processor.resolve_path(&file_path).exists() {
+                continue;
+            }
+            log::debug!("[Backup] 图片文件不存在，删除条目 {}: {}", id, file_path);
+            sqlx::query("DELETE FROM clipboard_entries WHERE id = ?")
+                .bind(&id)
+                .execute(&mut *tx)
+                .await?;
+            removed += 1;
+        }
+        tx.commit().await?;
+        Ok(removed)
+    }
+
+    /// 自动备份开启且距最新备份已超过 interval_hours 时备份一次，未备份时返回 None
+    pub async fn run_scheduled_backup(&self) -> Result<Option<BackupInfo>> {
+        let config = self.get_config().await?.backup;
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let latest = backup::latest_backup_time(&self.backups_dir().await?)?;
+        let interval = std::time::Duration::from_secs(config.interval_hours as u64 * 3600);
+        if !backup::is_backup_due(latest, std::time::SystemTime::now(), interval) {
+            return Ok(None);
+        }
+        self.backup_now().await.map(Some)
+    }
+
+    /// 启动自动备份任务，定期检查是否需要备份；配置修改后下一次检查即生效
+    pub fn spawn_backup_task(&self) -> tauri::async_runtime::JoinHandle<()> {
+        let state = self.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                if let Err(e) = state.run_scheduled_backup().await {
+                    log::error!("[Backup] 自动备份失败: {}", e);
+                }
+                tokio::time::sleep(BACKUP_CHECK_INTERVAL).await;
+            }
         })
     }
 
@@ -732,59 +2682,53 @@ impl AppState {
         let config = self.get_config().await?;
         let now = Utc::now().timestamp_millis();
 
-        // Get cutoff times, skip cleanup for Never expiry
-        let text_cutoff = match config.text.expiry.as_days() {
-            Some(days) => {
-                let expiry_ms = (days as i64) * 24 * 60 * 60 * 1000;
-                Some(now - expiry_ms)
-            }
-            None => None, // Never expire
-        };
-
-        let image_cutoff = match config.image.expiry.as_days() {
-            Some(days) => {
-                let expiry_ms = (days as i64) * 24 * 60 * 60 * 1000;
-                Some(now - expiry_ms)
-            }
-            None => None, // Never expire
-        };
-
-        // Get entries to remove
-        let expired_text_entries = match text_cutoff {
-            Some(cutoff) => sqlx::query("SELECT id, file_path FROM clipboard_entries WHERE content_type LIKE 'text%' AND created_at < ?")
-                .bind(cutoff)
-                .fetch_all(self.db.pool())
-                .await?,
-            None => vec![], // Never expire text
-        };
-
-        let expired_image_entries = match image_cutoff {
-            Some(cutoff) => sqlx::query("SELECT id, file_path FROM clipboard_entries WHERE content_type LIKE 'image%' AND created_at < ?")
+        // Get cutoff times, None means never expire
+        let text_cutoff = config.text.expiry.cutoff_ms(now);
+        let image_cutoff = config.image.expiry.cutoff_ms(now);
+        let subtype_cutoffs: BTreeMap<&str, Option<i64>> = config
+            .subtype_expiry
+            .iter()
+            .map(|(subtype, expiry)| (subtype.as_str(), expiry.cutoff_ms(now)))
+            .collect();
+
+        // 只有早于最晚截止时间的条目才可能过期
+        let latest_cutoff = [text_cutoff, image_cutoff]
+            .into_iter()
+            .chain(subtype_cutoffs.values().copied())
+            .flatten()
+            .max();
+        let rows = match latest_cutoff {
+            Some(cutoff) => sqlx::query("SELECT id, content_type, content_subtype, file_path, created_at FROM clipboard_entries WHERE created_at < ?")
                 .bind(cutoff)
                 .fetch_all(self.db.pool())
                 .await?,
-            None => vec![], // Never expire images
+            None => vec![], // Nothing ever expires
         };
 
         let mut entries_removed = 0;
         let mut images_removed = 0;
         let mut size_freed = 0u64;
 
-        // Remove text entries
-        for row in expired_text_entries {
-            let id: String = row.get("id");
-            sqlx::query("DELETE FROM clipboard_entries WHERE id = ?")
-                .bind(&id)
-                .execute(self.db.pool())
-                .await?;
-            entries_removed += 1;
-        }
+        for row in rows {
+            let content_type: String = row.get("content_type");
+            let content_subtype: Option<String> = row.get("content_subtype");
+            let created_at: i64 = row.get("created_at");
+
+            // 子类型规则优先，没有时按文本/图片的规则
+            let cutoff = match content_subtype
+                .as_deref()
+                .and_then(|subtype| subtype_cutoffs.get(subtype))
+            {
+                Some(cutoff) => *cutoff,
+                None if content_type.starts_with("image") => image_cutoff,
+                None if content_type.starts_with("text") => text_cutoff,
+                None => None,
+            };
+            if cutoff.is_none_or(|cutoff| created_at >= cutoff) {
+                continue;
+            }
 
-        // Remove image entries and files
-        for row in expired_image_entries {
             let id: String = row.get("id");
-            let file_path: Option<String> = row.get("file_path");
-
             sqlx::query("DELETE FROM clipboard_entries WHERE id = ?")
                 .bind(&id)
                 .execute(self.db.pool())
@@ -792,9 +2736,12 @@ impl AppState {
             entries_removed += 1;
 
             // Remove image file if exists
+            if !content_type.starts_with("image") {
+                continue;
+            }
+            let file_path: Option<String> = row.get("file_path");
             if let Some(relative_path) = file_path {
-                let images_dir = self.get_images_path()?;
-                let full_path = images_dir.join(relative_path.replace("imgs/", ""));
+                let full_path = self.resolve_image_path(&relative_path)?;
 
                 if full_path.exists() {
                     if let Ok(metadata) = std::fs::metadata(&full_path) {
@@ -803,9 +2750,129 @@ impl AppState {
                     let _ = std::fs::remove_file(&full_path);
                     images_removed += 1;
                 }
+                size_freed += self.processor.remove_thumbnails(&relative_path);
+            }
+        }
+
+        if entries_removed > 0 {
+            self.notify_statistics_changed();
+        }
+        Ok(CleanupResult {
+            entries_removed,
+            images_removed,
+            size_freed_bytes: size_freed,
+        })
+    }
+
+    /// 合并 content_hash 相同的历史记录（去重上线前遗留的数据）：保留最新的一条，
+    /// 累加复制次数，任意一条已收藏则保留收藏，删除其余记录和不再被引用的图片文件。
+    /// 豁免去重的子类型保持原样
+    pub async fn deduplicate_history(&self) -> Result<CleanupResult> {
+        let (max_copy_count, exempt_subtypes) = {
+            let config_manager = self.config_manager.lock().await;
+            (
+                config_manager.config.max_copy_count.max(1),
+                config_manager.config.never_dedup_subtypes.clone(),
+            )
+        };
+
+        let mut tx = self.db.pool().begin().await?;
+        let hashes: Vec<String> = sqlx::query_scalar(
+            "SELECT content_hash FROM clipboard_entries GROUP BY content_hash HAVING COUNT(*) > 1",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut entries_removed = 0;
+        let mut removed_files = Vec::new();
+        for hash in hashes {
+            let rows = sqlx::query(
+                r#"
+                SELECT id, copy_count, is_favorite, file_path, content_subtype FROM clipboard_entries
+                WHERE content_hash = ?
+                ORDER BY created_at DESC, rowid DESC
+                "#,
+            )
+            .bind(&hash)
+            .fetch_all(&mut *tx)
+            .await?;
+
+            let keep = &rows[0];
+            let subtype: Option<String> = keep.get("content_subtype");
+            if subtype.is_some_and(|subtype| exempt_subtypes.contains(&subtype)) {
+                continue;
+            }
+
+            let keep_id: String = keep.get("id");
+            let keep_file: Option<String> = keep.get("file_path");
+            let total_copies: i64 = rows
+                .iter()
+                .map(|row| row.get::<i32, _>("copy_count") as i64)
+                .sum();
+            let is_favorite = rows.iter().any(|row| row.get::<bool, _>("is_favorite"));
+
+            sqlx::query(
+                "UPDATE clipboard_entries SET copy_count = ?, is_favorite = ? WHERE id = ?",
+            )
+            .bind(total_copies.min(max_copy_count as i64) as i32)
+            .bind(is_favorite)
+            .bind(&keep_id)
+            .execute(&mut *tx)
+            .await?;
+
+            for row in &rows[1..] {
+                let id: String = row.get("id");
+                sqlx::query("DELETE FROM clipboard_entries WHERE id = ?")
+                    .bind(&id)
+                    .execute(&mut *tx)
+                    .await?;
+                entries_removed += 1;
+
+                let file_path: Option<String> = row.get("file_path");
+                if let Some(file_path) = file_path.filter(|path| Some(path) != keep_file.as_ref()) {
+                    removed_files.push(file_path);
+                }
+            }
+        }
+        tx.commit().await?;
+        if entries_removed > 0 {
+            self.notify_statistics_changed();
+        }
+
+        // 事务提交后再删除图片文件，仍被其他记录引用的文件保留
+        let mut images_removed = 0;
+        let mut size_freed = 0u64;
+        removed_files.sort();
+        removed_files.dedup();
+        for relative_path in removed_files {
+            let still_used: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM clipboard_entries WHERE file_path = ?)",
+            )
+            .bind(&relative_path)
+            .fetch_one(self.db.pool())
+            .await?;
+            if still_used {
+                continue;
+            }
+
+            let full_path = self.resolve_image_path(&relative_path)?;
+            if let Ok(metadata) = std::fs::metadata(&full_path) {
+                match std::fs::remove_file(&full_path) {
+                    Ok(()) => {
+                        size_freed += metadata.len();
+                        images_removed += 1;
+                    }
+                    Err(e) => log::warn!("[AppState] 删除图片文件失败 {:?}: {}", full_path, e),
+                }
             }
+            size_freed += self.processor.remove_thumbnails(&relative_path);
         }
 
+        log::info!(
+            "[AppState] 历史去重完成: 删除 {} 条重复记录, {} 个图片文件",
+            entries_removed,
+            images_removed
+        );
         Ok(CleanupResult {
             entries_removed,
             images_removed,
@@ -813,17 +2880,77 @@ impl AppState {
         })
     }
 
+    /// 为没有 content_subtype 的旧文本条目重新运行内容检测，最多处理 limit 条（默认 1000）。
+    /// 每批在单独的事务中提交，处理过的条目都会写入子类型，
+    /// 因此中断后再次调用会从剩余条目继续，全部处理完后返回 0
+    pub async fn reprocess_entries(&self, limit: Option<i64>) -> Result<u64> {
+        let limit = limit.unwrap_or(1000);
+        if limit < 1 {
+            return Err(CommandError::invalid_input("Limit must be at least 1").into());
+        }
+
+        let mut processed = 0i64;
+        while processed < limit {
+            let mut tx = self.db.pool().begin().await?;
+            let rows = sqlx::query(
+                r#"
+                SELECT id, content_data, metadata FROM clipboard_entries
+                WHERE content_type = ? AND content_subtype IS NULL
+                ORDER BY created_at DESC
+                LIMIT ?
+                "#,
+            )
+            .bind(ContentType::Text.as_str())
+            .bind(REPROCESS_BATCH_SIZE.min(limit - processed))
+            .fetch_all(&mut *tx)
+            .await?;
+            if rows.is_empty() {
+                break;
+            }
+
+            for row in &rows {
+                let id: String = row.get("id");
+                let content: Option<String> = row.get("content_data");
+                let existing: Option<String> = row.get("metadata");
+
+                let (subtype, metadata) =
+                    ContentDetector::detect(content.as_deref().unwrap_or_default().trim());
+                let metadata = match metadata {
+                    Some(metadata) => merge_detected_metadata(existing.as_deref(), &metadata),
+                    None => existing,
+                };
+
+                sqlx::query(
+                    "UPDATE clipboard_entries SET content_subtype = ?, metadata = ? WHERE id = ?",
+                )
+                .bind(subtype.as_str())
+                .bind(&metadata)
+                .bind(&id)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await?;
+            processed += rows.len() as i64;
+        }
+
+        log::info!("[AppState] 重新检测了 {} 条旧条目", processed);
+        Ok(processed as u64)
+    }
+
     // Helper methods
-    fn get_db_path(&self) -> Result<PathBuf> {
-        let config_dir =
-            dirs::config_dir().ok_or_else(|| anyhow::anyhow!("Unable to get config directory"))?;
-        Ok(config_dir.join("clipboard-app").join("clipboard.db"))
+    /// 当前打开的数据库文件，而不是默认位置
+    pub(crate) async fn get_db_path(&self) -> Result<PathBuf> {
+        self.db.file_path().await
     }
 
     fn get_images_path(&self) -> Result<PathBuf> {
-        let config_dir =
-            dirs::config_dir().ok_or_else(|| anyhow::anyhow!("Unable to get config directory"))?;
-        Ok(config_dir.join("clipboard-app").join("imgs"))
+        Ok(self.processor.imgs_dir().clone())
+    }
+
+    /// 将数据库中的相对路径（imgs/xxx.png）解析为图片文件的绝对路径
+    fn resolve_image_path(&self, relative_path: &str) -> Result<PathBuf> {
+        let images_dir = self.get_images_path()?;
+        Ok(images_dir.join(relative_path.replace("imgs/", "")))
     }
 
     fn calculate_directory_size(&self, path: &PathBuf) -> Result<u64> {
@@ -831,6 +2958,94 @@ impl AppState {
     }
 }
 
+/// 把内容检测的结果合并进已有的 metadata JSON，保留富文本、OCR 等其他字段
+fn merge_detected_metadata(existing: Option<&str>, detected: &ContentMetadata) -> Option<String> {
+    let serde_json::Value::Object(detected) = serde_json::to_value(detected).ok()? else {
+        return None;
+    };
+    let mut value = existing
+        .and_then(|metadata| serde_json::from_str::<serde_json::Value>(metadata).ok())
+        .filter(|value| value.is_object())
+        .unwrap_or_else(|| serde_json::json!({}));
+    for (key, field) in detected {
+        value[key] = field;
+    }
+    Some(value.to_string())
+}
+
+/// preview_only 的历史查询：有预览的条目不返回完整内容，
+/// 没有预览的条目（图片等）保留 content_data
+const PREVIEW_COLUMNS: &str = "SELECT id, content_hash, content_type, \
+    CASE WHEN preview IS NULL THEN content_data END AS content_data, \
+    source_app, created_at, copy_count, file_path, is_favorite, content_subtype, metadata, \
+    app_bundle_id, source_window_title, preview FROM clipboard_entries";
+
+/// 为历史查询追加 WHERE 条件，各过滤条件之间为 AND 关系
+fn push_history_filters(
+    query: &mut QueryBuilder<'_, Sqlite>,
+    search: Option<String>,
+    filter: HistoryFilter,
+) {
+    query.push(" WHERE 1 = 1");
+
+    if let Some(search_term) = search {
+        let pattern = format!("%{}%", search_term);
+        // 来源窗口标题和图片通过 OCR 识别出的文字也参与搜索
+        query
+            .push(" AND (content_data LIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR source_app LIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR source_window_title LIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR (json_valid(metadata) AND json_extract(metadata, '$.ocr_text') LIKE ")
+            .push_bind(pattern.clone())
+            .push(")");
+        if filter.search_metadata {
+            query.push(" OR metadata LIKE ").push_bind(pattern);
+        }
+        query.push(")");
+    }
+
+    if let Some(subtype) = filter.subtype {
+        query.push(" AND content_subtype = ").push_bind(subtype);
+    }
+
+    if let Some(start_ms) = filter.start_ms {
+        query.push(" AND created_at >= ").push_bind(start_ms);
+    }
+
+    if let Some(end_ms) = filter.end_ms {
+        query.push(" AND created_at <= ").push_bind(end_ms);
+    }
+
+    // 来源应用按精确匹配，避免 App1 匹配到 App10
+    if let Some(source_app) = filter.source_app {
+        query.push(" AND source_app = ").push_bind(source_app);
+    }
+
+    if filter.favorites_only {
+        query.push(" AND is_favorite = 1");
+    }
+}
+
+/// 按半衰期衰减后的复制次数：每过 half_life_days 天权重减半，未来时间按 0 天计算
+fn decayed_copy_score(copy_count: i32, age_ms: i64, half_life_days: u32) -> f64 {
+    let age_days = age_ms.max(0) as f64 / (24.0 * 60.0 * 60.0 * 1000.0);
+    copy_count as f64 * 0.5f64.powf(age_days / half_life_days as f64)
+}
+
+/// 把图片转换为 arboard 需要的 RGBA 数据
+fn rgba_image_data(img: &image::DynamicImage) -> arboard::ImageData<'static> {
+    let rgba_img = img.to_rgba8();
+    let (width, height) = rgba_img.dimensions();
+    arboard::ImageData {
+        width: width as usize,
+        height: height as usize,
+        bytes: rgba_img.into_raw().into(),
+    }
+}
+
 fn calculate_directory_size_impl(path: &PathBuf) -> Result<u64> {
     let mut size = 0u64;
     if path.is_dir() {
@@ -0,0 +1,188 @@
+use crate::error::CommandError;
+use crate::models::ClipboardEntry;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// 同步包格式版本，格式不兼容时递增
+pub const BUNDLE_VERSION: u32 = 1;
+const MANIFEST_NAME: &str = "manifest.json";
+const IMAGES_DIR: &str = "imgs";
+
+/// 同步包中的条目清单，写在归档的第一个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub exported_at: i64,
+    pub entries: Vec<ClipboardEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncExportResult {
+    pub entries: u32,
+    pub images: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncImportResult {
+    pub imported: u32, // New rows inserted
+    pub merged: u32,   // Rows whose content hash already existed locally
+    pub images: u32,
+}
+
+/// 图片在归档内的路径，和数据库中的相对路径（imgs/xxx.png）保持一致
+pub fn image_archive_path(file_name: &str) -> String {
+    format!("{}/{}", IMAGES_DIR, file_name)
+}
+
+/// 写入 tar 归档：先写清单，再写 images 中的 (文件名, 本地路径)
+pub fn write(path: &Path, manifest: &Manifest, images: &[(String, PathBuf)]) -> Result<()> {
+    let mut builder = tar::Builder::new(File::create(path)?);
+
+    let data = serde_json::to_vec(manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime((manifest.exported_at / 1000).max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_NAME, data.as_slice())?;
+
+    for (file_name, source) in images {
+        builder.append_path_with_name(source, image_archive_path(file_name))?;
+    }
+
+    builder.into_inner()?.sync_all()?;
+    Ok(())
+}
+
+/// 读取归档中的清单
+pub fn read_manifest(path: &Path) -> Result<Manifest> {
+    let mut archive = tar::Archive::new(File::open(path)?);
+    for file in archive.entries()? {
+        let mut file = file?;
+        if file.path()?.as_ref() != Path::new(MANIFEST_NAME) {
+            continue;
+        }
+
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        let manifest: Manifest = serde_json::from_slice(&data)?;
+        if manifest.version > BUNDLE_VERSION {
+            return Err(CommandError::invalid_input(format!(
+                "Unsupported sync bundle version {} (expected at most {})",
+                manifest.version, BUNDLE_VERSION
+            ))
+            .into());
+        }
+        return Ok(manifest);
+    }
+    Err(CommandError::invalid_input(format!("Sync bundle has no {}", MANIFEST_NAME)).into())
+}
+
+/// 把 wanted 中的图片解压到 imgs_dir，已存在的同名文件不覆盖。
+/// 返回归档中实际找到的文件名
+pub fn extract_images(
+    path: &Path,
+    wanted: &HashSet<String>,
+    imgs_dir: &Path,
+) -> Result<HashSet<String>> {
+    let mut found = HashSet::new();
+    if wanted.is_empty() {
+        return Ok(found);
+    }
+
+    let mut archive = tar::Archive::new(File::open(path)?);
+    for file in archive.entries()? {
+        let mut file = file?;
+        let archive_path = file.path()?.into_owned();
+
+        // 只接受 imgs/<文件名>，避免归档中的路径写到图片目录之外
+        let Some(file_name) = archive_path
+            .strip_prefix(IMAGES_DIR)
+            .ok()
+            .filter(|rest| rest.components().count() == 1)
+            .and_then(|rest| rest.to_str())
+        else {
+            continue;
+        };
+        if !wanted.contains(file_name) {
+            continue;
+        }
+
+        let target = imgs_dir.join(file_name);
+        if !target.exists() {
+            file.unpack(&target)?;
+        }
+        found.insert(file_name.to_string());
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ContentType;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_and_read_bundle() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("photo.png");
+        std::fs::write(&source, b"fake image bytes").unwrap();
+
+        let manifest = Manifest {
+            version: BUNDLE_VERSION,
+            exported_at: 1_700_000_000_000,
+            entries: vec![ClipboardEntry::new(
+                ContentType::Text,
+                Some("bundled".to_string()),
+                "bundled_hash".to_string(),
+                None,
+                None,
+            )],
+        };
+        let bundle = temp_dir.path().join("history.tar");
+        write(
+            &bundle,
+            &manifest,
+            &[("photo.png".to_string(), source.clone())],
+        )
+        .unwrap();
+
+        let read = read_manifest(&bundle).unwrap();
+        assert_eq!(read.entries.len(), 1);
+        assert_eq!(read.entries[0].content_hash, "bundled_hash");
+
+        let imgs_dir = temp_dir.path().join("imgs");
+        std::fs::create_dir_all(&imgs_dir).unwrap();
+        let wanted = HashSet::from(["photo.png".to_string(), "missing.png".to_string()]);
+        let found = extract_images(&bundle, &wanted, &imgs_dir).unwrap();
+        assert_eq!(found, HashSet::from(["photo.png".to_string()]));
+        assert_eq!(
+            std::fs::read(imgs_dir.join("photo.png")).unwrap(),
+            b"fake image bytes"
+        );
+    }
+
+    #[test]
+    fn test_rejects_newer_bundle_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let bundle = temp_dir.path().join("future.tar");
+        let manifest = Manifest {
+            version: BUNDLE_VERSION + 1,
+            exported_at: 0,
+            entries: Vec::new(),
+        };
+        write(&bundle, &manifest, &[]).unwrap();
+
+        let error = read_manifest(&bundle).unwrap_err().to_string();
+        assert!(
+            error.contains("Unsupported sync bundle version"),
+            "{}",
+            error
+        );
+    }
+}
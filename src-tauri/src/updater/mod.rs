@@ -2,8 +2,9 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter};
-use tauri_plugin_updater::UpdaterExt;
+use tauri_plugin_updater::{Updater, UpdaterExt};
 use time::format_description::well_known::Rfc3339;
+use url::Url;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateInfo {
@@ -13,14 +14,216 @@ pub struct UpdateInfo {
     pub available: bool,
 }
 
+/// 更新失败的类别，让前端能区分签名校验失败（安全问题）和普通的网络问题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateErrorKind {
+    Network,
+    SignatureInvalid,
+    ManifestMalformed,
+    Other,
+}
+
+impl UpdateErrorKind {
+    /// 优先按 updater 的错误类型分类，无法识别时再根据错误信息判断
+    pub fn classify(error: &anyhow::Error) -> Self {
+        use tauri_plugin_updater::Error as UpdaterError;
+
+        if let Some(error) = error.downcast_ref::<UpdaterError>() {
+            match error {
+                UpdaterError::Minisign(_)
+                | UpdaterError::Base64(_)
+                | UpdaterError::SignatureUtf8(_) => return Self::SignatureInvalid,
+                UpdaterError::Reqwest(_) | UpdaterError::Network(_) => return Self::Network,
+                UpdaterError::Serialization(_)
+                | UpdaterError::ReleaseNotFound
+                | UpdaterError::TargetNotFound(_)
+                | UpdaterError::Semver(_) => return Self::ManifestMalformed,
+                _ => {}
+            }
+        }
+        Self::from_message(&format!("{:#}", error))
+    }
+
+    pub fn from_message(message: &str) -> Self {
+        const SIGNATURE: [&str; 3] = ["signature", "minisign", "public key"];
+        const MANIFEST: [&str; 7] = [
+            "release json",
+            "platforms",
+            "missing field",
+            "invalid type",
+            "expected value",
+            "while parsing",
+            "semver",
+        ];
+        const NETWORK: [&str; 7] = [
+            "error sending request",
+            "connection",
+            "timed out",
+            "dns",
+            "network",
+            "status code",
+            "tls",
+        ];
+
+        let message = message.to_lowercase();
+        let matches = |keywords: &[&str]| keywords.iter().any(|k| message.contains(k));
+        if matches(&SIGNATURE) {
+            Self::SignatureInvalid
+        } else if matches(&MANIFEST) {
+            Self::ManifestMalformed
+        } else if matches(&NETWORK) {
+            Self::Network
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// 返回给前端的更新错误
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UpdateError {
+    pub kind: UpdateErrorKind,
+    pub message: String,
+}
+
+impl UpdateError {
+    pub fn new(context: &str, error: anyhow::Error) -> Self {
+        Self {
+            kind: UpdateErrorKind::classify(&error),
+            message: format!("{}: {:#}", context, error),
+        }
+    }
+}
+
+impl From<String> for UpdateError {
+    fn from(message: String) -> Self {
+        Self {
+            kind: UpdateErrorKind::Other,
+            message,
+        }
+    }
+}
+
+/// 更新下载进度，随 update-download-progress 事件发送。
+/// 服务器未返回 Content-Length 时 total 和 percent 为 None
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub percent: Option<u32>,
+}
+
+impl DownloadProgress {
+    /// 累加本次收到的数据块（updater 回调给出的是单个块的长度，而不是累计值）
+    pub fn advance(&mut self, chunk_length: usize, content_length: Option<u64>) {
+        self.downloaded += chunk_length as u64;
+        self.total = content_length.filter(|total| *total > 0);
+        self.percent = self
+            .total
+            .map(|total| (self.downloaded.min(total) * 100 / total) as u32);
+    }
+}
+
+/// 更新渠道，每个渠道对应独立的更新清单
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    /// 按配置中的名称解析渠道（配置本身通过 serde 读取）
+    #[cfg(test)]
+    pub fn parse(value: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(value.to_string())).ok()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+        }
+    }
+
+    /// 渠道的 latest.json 地址。GitHub 的 latest 不包含预发布版本，
+    /// beta 渠道使用持续更新的 beta 标签下的清单
+    pub fn endpoint(&self) -> Url {
+        let endpoint = match self {
+            UpdateChannel::Stable => {
+                "https://github.com/try-to-fly/dance/releases/latest/download/latest.json"
+            }
+            UpdateChannel::Beta => {
+                "https://github.com/try-to-fly/dance/releases/download/beta/latest.json"
+            }
+        };
+        Url::parse(endpoint).expect("update endpoint is a valid URL")
+    }
+}
+
+/// 更新说明，notes 为发布时填写的原始 Markdown，entries 为其中的列表项
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Changelog {
+    pub version: String,
+    pub pub_date: Option<String>,
+    pub notes: Option<String>,
+    pub entries: Vec<String>,
+}
+
+impl Changelog {
+    pub fn from_update_info(info: &UpdateInfo) -> Self {
+        Self {
+            version: info.version.clone(),
+            pub_date: info.pub_date.clone(),
+            notes: info.notes.clone(),
+            entries: info
+                .notes
+                .as_deref()
+                .map(parse_release_notes)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// 提取 Markdown 更新说明中的列表项（- / * / + 开头），忽略标题和空行
+pub fn parse_release_notes(notes: &str) -> Vec<String> {
+    notes
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            line.strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))
+                .or_else(|| line.strip_prefix("+ "))
+        })
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
 pub struct UpdateManager;
 
 impl UpdateManager {
-    /// Check if we should check for updates (once per day)
-    pub fn should_check_for_updates(last_check: Option<&str>) -> bool {
+    /// Check if we should check for updates (once per day, unless snoozed)
+    pub fn should_check_for_updates(last_check: Option<&str>, snoozed_until: Option<&str>) -> bool {
+        Self::should_check_for_updates_at(last_check, snoozed_until, Utc::now())
+    }
+
+    fn should_check_for_updates_at(
+        last_check: Option<&str>,
+        snoozed_until: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if let Some(snoozed_until) =
+            snoozed_until.and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+        {
+            if now < snoozed_until {
+                return false;
+            }
+        }
         if let Some(last_check_str) = last_check {
             if let Ok(last_check_time) = DateTime::parse_from_rfc3339(last_check_str) {
-                let now = Utc::now();
                 let duration = now.signed_duration_since(last_check_time);
                 // Check if more than 24 hours have passed
                 return duration.num_hours() >= 24;
@@ -30,20 +233,44 @@ impl UpdateManager {
         true
     }
 
+    /// Longest allowed snooze, in days
+    pub const MAX_SNOOZE_DAYS: u32 = 365;
+
+    /// Timestamp until which update prompts are snoozed, in ISO 8601 format.
+    /// days is capped at MAX_SNOOZE_DAYS so the date can't overflow
+    pub fn snooze_until(days: u32) -> String {
+        let days = days.min(Self::MAX_SNOOZE_DAYS);
+        (Utc::now() + chrono::Duration::days(days as i64)).to_rfc3339()
+    }
+
     /// Get current timestamp in ISO 8601 format
     pub fn get_current_timestamp() -> String {
         Utc::now().to_rfc3339()
     }
 
+    /// Build an updater that targets the given channel's manifest
+    fn updater(app: &AppHandle, channel: UpdateChannel) -> Result<Updater> {
+        Ok(app
+            .updater_builder()
+            .endpoints(vec![channel.endpoint()])?
+            .build()?)
+    }
+
     /// Check for updates
-    pub async fn check_for_updates(app: &AppHandle) -> Result<Option<UpdateInfo>> {
-        log::info!("[UpdateManager] Starting update check...");
+    pub async fn check_for_updates(
+        app: &AppHandle,
+        channel: UpdateChannel,
+    ) -> Result<Option<UpdateInfo>> {
+        log::info!(
+            "[UpdateManager] Starting update check on {} channel...",
+            channel.as_str()
+        );
         log::info!(
             "[UpdateManager] Current app version: {}",
             app.package_info().version
         );
 
-        let updater = app.updater_builder().build()?;
+        let updater = Self::updater(app, channel)?;
         log::debug!("[UpdateManager] Updater built successfully");
 
         match updater.check().await {
@@ -88,22 +315,18 @@ impl UpdateManager {
     }
 
     /// Download and install update
-    pub async fn download_and_install(app: &AppHandle) -> Result<()> {
-        let updater = app.updater_builder().build()?;
+    pub async fn download_and_install(app: &AppHandle, channel: UpdateChannel) -> Result<()> {
+        let updater = Self::updater(app, channel)?;
 
         if let Some(update) = updater.check().await? {
             // Emit progress events to frontend
             let app_handle = app.clone();
+            let mut progress = DownloadProgress::default();
 
             update
                 .download_and_install(
                     |chunk_length, content_length| {
-                        let progress = if let Some(total) = content_length {
-                            (chunk_length as f64 / total as f64 * 100.0) as u32
-                        } else {
-                            0
-                        };
-
+                        progress.advance(chunk_length, content_length);
                         let _ = app_handle.emit("update-download-progress", progress);
                     },
                     || {
@@ -119,8 +342,11 @@ impl UpdateManager {
 
     /// Manually trigger update check
     #[allow(dead_code)]
-    pub async fn manual_check_and_update(app: &AppHandle) -> Result<UpdateInfo> {
-        if let Some(info) = Self::check_for_updates(app).await? {
+    pub async fn manual_check_and_update(
+        app: &AppHandle,
+        channel: UpdateChannel,
+    ) -> Result<UpdateInfo> {
+        if let Some(info) = Self::check_for_updates(app, channel).await? {
             Ok(info)
         } else {
             Ok(UpdateInfo {
@@ -132,3 +358,252 @@ impl UpdateManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_channel_endpoints() {
+        let stable = UpdateChannel::Stable.endpoint();
+        let beta = UpdateChannel::Beta.endpoint();
+
+        assert_ne!(stable, beta);
+        assert!(stable.path().contains("/releases/latest/"));
+        assert!(beta.path().contains("/download/beta/"));
+        for endpoint in [stable, beta] {
+            assert_eq!(endpoint.scheme(), "https");
+            assert!(endpoint.path().ends_with("latest.json"));
+        }
+    }
+
+    #[test]
+    fn test_update_channel_parse() {
+        assert_eq!(UpdateChannel::parse("beta"), Some(UpdateChannel::Beta));
+        assert_eq!(UpdateChannel::parse("stable"), Some(UpdateChannel::Stable));
+        assert_eq!(UpdateChannel::parse("nightly"), None);
+        assert_eq!(UpdateChannel::default().as_str(), "stable");
+    }
+
+    fn hours_ago(now: DateTime<Utc>, hours: i64) -> String {
+        (now - chrono::Duration::hours(hours)).to_rfc3339()
+    }
+
+    #[test]
+    fn test_should_check_for_updates_after_a_day() {
+        let now = Utc::now();
+        assert!(UpdateManager::should_check_for_updates_at(None, None, now));
+        assert!(UpdateManager::should_check_for_updates_at(
+            Some("not a date"),
+            None,
+            now
+        ));
+        assert!(!UpdateManager::should_check_for_updates_at(
+            Some(&hours_ago(now, 2)),
+            None,
+            now
+        ));
+        assert!(UpdateManager::should_check_for_updates_at(
+            Some(&hours_ago(now, 25)),
+            None,
+            now
+        ));
+    }
+
+    #[test]
+    fn test_should_check_for_updates_honors_snooze() {
+        let now = Utc::now();
+        let last_check = hours_ago(now, 48);
+
+        // Snoozed into the future: skip even though the last check is stale
+        let snoozed = (now + chrono::Duration::days(3)).to_rfc3339();
+        assert!(!UpdateManager::should_check_for_updates_at(
+            Some(&last_check),
+            Some(&snoozed),
+            now
+        ));
+        assert!(!UpdateManager::should_check_for_updates_at(
+            None,
+            Some(&snoozed),
+            now
+        ));
+
+        // Snooze expired: fall back to the daily check
+        let expired = hours_ago(now, 1);
+        assert!(UpdateManager::should_check_for_updates_at(
+            Some(&last_check),
+            Some(&expired),
+            now
+        ));
+        assert!(!UpdateManager::should_check_for_updates_at(
+            Some(&hours_ago(now, 2)),
+            Some(&expired),
+            now
+        ));
+
+        // An unparseable snooze value is ignored
+        assert!(UpdateManager::should_check_for_updates_at(
+            Some(&last_check),
+            Some("garbage"),
+            now
+        ));
+    }
+
+    #[test]
+    fn test_snooze_until_is_in_the_future() {
+        let until = DateTime::parse_from_rfc3339(&UpdateManager::snooze_until(2)).unwrap();
+        let remaining = until.signed_duration_since(Utc::now());
+        assert!(remaining.num_hours() >= 47 && remaining.num_hours() <= 48);
+
+        // Huge values from the frontend are capped instead of overflowing the date
+        let until = DateTime::parse_from_rfc3339(&UpdateManager::snooze_until(u32::MAX)).unwrap();
+        let remaining = until.signed_duration_since(Utc::now());
+        assert!(remaining.num_days() <= UpdateManager::MAX_SNOOZE_DAYS as i64);
+    }
+
+    #[test]
+    fn test_classify_update_error_messages() {
+        let cases = [
+            (
+                "The signature verification failed",
+                UpdateErrorKind::SignatureInvalid,
+            ),
+            (
+                "The signature abc could not be decoded, please check if it is a valid base64 string.",
+                UpdateErrorKind::SignatureInvalid,
+            ),
+            (
+                "Invalid encoding in minisign data",
+                UpdateErrorKind::SignatureInvalid,
+            ),
+            (
+                "Could not fetch a valid release JSON from the remote",
+                UpdateErrorKind::ManifestMalformed,
+            ),
+            (
+                "the platform `darwin-aarch64` was not found on the response `platforms` object",
+                UpdateErrorKind::ManifestMalformed,
+            ),
+            (
+                "missing field `version` at line 1 column 20",
+                UpdateErrorKind::ManifestMalformed,
+            ),
+            (
+                "error sending request for url (https://github.com/try-to-fly/dance/releases/latest/download/latest.json)",
+                UpdateErrorKind::Network,
+            ),
+            ("operation timed out", UpdateErrorKind::Network),
+            (
+                "Download request failed with status code: 503",
+                UpdateErrorKind::Network,
+            ),
+            (
+                "Failed to install .deb package",
+                UpdateErrorKind::Other,
+            ),
+        ];
+
+        for (message, expected) in cases {
+            assert_eq!(
+                UpdateErrorKind::from_message(message),
+                expected,
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn test_classify_updater_error_variants() {
+        let signature: anyhow::Error =
+            tauri_plugin_updater::Error::SignatureUtf8("x".into()).into();
+        assert_eq!(
+            UpdateErrorKind::classify(&signature),
+            UpdateErrorKind::SignatureInvalid
+        );
+
+        let manifest: anyhow::Error = tauri_plugin_updater::Error::ReleaseNotFound.into();
+        assert_eq!(
+            UpdateErrorKind::classify(&manifest),
+            UpdateErrorKind::ManifestMalformed
+        );
+
+        // Context wrapping does not hide the underlying updater error
+        let network = anyhow::Error::from(tauri_plugin_updater::Error::Network("reset".into()))
+            .context("下载更新失败");
+        assert_eq!(
+            UpdateErrorKind::classify(&network),
+            UpdateErrorKind::Network
+        );
+
+        let error = UpdateError::new("更新检查失败", anyhow::anyhow!("something else"));
+        assert_eq!(error.kind, UpdateErrorKind::Other);
+        assert_eq!(error.message, "更新检查失败: something else");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap()["kind"],
+            serde_json::json!("other")
+        );
+    }
+
+    #[test]
+    fn test_download_progress_accumulates_chunks() {
+        let mut progress = DownloadProgress::default();
+        progress.advance(250, Some(1000));
+        assert_eq!(progress.downloaded, 250);
+        assert_eq!(progress.total, Some(1000));
+        assert_eq!(progress.percent, Some(25));
+
+        progress.advance(500, Some(1000));
+        assert_eq!(progress.downloaded, 750);
+        assert_eq!(progress.percent, Some(75));
+
+        // Never report more than 100% if the server under-reports the length
+        progress.advance(500, Some(1000));
+        assert_eq!(progress.percent, Some(100));
+    }
+
+    #[test]
+    fn test_download_progress_without_content_length() {
+        let mut progress = DownloadProgress::default();
+        progress.advance(4096, None);
+        progress.advance(4096, Some(0));
+        assert_eq!(progress.downloaded, 8192);
+        assert_eq!(progress.total, None);
+        assert_eq!(progress.percent, None);
+
+        let payload = serde_json::to_value(progress).unwrap();
+        assert_eq!(
+            payload,
+            serde_json::json!({ "downloaded": 8192, "total": null, "percent": null })
+        );
+    }
+
+    #[test]
+    fn test_parse_release_notes() {
+        let notes = "## What's new\n\n- Faster search\n* Image thumbnails \n  + Nested item\n-\nPlain paragraph";
+        assert_eq!(
+            parse_release_notes(notes),
+            vec!["Faster search", "Image thumbnails", "Nested item"]
+        );
+        assert!(parse_release_notes("").is_empty());
+    }
+
+    #[test]
+    fn test_changelog_from_update_info() {
+        let info = UpdateInfo {
+            version: "1.2.0".to_string(),
+            notes: Some("- Fix crash on startup".to_string()),
+            pub_date: Some("2024-05-01T00:00:00Z".to_string()),
+            available: true,
+        };
+        let changelog = Changelog::from_update_info(&info);
+        assert_eq!(changelog.version, "1.2.0");
+        assert_eq!(changelog.entries, vec!["Fix crash on startup"]);
+
+        let without_notes = Changelog::from_update_info(&UpdateInfo {
+            notes: None,
+            ..info
+        });
+        assert!(without_notes.entries.is_empty());
+    }
+}
@@ -0,0 +1,252 @@
+use crate::error::CommandError;
+use anyhow::Result;
+use kuchikiki::traits::TendrilSink;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use url::Url;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// 预览只需要 <head> 中的信息，超过这个大小就停止下载
+const MAX_PREVIEW_BYTES: usize = 512 * 1024;
+const USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UrlPreview {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub favicon_url: Option<String>,
+}
+
+/// 下载页面开头部分并解析预览信息
+pub async fn fetch(url: &str) -> Result<UrlPreview> {
+    let parsed = Url::parse(url)
+        .map_err(|e| CommandError::invalid_input(format!("Invalid URL {:?}: {}", url, e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(CommandError::invalid_input(format!(
+            "Only http and https URLs can be previewed, got {:?}",
+            parsed.scheme()
+        ))
+        .into());
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(USER_AGENT)
+        .build()?;
+    let mut response = client.get(parsed).send().await?;
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!("HTTP error: {}", response.status()));
+    }
+
+    // 跟随重定向后的地址才是相对路径的基准
+    let base = response.url().clone();
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_PREVIEW_BYTES {
+            log::debug!("[UrlPreview] 页面超过 {} 字节，截断解析", MAX_PREVIEW_BYTES);
+            body.truncate(MAX_PREVIEW_BYTES);
+            break;
+        }
+    }
+
+    Ok(parse_preview(&String::from_utf8_lossy(&body), &base))
+}
+
+/// 从 HTML 中提取标题、描述和图标，优先使用 OpenGraph 标签
+pub fn parse_preview(html: &str, base: &Url) -> UrlPreview {
+    let document = kuchikiki::parse_html().one(html).document_node;
+
+    let meta = |selector: &str| -> Option<String> {
+        let element = document.select_first(selector).ok()?;
+        let content = element
+            .attributes
+            .borrow()
+            .get("content")?
+            .trim()
+            .to_string();
+        (!content.is_empty()).then_some(content)
+    };
+
+    let title = meta(r#"meta[property="og:title"]"#)
+        .or_else(|| meta(r#"meta[name="twitter:title"]"#))
+        .or_else(|| {
+            let title = document.select_first("title").ok()?.text_contents();
+            let title = title.trim();
+            (!title.is_empty()).then(|| title.to_string())
+        });
+
+    let description = meta(r#"meta[property="og:description"]"#)
+        .or_else(|| meta(r#"meta[name="twitter:description"]"#))
+        .or_else(|| meta(r#"meta[name="description"]"#));
+
+    // 没有声明图标时退回到站点根目录的 /favicon.ico
+    let favicon_url = document
+        .select_first(r#"link[rel~="icon"]"#)
+        .ok()
+        .and_then(|link| link.attributes.borrow().get("href").map(str::to_string))
+        .and_then(|href| base.join(href.trim()).ok())
+        .or_else(|| base.join("/favicon.ico").ok())
+        .map(String::from);
+
+    UrlPreview {
+        title,
+        description,
+        favicon_url,
+    }
+}
+
+/// 按 URL 缓存预览结果，过期时间由调用方传入（配置可能随时变化）
+#[derive(Debug, Default)]
+pub struct UrlPreviewCache {
+    entries: HashMap<String, (Instant, UrlPreview)>,
+}
+
+impl UrlPreviewCache {
+    pub fn get(&self, url: &str, ttl: Duration) -> Option<UrlPreview> {
+        self.entries
+            .get(url)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < ttl)
+            .map(|(_, preview)| preview.clone())
+    }
+
+    pub fn insert(&mut self, url: String, preview: UrlPreview) {
+        // 顺便清掉过期很久的条目，避免缓存无限增长
+        self.entries
+            .retain(|_, (fetched_at, _)| fetched_at.elapsed() < Duration::from_secs(24 * 60 * 60));
+        self.entries.insert(url, (Instant::now(), preview));
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use axum::http::header;
+    use axum::routing::get;
+    use axum::Router;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const OPEN_GRAPH_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <title>Fallback title</title>
+  <meta property="og:title" content="Dance Clipboard">
+  <meta property="og:description" content="  A clipboard manager  ">
+  <meta name="description" content="Plain description">
+  <link rel="shortcut icon" href="/static/icon.png">
+</head>
+<body><p>Hello</p></body>
+</html>"#;
+
+    /// 本地 mock 服务器：/og 返回带 OpenGraph 标签的页面，/huge 返回超过大小上限的页面。
+    /// 返回服务器地址和请求计数
+    pub(crate) async fn mock_server() -> (String, Arc<AtomicUsize>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let app = Router::new()
+            .route(
+                "/og",
+                get(move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    async { ([(header::CONTENT_TYPE, "text/html")], OPEN_GRAPH_PAGE) }
+                }),
+            )
+            .route(
+                "/huge",
+                get(|| async {
+                    // The title only appears after the size cap
+                    let padding = "<!-- padding -->".repeat(MAX_PREVIEW_BYTES / 8);
+                    let page = format!(
+                        "<html><head>{}<title>Too late</title></head></html>",
+                        padding
+                    );
+                    ([(header::CONTENT_TYPE, "text/html")], page)
+                }),
+            )
+            .route(
+                "/missing",
+                get(|| async { axum::http::StatusCode::NOT_FOUND }),
+            );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (format!("http://{}", addr), requests)
+    }
+
+    #[test]
+    fn test_parse_preview_fallbacks() {
+        let base = Url::parse("https://example.com/blog/post").unwrap();
+
+        let preview = parse_preview(OPEN_GRAPH_PAGE, &base);
+        assert_eq!(preview.title.as_deref(), Some("Dance Clipboard"));
+        assert_eq!(preview.description.as_deref(), Some("A clipboard manager"));
+        assert_eq!(
+            preview.favicon_url.as_deref(),
+            Some("https://example.com/static/icon.png")
+        );
+
+        // Without OpenGraph tags the <title> and meta description are used
+        let html = r#"<html><head><title> Plain page </title>
+            <meta name="description" content="Plain description"></head></html>"#;
+        let preview = parse_preview(html, &base);
+        assert_eq!(preview.title.as_deref(), Some("Plain page"));
+        assert_eq!(preview.description.as_deref(), Some("Plain description"));
+        assert_eq!(
+            preview.favicon_url.as_deref(),
+            Some("https://example.com/favicon.ico")
+        );
+
+        let preview = parse_preview("not html at all", &base);
+        assert_eq!(preview.title, None);
+        assert_eq!(preview.description, None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_open_graph_preview() {
+        let (base, _requests) = mock_server().await;
+
+        let preview = fetch(&format!("{}/og", base)).await.unwrap();
+        assert_eq!(preview.title.as_deref(), Some("Dance Clipboard"));
+        assert_eq!(preview.description.as_deref(), Some("A clipboard manager"));
+        assert_eq!(
+            preview.favicon_url,
+            Some(format!("{}/static/icon.png", base))
+        );
+
+        assert!(fetch(&format!("{}/missing", base)).await.is_err());
+        assert!(fetch("ftp://example.com/file").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_stops_at_size_cap() {
+        let (base, _requests) = mock_server().await;
+
+        let preview = fetch(&format!("{}/huge", base)).await.unwrap();
+        assert_eq!(preview.title, None);
+    }
+
+    #[test]
+    fn test_cache_respects_ttl() {
+        let mut cache = UrlPreviewCache::default();
+        let preview = UrlPreview {
+            title: Some("Cached".to_string()),
+            ..Default::default()
+        };
+        cache.insert("https://example.com".to_string(), preview.clone());
+
+        assert_eq!(
+            cache.get("https://example.com", Duration::from_secs(60)),
+            Some(preview)
+        );
+        assert_eq!(cache.get("https://example.com", Duration::ZERO), None);
+        assert_eq!(
+            cache.get("https://other.com", Duration::from_secs(60)),
+            None
+        );
+    }
+}
@@ -0,0 +1,417 @@
+#[derive(Debug, Clone)]
+pub struct AppInfo {
+    pub name: String,
+    pub bundle_id: Option<String>,
+    pub window_title: Option<String>, // 前台窗口标题，通常包含文档名或网页标题
+}
+
+/// 获取当前前台应用，平台不支持或获取失败时返回 None。会阻塞当前线程（Linux 上启动 xprop 进程）
+pub fn get_frontmost_app() -> Option<AppInfo> {
+    #[cfg(target_os = "macos")]
+    {
+        get_frontmost_app_macos()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        get_frontmost_app_windows()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        get_frontmost_app_linux()
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn get_frontmost_app_macos() -> Option<AppInfo> {
+    use cocoa::base::{id, nil};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    // 使用 std::panic::catch_unwind 来捕获可能的外部异常
+    std::panic::catch_unwind(|| {
+        unsafe {
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            if workspace == nil {
+                return None;
+            }
+
+            let active_app: id = msg_send![workspace, frontmostApplication];
+            if active_app == nil {
+                return None;
+            }
+
+            // 获取应用名称
+            let localized_name: id = msg_send![active_app, localizedName];
+            if localized_name == nil {
+                return None;
+            }
+
+            let name_c_str: *const i8 = msg_send![localized_name, UTF8String];
+            if name_c_str.is_null() {
+                return None;
+            }
+
+            let app_name = match std::ffi::CStr::from_ptr(name_c_str).to_str() {
+                Ok(name) => name.to_string(),
+                Err(_) => return None, // 跳过无效的UTF-8字符串
+            };
+
+            // 获取Bundle ID
+            let bundle_id_obj: id = msg_send![active_app, bundleIdentifier];
+            let bundle_id = if bundle_id_obj != nil {
+                let bundle_id_c_str: *const i8 = msg_send![bundle_id_obj, UTF8String];
+                if !bundle_id_c_str.is_null() {
+                    match std::ffi::CStr::from_ptr(bundle_id_c_str).to_str() {
+                        Ok(id) => Some(id.to_string()),
+                        Err(_) => None,
+                    }
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+
+            let pid: i32 = msg_send![active_app, processIdentifier];
+
+            Some(AppInfo {
+                name: app_name,
+                bundle_id,
+                window_title: focused_window_title_macos(pid),
+            })
+        }
+    })
+    .unwrap_or_else(|_| {
+        log::error!("获取活动应用程序时发生异常，已安全处理");
+        None
+    })
+}
+
+/// 通过辅助功能 API 读取应用焦点窗口的标题，未授予辅助功能权限时返回 None
+#[cfg(target_os = "macos")]
+unsafe fn focused_window_title_macos(pid: i32) -> Option<String> {
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use objc::{msg_send, sel, sel_impl};
+    use std::ffi::c_void;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCreateApplication(pid: i32) -> *const c_void;
+        fn AXUIElementCopyAttributeValue(
+            element: *const c_void,
+            attribute: *const c_void,
+            value: *mut *const c_void,
+        ) -> i32;
+    }
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRelease(cf: *const c_void);
+    }
+
+    // NSString 与 CFStringRef 可以直接互换使用
+    let copy_attribute = |element: *const c_void, name: &str| -> Option<*const c_void> {
+        let attribute: id = NSString::alloc(nil).init_str(name);
+        let mut value: *const c_void = std::ptr::null();
+        let error = AXUIElementCopyAttributeValue(element, attribute as *const c_void, &mut value);
+        let _: () = msg_send![attribute, release];
+        (error == 0 && !value.is_null()).then_some(value)
+    };
+
+    let app = AXUIElementCreateApplication(pid);
+    if app.is_null() {
+        return None;
+    }
+    let window = copy_attribute(app, "AXFocusedWindow");
+    CFRelease(app);
+    let window = window?;
+    let title = copy_attribute(window, "AXTitle");
+    CFRelease(window);
+    let title = title? as id;
+
+    let title_c_str: *const i8 = msg_send![title, UTF8String];
+    let result = if title_c_str.is_null() {
+        None
+    } else {
+        std::ffi::CStr::from_ptr(title_c_str)
+            .to_str()
+            .ok()
+            .map(|title| title.to_string())
+            .filter(|title| !title.is_empty())
+    };
+    CFRelease(title as *const c_void);
+    result
+}
+
+#[cfg(target_os = "windows")]
+fn get_frontmost_app_windows() -> Option<AppInfo> {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+    use winapi::shared::minwindef::{DWORD, FALSE, MAX_PATH};
+    use winapi::shared::windef::HWND;
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::OpenProcess;
+    use winapi::um::psapi::GetProcessImageFileNameW;
+    use winapi::um::winnt::{PROCESS_QUERY_INFORMATION, PROCESS_VM_READ};
+    use winapi::um::winuser::GetWindowThreadProcessId;
+    use winapi::um::winuser::{GetForegroundWindow, GetWindowTextW};
+
+    unsafe {
+        // 获取前台窗口句柄
+        let hwnd: HWND = GetForegroundWindow();
+        if hwnd.is_null() {
+            return None;
+        }
+
+        // 获取窗口标题
+        let mut window_title = [0u16; 256];
+        let title_len = GetWindowTextW(hwnd, window_title.as_mut_ptr(), 256);
+
+        let window_title = (title_len > 0).then(|| {
+            OsString::from_wide(&window_title[..title_len as usize])
+                .to_string_lossy()
+                .to_string()
+        });
+        let app_name = window_title
+            .clone()
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        // 获取进程ID
+        let mut process_id: DWORD = 0;
+        GetWindowThreadProcessId(hwnd, &mut process_id);
+
+        if process_id == 0 {
+            return Some(AppInfo {
+                name: app_name,
+                bundle_id: None,
+                window_title,
+            });
+        }
+
+        // 打开进程句柄
+        let process_handle = OpenProcess(
+            PROCESS_QUERY_INFORMATION | PROCESS_VM_READ,
+            FALSE,
+            process_id,
+        );
+
+        if process_handle.is_null() {
+            return Some(AppInfo {
+                name: app_name,
+                bundle_id: None,
+                window_title,
+            });
+        }
+
+        // 获取进程可执行文件路径
+        let mut exe_path = [0u16; MAX_PATH];
+        let path_len =
+            GetProcessImageFileNameW(process_handle, exe_path.as_mut_ptr(), MAX_PATH as DWORD);
+
+        CloseHandle(process_handle);
+
+        let bundle_id = if path_len > 0 {
+            let path_slice = &exe_path[..path_len as usize];
+            let path_string = OsString::from_wide(path_slice)
+                .to_string_lossy()
+                .to_string();
+
+            // 从路径中提取可执行文件名作为bundle_id
+            if let Some(filename) = std::path::Path::new(&path_string).file_stem() {
+                Some(filename.to_string_lossy().to_string())
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        Some(AppInfo {
+            name: app_name,
+            bundle_id,
+            window_title,
+        })
+    }
+}
+
+/// X11（包括 Wayland 下的 XWayland）通过 xprop 读取活动窗口的 WM_CLASS 和 PID。
+/// 纯 Wayland 会话没有通用的前台窗口接口，此时返回 None
+#[cfg(target_os = "linux")]
+fn get_frontmost_app_linux() -> Option<AppInfo> {
+    use std::process::Command;
+
+    if std::env::var_os("DISPLAY").is_none() {
+        log::trace!("[ActiveWindow] 没有 X11 显示，无法获取前台应用");
+        return None;
+    }
+
+    let xprop = |args: &[&str]| -> Option<String> {
+        let output = Command::new("xprop").args(args).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8(output.stdout).ok()
+    };
+
+    let root = xprop(&["-root", "_NET_ACTIVE_WINDOW"])?;
+    let window_id = parse_active_window_id(&root)?;
+    let props = xprop(&["-id", &window_id, "WM_CLASS", "_NET_WM_PID", "_NET_WM_NAME"])?;
+    let window_title = parse_wm_name(&props);
+
+    let process_name = parse_wm_pid(&props).and_then(|pid| {
+        std::fs::read_to_string(format!("/proc/{}/comm", pid))
+            .ok()
+            .map(|comm| comm.trim().to_string())
+            .filter(|comm| !comm.is_empty())
+    });
+
+    match parse_wm_class(&props) {
+        // WM_CLASS 的 class 是展示名（如 "Firefox"），instance 通常与 .desktop 文件名一致
+        Some((instance, class)) => Some(AppInfo {
+            name: class,
+            bundle_id: Some(instance),
+            window_title,
+        }),
+        None => process_name.map(|name| AppInfo {
+            name: name.clone(),
+            bundle_id: Some(name),
+            window_title,
+        }),
+    }
+}
+
+/// 解析 `_NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007`
+#[cfg(target_os = "linux")]
+fn parse_active_window_id(output: &str) -> Option<String> {
+    let id = output.split('#').nth(1)?.split(',').next()?.trim();
+    // 0x0 表示当前没有活动窗口
+    if id.is_empty() || id == "0x0" {
+        return None;
+    }
+    Some(id.to_string())
+}
+
+/// 解析 `WM_CLASS(STRING) = "instance", "Class"`，返回 (instance, class)
+#[cfg(target_os = "linux")]
+fn parse_wm_class(output: &str) -> Option<(String, String)> {
+    let line = output.lines().find(|line| line.starts_with("WM_CLASS"))?;
+    let values: Vec<&str> = line
+        .split_once('=')?
+        .1
+        .split(',')
+        .map(|value| value.trim().trim_matches('"'))
+        .filter(|value| !value.is_empty())
+        .collect();
+
+    match values.as_slice() {
+        [instance, class, ..] => Some((instance.to_string(), class.to_string())),
+        [only] => Some((only.to_string(), only.to_string())),
+        [] => None,
+    }
+}
+
+/// 解析 `_NET_WM_PID(CARDINAL) = 12345`
+#[cfg(target_os = "linux")]
+fn parse_wm_pid(output: &str) -> Option<u32> {
+    output
+        .lines()
+        .find(|line| line.starts_with("_NET_WM_PID"))?
+        .split_once('=')?
+        .1
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// 解析 `_NET_WM_NAME(UTF8_STRING) = "README.md - Visual Studio Code"`，
+/// xprop 会把标题中的引号和反斜杠转义
+#[cfg(target_os = "linux")]
+fn parse_wm_name(output: &str) -> Option<String> {
+    let value = output
+        .lines()
+        .find(|line| line.starts_with("_NET_WM_NAME"))?
+        .split_once('=')?
+        .1
+        .trim();
+    let quoted = value.strip_prefix('"')?.strip_suffix('"')?;
+
+    let mut title = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            title.extend(chars.next());
+        } else {
+            title.push(c);
+        }
+    }
+    (!title.is_empty()).then_some(title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_frontmost_app_does_not_panic() {
+        // Headless CI has no frontmost app; the call must still return cleanly
+        if let Some(info) = get_frontmost_app() {
+            assert!(!info.name.is_empty());
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_active_window_id() {
+        assert_eq!(
+            parse_active_window_id("_NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007\n"),
+            Some("0x3a00007".to_string())
+        );
+        assert_eq!(
+            parse_active_window_id("_NET_ACTIVE_WINDOW(WINDOW): window id # 0x0\n"),
+            None
+        );
+        assert_eq!(
+            parse_active_window_id("_NET_ACTIVE_WINDOW:  not found.\n"),
+            None
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_wm_class_and_pid() {
+        let output = "WM_CLASS(STRING) = \"gnome-terminal-server\", \"Gnome-terminal\"\n\
+                      _NET_WM_PID(CARDINAL) = 4242\n";
+        assert_eq!(
+            parse_wm_class(output),
+            Some((
+                "gnome-terminal-server".to_string(),
+                "Gnome-terminal".to_string()
+            ))
+        );
+        assert_eq!(parse_wm_pid(output), Some(4242));
+
+        let missing = "WM_CLASS:  not found.\n_NET_WM_PID:  not found.\n";
+        assert_eq!(parse_wm_class(missing), None);
+        assert_eq!(parse_wm_pid(missing), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_wm_name() {
+        let output = "WM_CLASS(STRING) = \"code\", \"Code\"\n\
+                      _NET_WM_NAME(UTF8_STRING) = \"notes \\\"draft\\\".md - Visual Studio Code\"\n";
+        assert_eq!(
+            parse_wm_name(output),
+            Some("notes \"draft\".md - Visual Studio Code".to_string())
+        );
+
+        assert_eq!(parse_wm_name("_NET_WM_NAME(UTF8_STRING) = \"\"\n"), None);
+        assert_eq!(parse_wm_name("_NET_WM_NAME:  not found.\n"), None);
+    }
+}
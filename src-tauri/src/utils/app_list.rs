@@ -134,8 +134,14 @@ impl AppListManager {
             Self::scan_installed_apps_windows(running_bundle_ids)
         }
 
-        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        #[cfg(target_os = "linux")]
+        {
+            Self::scan_installed_apps_linux(running_bundle_ids)
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
         {
+            let _ = running_bundle_ids;
             Vec::new()
         }
     }
@@ -193,7 +199,10 @@ impl AppListManager {
 
     #[cfg(target_os = "windows")]
     fn scan_installed_apps_windows(running_bundle_ids: &HashSet<String>) -> Vec<InstalledApp> {
-        let mut apps = Vec::new();
+        // Apps registered for uninstall carry proper display names, so list them first
+        let mut apps = Self::scan_uninstall_registry_windows(running_bundle_ids);
+        let mut seen: HashSet<String> = running_bundle_ids.clone();
+        seen.extend(apps.iter().map(|app| app.bundle_id.clone()));
 
         // Common Windows application directories
         let app_dirs = vec![
@@ -216,8 +225,8 @@ impl AppListManager {
                                 if extension == "exe" {
                                     match Self::parse_executable_windows(&app_entry.path()) {
                                         Ok(Some(app)) => {
-                                            // Don't duplicate running apps
-                                            if !running_bundle_ids.contains(&app.bundle_id) {
+                                            // Don't duplicate running or registered apps
+                                            if seen.insert(app.bundle_id.clone()) {
                                                 apps.push(app);
                                                 count += 1;
                                             }
@@ -249,6 +258,177 @@ impl AppListManager {
         apps
     }
 
+    /// Enumerates the Uninstall registry keys (machine and user, 64 and 32 bit)
+    #[cfg(target_os = "windows")]
+    fn scan_uninstall_registry_windows(running_bundle_ids: &HashSet<String>) -> Vec<InstalledApp> {
+        use std::ffi::{OsStr, OsString};
+        use std::os::windows::ffi::{OsStrExt, OsStringExt};
+        use std::ptr::null_mut;
+        use winapi::shared::minwindef::{DWORD, HKEY};
+        use winapi::shared::winerror::ERROR_SUCCESS;
+        use winapi::um::winnt::{KEY_READ, REG_EXPAND_SZ, REG_SZ};
+        use winapi::um::winreg::{
+            RegCloseKey, RegEnumKeyExW, RegOpenKeyExW, RegQueryValueExW, HKEY_CURRENT_USER,
+            HKEY_LOCAL_MACHINE,
+        };
+
+        const UNINSTALL_KEYS: [&str; 2] = [
+            "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+            "SOFTWARE\\WOW6432Node\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+        ];
+
+        fn wide(value: &str) -> Vec<u16> {
+            OsStr::new(value).encode_wide().chain(Some(0)).collect()
+        }
+
+        unsafe fn query_string(key: HKEY, name: &str) -> Option<String> {
+            let name = wide(name);
+            let mut value_type: DWORD = 0;
+            let mut size: DWORD = 0;
+            if RegQueryValueExW(
+                key,
+                name.as_ptr(),
+                null_mut(),
+                &mut value_type,
+                null_mut(),
+                &mut size,
+            ) != ERROR_SUCCESS as i32
+                || (value_type != REG_SZ && value_type != REG_EXPAND_SZ)
+                || size == 0
+            {
+                return None;
+            }
+
+            let mut buffer = vec![0u16; size as usize / 2 + 1];
+            if RegQueryValueExW(
+                key,
+                name.as_ptr(),
+                null_mut(),
+                &mut value_type,
+                buffer.as_mut_ptr() as *mut u8,
+                &mut size,
+            ) != ERROR_SUCCESS as i32
+            {
+                return None;
+            }
+
+            let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+            let value = OsString::from_wide(&buffer[..len])
+                .to_string_lossy()
+                .trim()
+                .to_string();
+            (!value.is_empty()).then_some(value)
+        }
+
+        let mut apps = Vec::new();
+        let mut seen: HashSet<String> = running_bundle_ids.clone();
+
+        log::debug!("[AppListManager] Scanning Windows uninstall registry...");
+        for root in [HKEY_LOCAL_MACHINE, HKEY_CURRENT_USER] {
+            for path in UNINSTALL_KEYS {
+                unsafe {
+                    let mut uninstall_key: HKEY = null_mut();
+                    if RegOpenKeyExW(root, wide(path).as_ptr(), 0, KEY_READ, &mut uninstall_key)
+                        != ERROR_SUCCESS as i32
+                    {
+                        continue;
+                    }
+
+                    let mut index = 0;
+                    loop {
+                        let mut subkey_name = [0u16; 256];
+                        let mut subkey_len = subkey_name.len() as DWORD;
+                        if RegEnumKeyExW(
+                            uninstall_key,
+                            index,
+                            subkey_name.as_mut_ptr(),
+                            &mut subkey_len,
+                            null_mut(),
+                            null_mut(),
+                            null_mut(),
+                            null_mut(),
+                        ) != ERROR_SUCCESS as i32
+                        {
+                            // ERROR_NO_MORE_ITEMS ends the enumeration
+                            break;
+                        }
+                        index += 1;
+
+                        let mut app_key: HKEY = null_mut();
+                        if RegOpenKeyExW(
+                            uninstall_key,
+                            subkey_name.as_ptr(),
+                            0,
+                            KEY_READ,
+                            &mut app_key,
+                        ) != ERROR_SUCCESS as i32
+                        {
+                            continue;
+                        }
+
+                        let display_name = query_string(app_key, "DisplayName");
+                        let display_icon = query_string(app_key, "DisplayIcon");
+                        RegCloseKey(app_key);
+
+                        if let Some(app) = display_name.and_then(|name| {
+                            Self::app_from_uninstall_entry(&name, display_icon.as_deref())
+                        }) {
+                            if seen.insert(app.bundle_id.clone()) {
+                                apps.push(app);
+                            }
+                        }
+                    }
+
+                    RegCloseKey(uninstall_key);
+                }
+            }
+        }
+
+        log::debug!(
+            "[AppListManager] Found {} apps in uninstall registry",
+            apps.len()
+        );
+        apps
+    }
+
+    /// Builds an app from an Uninstall registry entry. The bundle_id is the lowercased
+    /// executable name from DisplayIcon, matching `parse_executable_windows`
+    #[cfg(any(target_os = "windows", test))]
+    fn app_from_uninstall_entry(
+        display_name: &str,
+        display_icon: Option<&str>,
+    ) -> Option<InstalledApp> {
+        let display_name = display_name.trim();
+        if display_name.is_empty() {
+            return None;
+        }
+
+        // DisplayIcon looks like `"C:\Program Files\App\app.exe",0`
+        let executable = display_icon
+            .map(|icon| icon.rsplit_once(',').map_or(icon, |(path, _)| path))
+            .map(|path| path.trim().trim_matches('"'))
+            .filter(|path| path.to_lowercase().ends_with(".exe"));
+        let executable = executable?;
+        let file_name = executable.rsplit(['\\', '/']).next()?;
+        let stem = &file_name[..file_name.len() - ".exe".len()];
+        if stem.is_empty() {
+            return None;
+        }
+
+        let bundle_id = stem.to_lowercase();
+        #[cfg(target_os = "windows")]
+        let icon_path = Self::get_app_icon_path(&bundle_id);
+        #[cfg(not(target_os = "windows"))]
+        let icon_path = None;
+
+        Some(InstalledApp {
+            name: display_name.to_string(),
+            bundle_id,
+            icon_path,
+            is_running: false,
+        })
+    }
+
     #[cfg(target_os = "windows")]
     fn get_running_applications_windows() -> Result<Vec<InstalledApp>> {
         use std::collections::HashMap;
@@ -360,6 +540,120 @@ impl AppListManager {
         Ok(apps)
     }
 
+    /// Scans `.desktop` files in the XDG data directories. The desktop file id
+    /// (e.g. `org.gnome.Terminal`) is the app's stable identifier on Linux
+    #[cfg(target_os = "linux")]
+    fn scan_installed_apps_linux(running_bundle_ids: &HashSet<String>) -> Vec<InstalledApp> {
+        let mut apps = Vec::new();
+        let mut seen: HashSet<String> = running_bundle_ids.clone();
+
+        log::debug!("[AppListManager] Scanning XDG application directories...");
+        // Earlier directories take precedence for the same desktop file id
+        for app_dir in Self::xdg_application_dirs() {
+            let Ok(entries) = std::fs::read_dir(&app_dir) else {
+                continue;
+            };
+
+            let mut count = 0;
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_none_or(|ext| ext != "desktop") {
+                    continue;
+                }
+                let Some(desktop_id) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                if seen.contains(desktop_id) {
+                    continue;
+                }
+
+                match std::fs::read_to_string(&path) {
+                    Ok(content) => {
+                        // Record the id even when hidden so lower-priority dirs can't re-add it
+                        seen.insert(desktop_id.to_string());
+                        if let Some(app) = Self::parse_desktop_entry(&content, desktop_id) {
+                            apps.push(app);
+                            count += 1;
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!("Warning: Failed to read desktop entry at {:?}: {}", path, e);
+                    }
+                }
+            }
+            log::debug!(
+                "[AppListManager] Found {} additional apps in {:?}",
+                count,
+                app_dir
+            );
+        }
+
+        apps
+    }
+
+    #[cfg(target_os = "linux")]
+    fn xdg_application_dirs() -> Vec<PathBuf> {
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| dirs::home_dir().map(|home| home.join(".local/share")));
+        let data_dirs = std::env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|dirs| !dirs.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+        data_home
+            .into_iter()
+            .chain(data_dirs.split(':').map(PathBuf::from))
+            .map(|dir| dir.join("applications"))
+            .collect()
+    }
+
+    /// Parses the `[Desktop Entry]` group, skipping entries that are not
+    /// launchable applications or are hidden from menus
+    #[cfg(any(target_os = "linux", test))]
+    fn parse_desktop_entry(content: &str, desktop_id: &str) -> Option<InstalledApp> {
+        let mut in_entry = false;
+        let mut name = None;
+        let mut is_application = false;
+
+        for line in content.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_entry = line == "[Desktop Entry]";
+                continue;
+            }
+            if !in_entry {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match (key.trim(), value.trim()) {
+                ("Name", value) if !value.is_empty() => name = Some(value.to_string()),
+                ("Type", value) => is_application = value == "Application",
+                ("NoDisplay" | "Hidden", "true") => return None,
+                _ => {}
+            }
+        }
+
+        if !is_application {
+            return None;
+        }
+
+        #[cfg(target_os = "linux")]
+        let icon_path = Self::get_app_icon_path(desktop_id);
+        #[cfg(not(target_os = "linux"))]
+        let icon_path = None;
+
+        Some(InstalledApp {
+            name: name?,
+            bundle_id: desktop_id.to_string(),
+            icon_path,
+            is_running: false,
+        })
+    }
+
     #[cfg(target_os = "macos")]
     fn parse_app_bundle_macos(bundle_path: &Path) -> Result<Option<InstalledApp>> {
         if bundle_path.extension().is_none_or(|ext| ext != "app") {
@@ -513,3 +807,78 @@ impl AppListManager {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_installed_applications_on_host() {
+        let apps = AppListManager::get_installed_applications().unwrap();
+
+        // Desktop platforms always ship some applications; other hosts
+        // (e.g. a headless Linux CI box) may legitimately have none
+        #[cfg(any(target_os = "macos", target_os = "windows"))]
+        assert!(!apps.is_empty());
+
+        for app in &apps {
+            assert!(!app.name.is_empty());
+            assert!(!app.bundle_id.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_parse_desktop_entry() {
+        let content = "[Desktop Entry]\n\
+                       Name=Firefox\n\
+                       Name[de]=Firefox Webbrowser\n\
+                       Type=Application\n\
+                       Exec=firefox %u\n\
+                       \n\
+                       [Desktop Action new-window]\n\
+                       Name=New Window\n";
+        let app = AppListManager::parse_desktop_entry(content, "firefox").unwrap();
+        assert_eq!(app.name, "Firefox");
+        assert_eq!(app.bundle_id, "firefox");
+        assert!(!app.is_running);
+    }
+
+    #[test]
+    fn test_parse_desktop_entry_skips_hidden_and_non_apps() {
+        let hidden = "[Desktop Entry]\nName=Helper\nType=Application\nNoDisplay=true\n";
+        assert!(AppListManager::parse_desktop_entry(hidden, "helper").is_none());
+
+        let link = "[Desktop Entry]\nName=Docs\nType=Link\nURL=https://example.com\n";
+        assert!(AppListManager::parse_desktop_entry(link, "docs").is_none());
+
+        let unnamed = "[Desktop Entry]\nType=Application\n";
+        assert!(AppListManager::parse_desktop_entry(unnamed, "unnamed").is_none());
+    }
+
+    #[test]
+    fn test_app_from_uninstall_entry() {
+        let app = AppListManager::app_from_uninstall_entry(
+            "Mozilla Firefox (x64 en-US)",
+            Some("\"C:\\Program Files\\Mozilla Firefox\\firefox.exe\",0"),
+        )
+        .unwrap();
+        assert_eq!(app.name, "Mozilla Firefox (x64 en-US)");
+        assert_eq!(app.bundle_id, "firefox");
+
+        let app = AppListManager::app_from_uninstall_entry(
+            "Notepad++",
+            Some("C:\\Program Files\\Notepad++\\Notepad++.EXE"),
+        )
+        .unwrap();
+        assert_eq!(app.bundle_id, "notepad++");
+
+        // Entries without an executable icon have no stable identifier
+        assert!(AppListManager::app_from_uninstall_entry(
+            "Runtime",
+            Some("C:\\Windows\\Installer\\icon.ico")
+        )
+        .is_none());
+        assert!(AppListManager::app_from_uninstall_entry("Runtime", None).is_none());
+        assert!(AppListManager::app_from_uninstall_entry("  ", None).is_none());
+    }
+}
@@ -0,0 +1,329 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+/// 默认最多缓存的文件数量
+pub const DEFAULT_CAPACITY: usize = 256;
+
+/// 调用 ffprobe 读取媒体信息并提取常用字段（时长、码率、分辨率等）
+pub fn probe(url: &str) -> Result<serde_json::Value> {
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "quiet",
+            "-print_format",
+            "json",
+            "-show_format",
+            "-show_streams",
+            url,
+        ])
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => anyhow::anyhow!("FFprobe not available"),
+            _ => anyhow::anyhow!("Failed to execute ffprobe: {}", e),
+        })?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        log::error!("[MediaMetadata] ffprobe 执行失败: {}", error_msg);
+        return Err(anyhow::anyhow!("FFprobe execution failed: {}", error_msg));
+    }
+
+    let json_output = String::from_utf8(output.stdout)
+        .map_err(|e| anyhow::anyhow!("Failed to parse ffprobe output as UTF-8: {}", e))?;
+    let output: serde_json::Value = serde_json::from_str(&json_output)
+        .map_err(|e| anyhow::anyhow!("Failed to parse ffprobe JSON output: {}", e))?;
+
+    Ok(summarize(&output))
+}
+
+/// 从 ffprobe 的 JSON 输出中提取前端展示用的字段
+pub fn summarize(output: &serde_json::Value) -> serde_json::Value {
+    // 提取关键信息
+    let mut result = serde_json::Map::new();
+
+    if let Some(format) = output.get("format") {
+        if let Some(duration) = format.get("duration") {
+            if let Some(duration_str) = duration.as_str() {
+                if let Ok(duration_f64) = duration_str.parse::<f64>() {
+                    let minutes = (duration_f64 / 60.0) as u32;
+                    let seconds = (duration_f64 % 60.0) as u32;
+                    result.insert(
+                        "duration".to_string(),
+                        serde_json::Value::String(format!("{}:{:02}", minutes, seconds)),
+                    );
+                }
+            }
+        }
+        if let Some(bit_rate) = format.get("bit_rate") {
+            if let Some(bit_rate_str) = bit_rate.as_str() {
+                if let Ok(bit_rate_i64) = bit_rate_str.parse::<i64>() {
+                    let kbps = bit_rate_i64 / 1000;
+                    result.insert(
+                        "bitrate".to_string(),
+                        serde_json::Value::String(format!("{} kbps", kbps)),
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(streams) = output.get("streams") {
+        if let Some(streams_array) = streams.as_array() {
+            for stream in streams_array {
+                if let Some(codec_type) = stream.get("codec_type") {
+                    if codec_type == "video" {
+                        if let Some(width) = stream.get("width") {
+                            result.insert("width".to_string(), width.clone());
+                        }
+                        if let Some(height) = stream.get("height") {
+                            result.insert("height".to_string(), height.clone());
+                        }
+                        if let Some(codec_name) = stream.get("codec_name") {
+                            result.insert("codec".to_string(), codec_name.clone());
+                        }
+                        if let Some(r_frame_rate) = stream.get("r_frame_rate") {
+                            if let Some(fps_str) = r_frame_rate.as_str() {
+                                // 处理分数形式的帧率，如 "30/1"
+                                if let Some(slash_pos) = fps_str.find('/') {
+                                    let numerator: f64 =
+                                        fps_str[..slash_pos].parse().unwrap_or(0.0);
+                                    let denominator: f64 =
+                                        fps_str[slash_pos + 1..].parse().unwrap_or(1.0);
+                                    if denominator != 0.0 {
+                                        let fps = numerator / denominator;
+                                        result.insert(
+                                            "fps".to_string(),
+                                            serde_json::Value::String(format!("{:.2}", fps)),
+                                        );
+                                    }
+                                }
+                            }
+                        }
+                    } else if codec_type == "audio" {
+                        if let Some(sample_rate) = stream.get("sample_rate") {
+                            result.insert("sample_rate".to_string(), sample_rate.clone());
+                        }
+                        if result.get("codec").is_none() {
+                            if let Some(codec_name) = stream.get("codec_name") {
+                                result.insert("codec".to_string(), codec_name.clone());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    serde_json::Value::Object(result)
+}
+
+/// 本地文件的路径（支持 file:// URL），远程地址返回 None
+pub fn local_path(url: &str) -> Option<PathBuf> {
+    if url.starts_with("file://") {
+        return url::Url::parse(url).ok()?.to_file_path().ok();
+    }
+    let path = Path::new(url);
+    path.is_absolute().then(|| path.to_path_buf())
+}
+
+/// 文件的修改时间，文件不存在时返回 None
+pub fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MediaCacheStats {
+    pub entries: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+struct CachedMetadata {
+    modified: SystemTime,
+    metadata: serde_json::Value,
+    last_used: u64,
+}
+
+/// 以文件路径 + 修改时间为键的 LRU 缓存，文件被修改后旧结果自动失效
+pub struct MediaMetadataCache {
+    capacity: usize,
+    entries: HashMap<PathBuf, CachedMetadata>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl Default for MediaMetadataCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl MediaMetadataCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn get(&mut self, path: &Path, modified: SystemTime) -> Option<serde_json::Value> {
+        self.clock += 1;
+        match self.entries.get_mut(path) {
+            Some(cached) if cached.modified == modified => {
+                cached.last_used = self.clock;
+                self.hits += 1;
+                Some(cached.metadata.clone())
+            }
+            Some(_) => {
+                // 文件已被修改，丢弃旧结果
+                self.entries.remove(path);
+                self.misses += 1;
+                None
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, path: PathBuf, modified: SystemTime, metadata: serde_json::Value) {
+        self.clock += 1;
+        if !self.entries.contains_key(&path) && self.entries.len() >= self.capacity {
+            // 淘汰最久未使用的条目
+            if let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(path, _)| path.clone())
+            {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(
+            path,
+            CachedMetadata {
+                modified,
+                metadata,
+                last_used: self.clock,
+            },
+        );
+    }
+
+    pub fn stats(&self) -> MediaCacheStats {
+        MediaCacheStats {
+            entries: self.entries.len() as u64,
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[test]
+    fn test_second_lookup_hits_cache() {
+        let mut cache = MediaMetadataCache::new(4);
+        let path = PathBuf::from("/tmp/video.mp4");
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+
+        assert_eq!(cache.get(&path, modified), None);
+        cache.insert(path.clone(), modified, json!({"duration": "1:05"}));
+        assert_eq!(
+            cache.get(&path, modified),
+            Some(json!({"duration": "1:05"}))
+        );
+        assert_eq!(
+            cache.stats(),
+            MediaCacheStats {
+                entries: 1,
+                hits: 1,
+                misses: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_mtime_change_busts_cache() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("clip.mp3");
+        std::fs::write(&path, b"first").unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000))
+            .unwrap();
+
+        let mut cache = MediaMetadataCache::default();
+        let modified = modified_time(&path).unwrap();
+        cache.insert(path.clone(), modified, json!({"codec": "mp3"}));
+        assert!(cache.get(&path, modified_time(&path).unwrap()).is_some());
+
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(2_000))
+            .unwrap();
+        assert_eq!(cache.get(&path, modified_time(&path).unwrap()), None);
+        // The stale entry is dropped
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = MediaMetadataCache::new(2);
+        let modified = SystemTime::UNIX_EPOCH;
+        let (a, b, c) = (
+            PathBuf::from("/a.mp4"),
+            PathBuf::from("/b.mp4"),
+            PathBuf::from("/c.mp4"),
+        );
+
+        cache.insert(a.clone(), modified, json!("a"));
+        cache.insert(b.clone(), modified, json!("b"));
+        // Touch a so b becomes the oldest
+        assert!(cache.get(&a, modified).is_some());
+        cache.insert(c.clone(), modified, json!("c"));
+
+        assert!(cache.get(&a, modified).is_some());
+        assert!(cache.get(&b, modified).is_none());
+        assert!(cache.get(&c, modified).is_some());
+    }
+
+    #[test]
+    fn test_summarize_ffprobe_output() {
+        let output = json!({
+            "format": {"duration": "125.4", "bit_rate": "256000"},
+            "streams": [
+                {"codec_type": "video", "width": 1920, "height": 1080,
+                 "codec_name": "h264", "r_frame_rate": "30000/1001"},
+                {"codec_type": "audio", "sample_rate": "48000", "codec_name": "aac"}
+            ]
+        });
+
+        let summary = summarize(&output);
+        assert_eq!(summary["duration"], "2:05");
+        assert_eq!(summary["bitrate"], "256 kbps");
+        assert_eq!(summary["width"], 1920);
+        assert_eq!(summary["codec"], "h264");
+        assert_eq!(summary["fps"], "29.97");
+        assert_eq!(summary["sample_rate"], "48000");
+    }
+
+    #[test]
+    fn test_local_path() {
+        assert_eq!(
+            local_path("file:///tmp/a%20b.mp4"),
+            Some(PathBuf::from("/tmp/a b.mp4"))
+        );
+        assert_eq!(local_path("/tmp/a.mp4"), Some(PathBuf::from("/tmp/a.mp4")));
+        assert_eq!(local_path("https://example.com/a.mp4"), None);
+    }
+}
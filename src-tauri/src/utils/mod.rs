@@ -1,3 +1,8 @@
-pub mod app_detector;
+pub mod active_window;
 pub mod app_icon_extractor;
 pub mod app_list;
+pub mod media_metadata;
+#[cfg(any(target_os = "macos", test))]
+pub mod paste_script;
+pub mod system_open;
+pub mod window_state;
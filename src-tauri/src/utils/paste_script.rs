@@ -0,0 +1,160 @@
+/// 脚本没能切换到目标应用时返回的结果前缀
+pub const FAILED_PREFIX: &str = "Failed:";
+
+/// 生成隐藏本应用窗口并向前台应用发送 Cmd+V 的 AppleScript。
+/// process_name 为本应用在 System Events 中的进程名（运行时从 package_info 获取），
+/// focus_delay_ms 为隐藏窗口后等待焦点切换的时间，pasted_label 用于结果日志（如 "Pasted image to"）
+pub fn hide_and_paste_script(
+    process_name: &str,
+    focus_delay_ms: u64,
+    pasted_label: &str,
+) -> String {
+    let process_name = applescript_string(process_name);
+    format!(
+        r#"
+        tell application "System Events"
+            -- 隐藏本应用窗口（不是最小化）
+            set visible of process {process_name} to false
+
+            -- 等待焦点切换到下层应用
+            delay {delay}
+
+            -- 获取当前前台应用
+            set frontApp to first application process whose frontmost is true
+            set frontAppName to name of frontApp
+
+            -- 执行粘贴（如果不是本应用）
+            if frontAppName is not {process_name} then
+                keystroke "v" using {{command down}}
+                return {label} & frontAppName
+            else
+                return "{failed} still on " & frontAppName
+            end if
+        end tell
+        "#,
+        delay = format_delay_secs(focus_delay_ms),
+        label = applescript_string(&format!("{}: ", pasted_label)),
+        failed = FAILED_PREFIX,
+    )
+}
+
+/// 不依赖进程名的后备脚本：按 PID 找到并隐藏本应用，再向前台应用发送 Cmd+V。
+/// 用于进程名未知（应用被重命名或本地化）或按名称隐藏失败的情况
+pub fn hide_by_pid_and_paste_script(pid: u32, focus_delay_ms: u64, pasted_label: &str) -> String {
+    format!(
+        r#"
+        tell application "System Events"
+            set visible of (first application process whose unix id is {pid}) to false
+
+            delay {delay}
+
+            set frontApp to first application process whose frontmost is true
+            if unix id of frontApp is not {pid} then
+                keystroke "v" using {{command down}}
+                return {label} & name of frontApp
+            else
+                return "{failed} still on " & name of frontApp
+            end if
+        end tell
+        "#,
+        delay = format_delay_secs(focus_delay_ms),
+        label = applescript_string(&format!("{}: ", pasted_label)),
+        failed = FAILED_PREFIX,
+    )
+}
+
+/// 隐藏本应用并粘贴到前台应用。优先按进程名隐藏，失败时改用按 PID 的后备脚本。会阻塞当前线程
+#[cfg(target_os = "macos")]
+pub fn paste_into_frontmost_app(
+    process_name: Option<&str>,
+    focus_delay_ms: u64,
+    pasted_label: &str,
+    log_tag: &str,
+) {
+    if let Some(process_name) = process_name {
+        match run_osascript(&hide_and_paste_script(
+            process_name,
+            focus_delay_ms,
+            pasted_label,
+        )) {
+            Ok(message) if !message.starts_with(FAILED_PREFIX) => {
+                log::info!("[{}] {}", log_tag, message);
+                return;
+            }
+            Ok(message) => log::warn!("[{}] {}，改用按 PID 隐藏窗口", log_tag, message),
+            Err(e) => log::warn!("[{}] AppleScript错误: {}，改用按 PID 隐藏窗口", log_tag, e),
+        }
+    }
+
+    let script = hide_by_pid_and_paste_script(std::process::id(), focus_delay_ms, pasted_label);
+    match run_osascript(&script) {
+        Ok(message) => log::info!("[{}] {}", log_tag, message),
+        Err(e) => log::error!("[{}] AppleScript错误: {}", log_tag, e),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn run_osascript(script: &str) -> Result<String, String> {
+    let output = std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// AppleScript 的 delay 以秒为单位
+fn format_delay_secs(delay_ms: u64) -> String {
+    format!("{}.{:03}", delay_ms / 1000, delay_ms % 1000)
+}
+
+/// 转为带引号的 AppleScript 字符串字面量
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_embeds_configured_delay() {
+        let script = hide_and_paste_script("Dance", 350, "Pasted to");
+        assert!(script.contains("delay 0.350"), "{}", script);
+        assert!(script.contains(r#"return "Pasted to: " & frontAppName"#));
+        assert!(script.contains(r#"keystroke "v" using {command down}"#));
+
+        let script = hide_and_paste_script("Dance", 1500, "Pasted image to");
+        assert!(script.contains("delay 1.500"), "{}", script);
+        assert!(script.contains(r#""Pasted image to: ""#));
+    }
+
+    #[test]
+    fn test_script_uses_runtime_process_name() {
+        let script = hide_and_paste_script("Dance", 200, "Pasted to");
+        assert!(script.contains(r#"set visible of process "Dance" to false"#));
+        assert!(script.contains(r#"if frontAppName is not "Dance" then"#));
+        assert!(!script.contains("clipboard-app"), "{}", script);
+
+        // Localized or unusual names are quoted safely
+        let script = hide_and_paste_script(r#"剪贴板 "Beta""#, 200, "Pasted to");
+        assert!(
+            script.contains(r#"process "剪贴板 \"Beta\"" to false"#),
+            "{}",
+            script
+        );
+    }
+
+    #[test]
+    fn test_fallback_script_targets_process_by_pid() {
+        let script = hide_by_pid_and_paste_script(4242, 200, "Pasted to");
+        assert!(script.contains("first application process whose unix id is 4242"));
+        assert!(script.contains("if unix id of frontApp is not 4242 then"));
+        assert!(script.contains("delay 0.200"));
+        assert!(!script.contains("set visible of process \""), "{}", script);
+    }
+}
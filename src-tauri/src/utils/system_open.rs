@@ -0,0 +1,118 @@
+use crate::error::CommandError;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+#[cfg(not(target_os = "windows"))]
+use std::process::Command;
+
+/// 将条目中保存的路径解析为绝对路径：imgs/ 开头的相对路径位于应用数据目录 app_dir 下，其他路径原样使用
+pub fn resolve_stored_path(file_path: &str, app_dir: &Path) -> PathBuf {
+    if file_path.starts_with("imgs/") {
+        app_dir.join(file_path)
+    } else {
+        PathBuf::from(file_path)
+    }
+}
+
+/// 用系统默认程序打开文件（macOS: open，Windows: ShellExecuteW，Linux: xdg-open），不等待程序退出
+#[cfg(not(target_os = "windows"))]
+pub fn open_with_system(path: &Path) -> Result<()> {
+    let mut command = open_command(path)?;
+    command
+        .spawn()
+        .map_err(|e| CommandError::io(format!("Failed to open file: {}", e)))?;
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn open_command(path: &Path) -> Result<Command> {
+    let mut command = Command::new("open");
+    command.arg(path);
+    Ok(command)
+}
+
+/// Windows 上直接调用 ShellExecuteW，不经过 cmd.exe，文件名中的 & | ^ % 不会被当作命令解释
+#[cfg(target_os = "windows")]
+pub fn open_with_system(path: &Path) -> Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::shellapi::ShellExecuteW;
+    use winapi::um::winuser::SW_SHOWNORMAL;
+
+    let operation: Vec<u16> = std::ffi::OsStr::new("open")
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let file: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    // 返回值大于 32 表示成功，否则为错误码
+    let result = unsafe {
+        ShellExecuteW(
+            std::ptr::null_mut(),
+            operation.as_ptr(),
+            file.as_ptr(),
+            std::ptr::null(),
+            std::ptr::null(),
+            SW_SHOWNORMAL,
+        )
+    } as isize;
+    if result <= 32 {
+        return Err(CommandError::io(format!(
+            "Failed to open file: ShellExecuteW returned {}",
+            result
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn open_command(path: &Path) -> Result<Command> {
+    let mut command = Command::new("xdg-open");
+    command.arg(path);
+    Ok(command)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn open_command(_path: &Path) -> Result<Command> {
+    Err(CommandError::unsupported(format!(
+        "Opening files with the system viewer is not supported on {}",
+        std::env::consts::OS
+    ))
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_image_paths_resolve_under_app_dir() {
+        let app_dir = Path::new("/data/clipboard-app");
+        assert_eq!(
+            resolve_stored_path("imgs/abc.png", app_dir),
+            Path::new("/data/clipboard-app/imgs/abc.png")
+        );
+        assert_eq!(
+            resolve_stored_path("imgs/nested/abc.webp", app_dir),
+            Path::new("/data/clipboard-app/imgs/nested/abc.webp")
+        );
+    }
+
+    #[test]
+    fn test_other_paths_are_used_as_is() {
+        let app_dir = Path::new("/data/clipboard-app");
+        assert_eq!(
+            resolve_stored_path("/Users/me/Pictures/shot.png", app_dir),
+            Path::new("/Users/me/Pictures/shot.png")
+        );
+        // Only the exact imgs/ prefix is treated as app data
+        assert_eq!(
+            resolve_stored_path("myimgs/shot.png", app_dir),
+            Path::new("myimgs/shot.png")
+        );
+        assert_eq!(resolve_stored_path("imgs", app_dir), Path::new("imgs"));
+    }
+}
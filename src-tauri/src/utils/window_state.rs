@@ -0,0 +1,169 @@
+use crate::error::CommandError;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// 主窗口的大小和位置（物理像素），x、y 为窗口左上角在虚拟桌面中的坐标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// 一块显示器在虚拟桌面中的区域（物理像素），副屏的坐标可能为负
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScreenBounds {
+    fn contains(&self, x: i64, y: i64) -> bool {
+        let (left, top) = (self.x as i64, self.y as i64);
+        (left..left + self.width as i64).contains(&x)
+            && (top..top + self.height as i64).contains(&y)
+    }
+
+    /// 点到屏幕区域的距离平方，点在区域内时为 0
+    fn distance_squared(&self, x: i64, y: i64) -> i64 {
+        let (left, top) = (self.x as i64, self.y as i64);
+        let dx = (left - x).max(x - (left + self.width as i64 - 1)).max(0);
+        let dy = (top - y).max(y - (top + self.height as i64 - 1)).max(0);
+        dx * dx + dy * dy
+    }
+}
+
+impl WindowState {
+    pub fn validate(&self) -> Result<()> {
+        if self.width == 0 || self.height == 0 {
+            return Err(CommandError::invalid_input(format!(
+                "Window size must be greater than 0, got {}x{}",
+                self.width, self.height
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// 把保存的窗口放回可见区域：以窗口中心所在的屏幕为准（不在任何屏幕上时取最近的屏幕），
+    /// 窗口大于屏幕时缩小，超出屏幕的部分移回屏幕内。
+    /// 用于显示器断开或分辨率变化后恢复窗口；没有屏幕信息时原样返回
+    pub fn clamp_to_screens(self, screens: &[ScreenBounds]) -> Self {
+        let center_x = self.x as i64 + self.width as i64 / 2;
+        let center_y = self.y as i64 + self.height as i64 / 2;
+        let screen = screens
+            .iter()
+            .find(|screen| screen.contains(center_x, center_y))
+            .or_else(|| {
+                screens
+                    .iter()
+                    .min_by_key(|screen| screen.distance_squared(center_x, center_y))
+            });
+        let Some(screen) = screen else {
+            return self;
+        };
+
+        let width = self.width.min(screen.width);
+        let height = self.height.min(screen.height);
+        let max_x = screen.x as i64 + (screen.width - width) as i64;
+        let max_y = screen.y as i64 + (screen.height - height) as i64;
+        Self {
+            width,
+            height,
+            x: (self.x as i64).clamp(screen.x as i64, max_x) as i32,
+            y: (self.y as i64).clamp(screen.y as i64, max_y) as i32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMARY: ScreenBounds = ScreenBounds {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+
+    // Secondary monitor to the left of the primary one
+    const LEFT: ScreenBounds = ScreenBounds {
+        x: -2560,
+        y: -200,
+        width: 2560,
+        height: 1440,
+    };
+
+    fn window(x: i32, y: i32, width: u32, height: u32) -> WindowState {
+        WindowState {
+            width,
+            height,
+            x,
+            y,
+        }
+    }
+
+    #[test]
+    fn test_visible_windows_are_unchanged() {
+        let saved = window(100, 80, 900, 700);
+        assert_eq!(saved.clamp_to_screens(&[PRIMARY]), saved);
+
+        // Negative coordinates are valid on a monitor left of the primary
+        let saved = window(-2000, 100, 900, 700);
+        assert_eq!(saved.clamp_to_screens(&[PRIMARY, LEFT]), saved);
+    }
+
+    #[test]
+    fn test_partially_offscreen_windows_are_moved_back() {
+        // Hanging off the right and bottom edges
+        assert_eq!(
+            window(1500, 900, 900, 700).clamp_to_screens(&[PRIMARY]),
+            window(1020, 380, 900, 700)
+        );
+        // Title bar above the top of the screen
+        assert_eq!(
+            window(200, -150, 900, 700).clamp_to_screens(&[PRIMARY]),
+            window(200, 0, 900, 700)
+        );
+    }
+
+    #[test]
+    fn test_window_on_disconnected_monitor_moves_to_nearest_screen() {
+        // Saved on the left monitor, which is no longer connected
+        assert_eq!(
+            window(-2000, 100, 900, 700).clamp_to_screens(&[PRIMARY]),
+            window(0, 100, 900, 700)
+        );
+        // Far below every screen
+        assert_eq!(
+            window(1000, 5000, 900, 700).clamp_to_screens(&[PRIMARY, LEFT]),
+            window(1000, 380, 900, 700)
+        );
+    }
+
+    #[test]
+    fn test_oversized_windows_shrink_to_the_screen() {
+        // Saved on a large monitor, restored on a smaller one
+        assert_eq!(
+            window(-2560, -200, 2560, 1440).clamp_to_screens(&[PRIMARY]),
+            window(0, 0, 1920, 1080)
+        );
+    }
+
+    #[test]
+    fn test_no_screens_keeps_saved_geometry() {
+        let saved = window(-5000, -5000, 900, 700);
+        assert_eq!(saved.clamp_to_screens(&[]), saved);
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_size() {
+        assert!(window(0, 0, 900, 700).validate().is_ok());
+        assert!(window(0, 0, 0, 700).validate().is_err());
+        assert!(window(0, 0, 900, 0).validate().is_err());
+    }
+}
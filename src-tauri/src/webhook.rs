@@ -0,0 +1,213 @@
+use crate::error::CommandError;
+use crate::models::ClipboardEntry;
+use anyhow::Result;
+use std::time::Duration;
+
+/// 每次推送最多尝试的次数（含首次）
+const MAX_ATTEMPTS: u32 = 3;
+/// 第一次重试前的等待时间，之后每次翻倍
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 检查 webhook 地址，只接受 http/https
+pub fn validate_url(url: Option<&str>) -> Result<()> {
+    let Some(url) = url else {
+        return Ok(());
+    };
+    let parsed = url::Url::parse(url).map_err(|e| {
+        CommandError::invalid_input(format!("Invalid webhook_url {:?}: {}", url, e))
+    })?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(CommandError::invalid_input(format!(
+            "webhook_url must use http or https, got {:?}",
+            parsed.scheme()
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// 推送的请求体即序列化后的条目。不包含内容时清空正文、图片路径和元数据（可能含 OCR 文字）
+pub fn payload(entry: &ClipboardEntry, include_content: bool) -> serde_json::Value {
+    let mut entry = entry.clone();
+    if !include_content {
+        entry.content_data = None;
+        entry.file_path = None;
+        entry.metadata = None;
+    }
+    serde_json::to_value(entry).unwrap_or_default()
+}
+
+/// 发送 POST 请求，失败（网络错误或非 2xx）时按指数退避重试，最多 MAX_ATTEMPTS 次
+pub async fn deliver(url: &str, payload: &serde_json::Value, retry_delay: Duration) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()?;
+
+    let mut delay = retry_delay;
+    let mut attempt = 1;
+    loop {
+        let result = match client.post(url).json(payload).send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => Err(anyhow::anyhow!("HTTP {}", response.status())),
+            Err(e) => Err(e.into()),
+        };
+        if attempt >= MAX_ATTEMPTS {
+            return result;
+        }
+
+        log::debug!(
+            "[Webhook] 第 {} 次推送失败，{:?} 后重试: {}",
+            attempt,
+            delay,
+            result.unwrap_err()
+        );
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+/// 在后台推送新条目，不阻塞保存流程
+pub fn spawn_delivery(url: String, entry: &ClipboardEntry, include_content: bool) {
+    let payload = payload(entry, include_content);
+    let id = entry.id.clone();
+    tokio::spawn(async move {
+        match deliver(&url, &payload, RETRY_DELAY).await {
+            Ok(()) => log::debug!("[Webhook] 已推送条目 {}", id),
+            Err(e) => log::warn!(
+                "[Webhook] 推送条目 {} 失败（已尝试 {} 次）: {}",
+                id,
+                MAX_ATTEMPTS,
+                e
+            ),
+        }
+    });
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::models::ContentType;
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::sync::mpsc;
+
+    /// 本地 mock 服务器：前 failures 次请求返回 500，之后返回 200。
+    /// 返回地址、收到的请求体和请求计数
+    pub(crate) async fn mock_server(
+        failures: usize,
+    ) -> (
+        String,
+        mpsc::UnboundedReceiver<serde_json::Value>,
+        Arc<AtomicUsize>,
+    ) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(
+                    move |State((tx, requests)): State<(
+                        mpsc::UnboundedSender<serde_json::Value>,
+                        Arc<AtomicUsize>,
+                    )>,
+                          Json(body): Json<serde_json::Value>| async move {
+                        if requests.fetch_add(1, Ordering::SeqCst) < failures {
+                            return StatusCode::INTERNAL_SERVER_ERROR;
+                        }
+                        let _ = tx.send(body);
+                        StatusCode::OK
+                    },
+                ),
+            )
+            .with_state((tx, requests.clone()));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (format!("http://{}/hook", addr), rx, requests)
+    }
+
+    fn text_entry() -> ClipboardEntry {
+        let mut entry = ClipboardEntry::new(
+            ContentType::Text,
+            Some("secret text".to_string()),
+            "hash_webhook".to_string(),
+            Some("TestApp".to_string()),
+            None,
+        );
+        entry.metadata = Some(r#"{"ocr_text":"secret"}"#.to_string());
+        entry
+    }
+
+    #[tokio::test]
+    async fn test_deliver_posts_entry_payload() {
+        let (url, mut rx, requests) = mock_server(0).await;
+        let entry = text_entry();
+
+        deliver(&url, &payload(&entry, true), Duration::ZERO)
+            .await
+            .unwrap();
+
+        let body = rx.recv().await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert_eq!(body["id"], entry.id.as_str());
+        assert_eq!(body["content_type"], "text");
+        assert_eq!(body["content_data"], "secret text");
+        assert_eq!(body["source_app"], "TestApp");
+        assert_eq!(body["copy_count"], entry.copy_count);
+        assert!(body["created_at"].is_i64());
+    }
+
+    #[tokio::test]
+    async fn test_payload_without_content_omits_data() {
+        let (url, mut rx, _requests) = mock_server(0).await;
+        let entry = text_entry();
+
+        deliver(&url, &payload(&entry, false), Duration::ZERO)
+            .await
+            .unwrap();
+
+        let body = rx.recv().await.unwrap();
+        assert_eq!(body["id"], entry.id.as_str());
+        assert_eq!(body["content_hash"], "hash_webhook");
+        assert!(body["content_data"].is_null());
+        assert!(body["metadata"].is_null());
+        assert!(body["file_path"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_deliver_retries_are_bounded() {
+        // Recovers after transient failures
+        let (url, mut rx, requests) = mock_server(2).await;
+        deliver(&url, &serde_json::json!({}), Duration::ZERO)
+            .await
+            .unwrap();
+        assert!(rx.recv().await.is_some());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // Gives up after MAX_ATTEMPTS
+        let (url, _rx, requests) = mock_server(usize::MAX).await;
+        let error = deliver(&url, &serde_json::json!({}), Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("500"));
+        assert_eq!(requests.load(Ordering::SeqCst), MAX_ATTEMPTS as usize);
+    }
+
+    #[test]
+    fn test_validate_url() {
+        assert!(validate_url(None).is_ok());
+        assert!(validate_url(Some("https://example.com/hook")).is_ok());
+        assert!(validate_url(Some("http://127.0.0.1:8080/hook")).is_ok());
+        assert!(validate_url(Some("ftp://example.com")).is_err());
+        assert!(validate_url(Some("not a url")).is_err());
+    }
+}
@@ -22,7 +22,7 @@ const queryClient = new QueryClient();
 
 function AppContent() {
   const { i18n } = useTranslation(['common']);
-  const { startMonitoring, setupEventListener } = useClipboardStore();
+  const { syncMonitoringState, setupEventListener } = useClipboardStore();
   const { loadConfig } = useConfigStore();
 
   // Function to update window title
@@ -49,7 +49,7 @@ function AppContent() {
     analytics.track(ANALYTICS_EVENTS.APP_OPENED);
 
     setupEventListener();
-    startMonitoring();
+    syncMonitoringState();
 
     // Load config and set language
     loadConfig().then(async () => {
@@ -70,7 +70,7 @@ function AppContent() {
     // Track startup time
     const startupTime = Date.now() - startTime;
     analytics.trackPerformance(ANALYTICS_EVENTS.STARTUP_TIME, startupTime);
-  }, [startMonitoring, setupEventListener, loadConfig, i18n]);
+  }, [syncMonitoringState, setupEventListener, loadConfig, i18n]);
 
   // Listen for language changes and update window title
   useEffect(() => {
@@ -7,6 +7,7 @@ import { Button } from '../../ui/button';
 import { Badge } from '../../ui/badge';
 import { Card, CardContent, CardHeader } from '../../ui/card';
 import { UnifiedTextRenderer } from './UnifiedTextRenderer';
+import { errorMessage } from '../../../utils/errors';
 
 interface UrlRendererProps {
   content: string;
@@ -97,7 +98,7 @@ export function UrlRenderer({ content, metadata }: UrlRendererProps) {
     } catch (e) {
       console.error('获取URL内容失败:', e);
       // 如果获取失败，显示提示信息
-      setTextContent(`// 无法获取URL内容\n// 错误信息: ${String(e)}`);
+      setTextContent(`// 无法获取URL内容\n// 错误信息: ${errorMessage(e)}`);
       setTextContentType('plain_text');
       setPreviewType('text');
     } finally {
@@ -7,6 +7,7 @@ import { Badge } from '../ui/badge';
 import { Card, CardContent } from '../ui/card';
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '../ui/select';
 import { ScrollArea } from '../ui/scroll-area';
+import { errorMessage } from '../../utils/errors';
 
 interface ImagePreviewProps {
   imageUrl: string;
@@ -79,7 +80,7 @@ export const ImagePreview: React.FC<ImagePreviewProps> = ({
       }
     } catch (error) {
       console.error('Failed to convert image:', error);
-      alert(t('imagePreview.convertError', { error: String(error) }));
+      alert(t('imagePreview.convertError', { error: errorMessage(error) }));
     } finally {
       setIsConverting(false);
     }
@@ -103,7 +104,7 @@ export const ImagePreview: React.FC<ImagePreviewProps> = ({
       }, 2000);
     } catch (error) {
       console.error('Failed to copy converted image:', error);
-      alert(t('imagePreview.copyError', { error: String(error) }));
+      alert(t('imagePreview.copyError', { error: errorMessage(error) }));
     }
   };
 
@@ -3,6 +3,7 @@ import { listen } from '@tauri-apps/api/event';
 import { StatisticsModal } from '../Statistics/StatisticsModal';
 import { PreferencesModal } from '../Preferences/PreferencesModal';
 import { useConfigStore } from '../../stores/configStore';
+import { useClipboardStore } from '../../stores/clipboardStore';
 import type { Statistics } from '../../types/clipboard';
 
 export const MenuEventHandler: React.FC = () => {
@@ -18,10 +19,17 @@ export const MenuEventHandler: React.FC = () => {
         setShowStatistics(true);
       });
 
+      // Listen for live statistics updates after entries are saved or deleted
+      const unlistenStatsUpdates = await listen('statistics-updated', (event) => {
+        const latest = event.payload as Statistics;
+        setStatistics((current) => (current ? latest : current));
+        useClipboardStore.setState({ statistics: latest });
+      });
+
       // Listen for monitoring toggle updates
       const unlistenMonitoring = await listen('monitoring_toggled', (event) => {
         const isMonitoringNow = event.payload as boolean;
-        console.log('Monitoring toggled:', isMonitoringNow);
+        useClipboardStore.setState({ isMonitoring: isMonitoringNow });
       });
 
       // Listen for history cleared event
@@ -44,6 +52,7 @@ export const MenuEventHandler: React.FC = () => {
 
       return () => {
         unlistenStats();
+        unlistenStatsUpdates();
         unlistenMonitoring();
         unlistenHistory();
         unlistenPreferences();
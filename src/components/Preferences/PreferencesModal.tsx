@@ -28,6 +28,7 @@ import * as Toast from '@radix-ui/react-toast';
 import { analytics } from '../../services/analytics';
 import { getSystemLanguage } from '../../i18n/config';
 import { LogViewer } from '../LogViewer/LogViewer';
+import { errorMessage } from '../../utils/errors';
 
 export function PreferencesModal() {
   const { t, i18n } = useTranslation(['preferences', 'common']);
@@ -106,7 +107,15 @@ export function PreferencesModal() {
 
   const validateShortcut = async (shortcut: string): Promise<boolean> => {
     try {
-      return await invoke<boolean>('validate_shortcut', { shortcut });
+      const result = await invoke<{
+        valid: boolean;
+        normalized: string | null;
+        reason: string | null;
+      }>('validate_shortcut', { shortcut, action: 'show_window' });
+      if (!result.valid && result.reason) {
+        console.warn('Invalid shortcut:', result.reason);
+      }
+      return result.valid;
     } catch (error) {
       console.error('Failed to validate shortcut:', error);
       return false;
@@ -132,7 +141,7 @@ export function PreferencesModal() {
           await registerGlobalShortcut(localConfig.global_shortcut);
           setShortcutError(null);
         } catch (error) {
-          setShortcutError(String(error));
+          setShortcutError(errorMessage(error));
           return;
         }
       }
@@ -534,11 +543,16 @@ export function PreferencesModal() {
                                   try {
                                     await invoke('install_update');
                                     // App will restart automatically after update
-                                  } catch (installError) {
+                                  } catch (installError: any) {
                                     console.error('Failed to install update:', installError);
-                                    await message(t('system.update.installFailed'), {
-                                      title: t('system.update.updateError'),
-                                    });
+                                    await message(
+                                      installError?.kind === 'signature_invalid'
+                                        ? t('system.update.signatureError')
+                                        : t('system.update.installFailed'),
+                                      {
+                                        title: t('system.update.updateError'),
+                                      }
+                                    );
                                   }
                                 }
                               } else {
@@ -549,10 +563,16 @@ export function PreferencesModal() {
                                 setUpdateToastType('success');
                                 setShowUpdateToast(true);
                               }
-                            } catch (error) {
+                            } catch (error: any) {
                               console.error('Failed to check for updates:', error);
                               const errorMessage =
-                                typeof error === 'string' ? error : t('system.update.networkError');
+                                error?.kind === 'signature_invalid'
+                                  ? t('system.update.signatureError')
+                                  : error?.kind === 'network'
+                                    ? t('system.update.networkError')
+                                    : typeof error === 'string'
+                                      ? error
+                                      : (error?.message ?? t('system.update.networkError'));
                               setUpdateToastMessage(
                                 t('system.update.checkError', { error: errorMessage })
                               );
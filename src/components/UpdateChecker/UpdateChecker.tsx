@@ -13,18 +13,29 @@ interface UpdateInfo {
   available: boolean;
 }
 
+interface DownloadProgress {
+  downloaded: number;
+  total: number | null;
+  percent: number | null;
+}
+
+// Days to wait before prompting again after the user postpones an update
+const UPDATE_SNOOZE_DAYS = 3;
+
+const formatMegabytes = (bytes: number) => (bytes / 1024 / 1024).toFixed(1);
+
 export function UpdateChecker() {
   const { t } = useTranslation(['common']);
   const [updateInfo, setUpdateInfo] = useState<UpdateInfo | null>(null);
   const [downloading, setDownloading] = useState(false);
-  const [downloadProgress, setDownloadProgress] = useState(0);
+  const [downloadProgress, setDownloadProgress] = useState<DownloadProgress | null>(null);
   const [showToast, setShowToast] = useState(false);
 
   useEffect(() => {
     checkForUpdatesOnStartup();
 
     // Listen for update download progress
-    const unlisten = listen<number>('update-download-progress', (event) => {
+    const unlisten = listen<DownloadProgress>('update-download-progress', (event) => {
       setDownloadProgress(event.payload);
     });
 
@@ -84,12 +95,23 @@ export function UpdateChecker() {
         });
         setDownloading(false);
       }
+    } else {
+      await snoozeUpdate();
+    }
+  };
+
+  const snoozeUpdate = async () => {
+    try {
+      await invoke('snooze_update', { days: UPDATE_SNOOZE_DAYS });
+    } catch (error) {
+      console.error('[UpdateChecker] Failed to snooze update:', error);
     }
   };
 
   const handleDismiss = () => {
     setShowToast(false);
     setUpdateInfo(null);
+    snoozeUpdate();
   };
 
   if (!updateInfo || !showToast) {
@@ -113,10 +135,19 @@ export function UpdateChecker() {
           {downloading ? (
             <div className="download-progress">
               <div className="progress-text">
-                {t('updateChecker.updating', { progress: downloadProgress })}
+                {t('updateChecker.updating', { progress: downloadProgress?.percent ?? 0 })}
+                {downloadProgress &&
+                  ` (${formatMegabytes(downloadProgress.downloaded)} MB${
+                    downloadProgress.total !== null
+                      ? ` / ${formatMegabytes(downloadProgress.total)} MB`
+                      : ''
+                  })`}
               </div>
               <div className="progress-bar">
-                <div className="progress-fill" style={{ width: `${downloadProgress}%` }} />
+                <div
+                  className="progress-fill"
+                  style={{ width: `${downloadProgress?.percent ?? 0}%` }}
+                />
               </div>
             </div>
           ) : (
@@ -1,14 +1,30 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import { ClipboardEntry, Statistics } from '../types/clipboard';
+import {
+  ClipboardEntry,
+  Statistics,
+  SyncExportResult,
+  SyncImportResult,
+} from '../types/clipboard';
 import { writeText } from '@tauri-apps/plugin-clipboard-manager';
+import { errorMessage } from '../utils/errors';
+import type { OversizePolicy } from './configStore';
+
+interface TextOversizeEvent {
+  size_bytes: number;
+  limit_bytes: number;
+  policy: OversizePolicy;
+  source_app: string | null;
+}
 
 interface ClipboardStore {
   entries: ClipboardEntry[];
   statistics: Statistics | null;
   isMonitoring: boolean;
   searchTerm: string;
+  searchMetadata: boolean;
+  favoritesOnly: boolean;
   loading: boolean;
   error: string | null;
   selectedType: string;
@@ -21,11 +37,16 @@ interface ClipboardStore {
   // Actions
   startMonitoring: () => Promise<void>;
   stopMonitoring: () => Promise<void>;
+  syncMonitoringState: () => Promise<void>;
+  pauseMonitoring: (minutes: number) => Promise<void>;
   fetchHistory: (limit?: number, offset?: number) => Promise<void>;
   loadMoreEntries: () => Promise<void>;
   toggleFavorite: (id: string) => Promise<void>;
   deleteEntry: (id: string) => Promise<void>;
   clearHistory: () => Promise<void>;
+  clearHistoryBefore: (timestampMs: number) => Promise<number>;
+  exportSyncBundle: (path: string) => Promise<SyncExportResult>;
+  importSyncBundle: (path: string) => Promise<SyncImportResult>;
   fetchStatistics: () => Promise<void>;
   copyToClipboard: (content: string) => Promise<void>;
   pasteSelectedEntry: (entry: ClipboardEntry) => Promise<void>;
@@ -36,6 +57,8 @@ interface ClipboardStore {
   checkFFprobeAvailable: () => Promise<boolean>;
   extractMediaMetadata: (url: string) => Promise<any>;
   setSearchTerm: (term: string) => void;
+  setSearchMetadata: (enabled: boolean) => void;
+  setFavoritesOnly: (enabled: boolean) => void;
   setSelectedType: (type: string) => void;
   setSelectedEntry: (entry: ClipboardEntry | null) => void;
   getFilteredEntries: () => ClipboardEntry[];
@@ -47,6 +70,8 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
   statistics: null,
   isMonitoring: false,
   searchTerm: '',
+  searchMetadata: false,
+  favoritesOnly: false,
   loading: false,
   error: null,
   selectedType: 'all',
@@ -63,7 +88,7 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
       set({ isMonitoring: true });
       get().fetchHistory();
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     } finally {
       set({ loading: false });
     }
@@ -74,7 +99,28 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
       await invoke('stop_monitoring');
       set({ isMonitoring: false });
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
+    }
+  },
+
+  // 到时自动恢复，恢复时会收到 monitoring_toggled 事件
+  pauseMonitoring: async (minutes: number) => {
+    try {
+      await invoke('pause_monitoring', { minutes });
+      set({ isMonitoring: false });
+    } catch (error) {
+      set({ error: errorMessage(error) });
+    }
+  },
+
+  // 后端启动时会按上次的状态恢复监听，这里只同步状态
+  syncMonitoringState: async () => {
+    try {
+      const isMonitoring = await invoke<boolean>('is_monitoring');
+      set({ isMonitoring });
+      get().fetchHistory();
+    } catch (error) {
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -85,13 +131,15 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
         limit,
         offset,
         search: get().searchTerm || undefined,
+        searchMetadata: get().searchMetadata || undefined,
+        favoritesOnly: get().favoritesOnly || undefined,
       });
       set({
         entries,
         hasMore: entries.length === limit,
       });
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     } finally {
       set({ loading: false });
     }
@@ -109,6 +157,8 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
         limit: 50,
         offset: state.entries.length,
         search: state.searchTerm || undefined,
+        searchMetadata: state.searchMetadata || undefined,
+        favoritesOnly: state.favoritesOnly || undefined,
       });
 
       set({
@@ -117,7 +167,7 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
         isLoadingMore: false,
       });
     } catch (error) {
-      set({ error: String(error), isLoadingMore: false });
+      set({ error: errorMessage(error), isLoadingMore: false });
     }
   },
 
@@ -131,7 +181,7 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
         ),
       }));
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -143,7 +193,7 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
         entries: state.entries.filter((entry) => entry.id !== id),
       }));
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -152,7 +202,41 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
       await invoke('clear_history');
       set({ entries: [] });
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
+    }
+  },
+
+  clearHistoryBefore: async (timestampMs: number) => {
+    try {
+      const removed = await invoke<number>('clear_history_before', { timestampMs });
+      // 收藏的条目不会被清除
+      set((state) => ({
+        entries: state.entries.filter(
+          (entry) => entry.is_favorite || entry.created_at >= timestampMs
+        ),
+      }));
+      return removed;
+    } catch (error) {
+      set({ error: errorMessage(error) });
+      return 0;
+    }
+  },
+
+  exportSyncBundle: async (path: string) => {
+    try {
+      return await invoke<SyncExportResult>('export_sync_bundle', { path });
+    } catch (error) {
+      throw new Error(errorMessage(error));
+    }
+  },
+
+  importSyncBundle: async (path: string) => {
+    try {
+      const result = await invoke<SyncImportResult>('import_sync_bundle', { path });
+      await get().fetchHistory();
+      return result;
+    } catch (error) {
+      throw new Error(errorMessage(error));
     }
   },
 
@@ -161,7 +245,7 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
       const statistics = await invoke<Statistics>('get_statistics');
       set({ statistics });
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -169,7 +253,7 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
     try {
       await writeText(content);
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -177,11 +261,13 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
     try {
       if (entry.content_type.toLowerCase().includes('image') && entry.file_path) {
         await invoke('paste_image', { filePath: entry.file_path });
+      } else if (entry.content_type === 'file') {
+        await invoke('paste_file_list', { id: entry.id });
       } else if (entry.content_data) {
         await invoke('paste_text', { content: entry.content_data });
       }
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -189,7 +275,7 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
     try {
       return await invoke<string>('get_image_url', { filePath });
     } catch (error) {
-      throw new Error(String(error));
+      throw new Error(errorMessage(error));
     }
   },
 
@@ -197,7 +283,7 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
     try {
       await invoke('open_file_with_system', { filePath });
     } catch (error) {
-      throw new Error(String(error));
+      throw new Error(errorMessage(error));
     }
   },
 
@@ -235,7 +321,7 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
 
       return content;
     } catch (error) {
-      throw new Error(String(error));
+      throw new Error(errorMessage(error));
     }
   },
 
@@ -275,7 +361,7 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
       return metadata;
     } catch (error) {
       console.error('Failed to extract media metadata:', error);
-      throw new Error(String(error));
+      throw new Error(errorMessage(error));
     }
   },
 
@@ -284,6 +370,16 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
     get().fetchHistory();
   },
 
+  setSearchMetadata: (enabled: boolean) => {
+    set({ searchMetadata: enabled, hasMore: true });
+    get().fetchHistory();
+  },
+
+  setFavoritesOnly: (enabled: boolean) => {
+    set({ favoritesOnly: enabled, hasMore: true });
+    get().fetchHistory();
+  },
+
   setSelectedType: (type: string) => {
     set({ selectedType: type });
     const filtered = get().getFilteredEntries();
@@ -345,12 +441,17 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
       });
     }
 
+    if (state.favoritesOnly) {
+      filtered = filtered.filter((entry) => entry.is_favorite);
+    }
+
     if (state.searchTerm) {
       const searchLower = state.searchTerm.toLowerCase();
       filtered = filtered.filter(
         (entry) =>
           entry.content_data?.toLowerCase().includes(searchLower) ||
-          entry.source_app?.toLowerCase().includes(searchLower)
+          entry.source_app?.toLowerCase().includes(searchLower) ||
+          (state.searchMetadata && entry.metadata?.toLowerCase().includes(searchLower))
       );
     }
 
@@ -391,5 +492,20 @@ export const useClipboardStore = create<ClipboardStore>((set, get) => ({
         };
       });
     });
+
+    // 超过文本大小上限的内容被跳过或截断时提示用户
+    listen<TextOversizeEvent>('text_oversize', (event) => {
+      const { size_bytes, limit_bytes, policy } = event.payload;
+      const action = policy === 'truncate' ? 'truncated' : 'skipped';
+      set({
+        error: `Copied text (${size_bytes} bytes) exceeds the ${limit_bytes} byte limit and was ${action}`,
+      });
+    });
+
+    // 从备份恢复后整个列表都已变化，重新加载
+    listen<number>('history_restored', () => {
+      set({ selectedEntry: null });
+      get().fetchHistory();
+    });
   },
 }));
@@ -1,15 +1,20 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
+import { errorMessage } from '../utils/errors';
 
 export type ExpiryOption = { Days: number } | 'Never';
 
+export type OversizePolicy = 'skip' | 'truncate';
+
 export interface TextConfig {
   max_size_mb: number;
   expiry: ExpiryOption;
+  on_oversize?: OversizePolicy; // Skip oversized text or keep its first max_size_mb
 }
 
 export interface ImageConfig {
   expiry: ExpiryOption;
+  dedup_distance?: number | null; // Max perceptual-hash distance to merge; null disables
 }
 
 export interface ExcludedApp {
@@ -17,6 +22,37 @@ export interface ExcludedApp {
   bundle_id: string;
 }
 
+export interface WindowState {
+  width: number; // Physical pixels
+  height: number;
+  x: number; // Top-left corner, may be negative on secondary monitors
+  y: number;
+}
+
+export type LogLevel = 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+export interface LogConfig {
+  level: LogLevel; // Applied immediately
+  to_file: boolean; // File output and rotation apply after restart
+  max_file_size_mb: number;
+  keep_files: number;
+}
+
+export interface BackupConfig {
+  enabled: boolean;
+  interval_hours: number;
+  keep: number; // Most recent backups kept
+}
+
+export interface StatisticsConfig {
+  most_copied_limit: number; // 1-100
+  recent_apps_limit: number; // 1-100
+}
+
+// 'hex' | 'rgb' | 'rgba' | 'hsl' for colors, 'iso8601' | 'unix_seconds' | 'unix_millis' for
+// timestamps, or any text transform name such as 'uppercase'
+export type SmartPasteTransform = string;
+
 export interface AppConfig {
   text: TextConfig;
   image: ImageConfig;
@@ -26,7 +62,22 @@ export interface AppConfig {
   auto_startup: boolean;
   auto_update: boolean;
   last_update_check?: string; // ISO 8601 date string
+  update_snoozed_until?: string; // ISO 8601 date string
   language: string; // Language preference (zh or en)
+  update_channel?: 'stable' | 'beta';
+  subtype_expiry?: Record<string, ExpiryOption>; // Overrides text/image expiry for these subtypes
+  window_state?: WindowState | null; // Main window geometry, restored on launch
+  log?: LogConfig;
+  backup?: BackupConfig;
+  dedup_mode?: 'by_hash' | 'consecutive_only' | 'none'; // none stores every copy, using more space
+  smart_paste?: Record<string, SmartPasteTransform>; // Subtype -> transform; others paste unchanged
+  statistics?: StatisticsConfig;
+}
+
+export interface BackupInfo {
+  path: string;
+  size_bytes: number;
+  created_at: number; // Unix milliseconds
 }
 
 export interface CacheStatistics {
@@ -35,6 +86,12 @@ export interface CacheStatistics {
   total_entries: number;
   text_entries: number;
   image_entries: number;
+  subtype_counts: [string, number][]; // Sorted by count descending; missing subtypes are "unknown"
+  media_metadata: {
+    entries: number;
+    hits: number;
+    misses: number;
+  };
 }
 
 interface ConfigStore {
@@ -66,6 +123,7 @@ const defaultConfig: AppConfig = {
   text: {
     max_size_mb: 1.0,
     expiry: 'Never',
+    on_oversize: 'skip',
   },
   image: {
     expiry: 'Never',
@@ -84,6 +142,7 @@ const defaultConfig: AppConfig = {
   auto_startup: false,
   auto_update: true,
   last_update_check: undefined,
+  update_snoozed_until: undefined,
   language: 'system',
 };
 
@@ -103,7 +162,7 @@ export const useConfigStore = create<ConfigStore>((set, get) => ({
       set({ config });
     } catch (error) {
       console.error('Failed to load config:', error);
-      set({ error: String(error), config: defaultConfig });
+      set({ error: errorMessage(error), config: defaultConfig });
     } finally {
       set({ loading: false });
     }
@@ -116,7 +175,7 @@ export const useConfigStore = create<ConfigStore>((set, get) => ({
       set({ config });
     } catch (error) {
       console.error('Failed to update config:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     } finally {
       set({ loading: false });
     }
@@ -131,9 +190,8 @@ export const useConfigStore = create<ConfigStore>((set, get) => ({
       set({ cacheStats: stats, cacheStatsLoading: false });
     } catch (error) {
       console.error('[ConfigStore] Failed to load cache statistics:', error);
-      const errorMessage = String(error);
       set({
-        cacheStatsError: errorMessage,
+        cacheStatsError: errorMessage(error),
         cacheStatsLoading: false,
         cacheStats: null,
       });
@@ -11,6 +11,8 @@ export interface ClipboardEntry {
   content_subtype?: string | null;
   metadata?: string | null;
   app_bundle_id?: string | null;
+  source_window_title?: string | null;
+  preview?: string | null; // First 200 characters of content_data
 }
 
 export type ContentType = 'text' | 'image' | 'file' | 'unknown';
@@ -25,7 +27,18 @@ export type ContentSubType =
   | 'command'
   | 'timestamp'
   | 'json'
-  | 'markdown';
+  | 'markdown'
+  | 'base64'
+  | 'text_with_links'
+  | 'base32'
+  | 'base58'
+  | 'csv'
+  | 'script'
+  | 'url_encoded'
+  | 'html_entities'
+  | 'secret'
+  | 'diff'
+  | 'env_vars';
 
 export interface ContentMetadata {
   detected_language?: string;
@@ -33,6 +46,85 @@ export interface ContentMetadata {
   color_formats?: ColorFormats;
   timestamp_formats?: TimestampFormats;
   image_metadata?: ImageMetadata;
+  links?: string[];
+  json_metadata?: JsonMetadata;
+  csv_metadata?: CsvMetadata;
+  script_metadata?: ScriptMetadata;
+  url_encoded_metadata?: UrlEncodedMetadata;
+  html_entities_metadata?: HtmlEntitiesMetadata;
+  secret_metadata?: SecretMetadata;
+  markdown_metadata?: MarkdownMetadata; // Markdown tables only
+  diff_metadata?: DiffMetadata;
+  env_vars_metadata?: EnvVarsMetadata; // Key names only, values are not stored here
+  file_list?: FileListMetadata; // File entries only
+  natural_language?: NaturalLanguage; // Plain text only, when language detection is enabled
+}
+
+export interface FileListMetadata {
+  paths: string[];
+}
+
+export interface CsvMetadata {
+  delimiter: string;
+  rows: number;
+  columns: number;
+}
+
+export interface ScriptMetadata {
+  interpreter: string; // e.g. bash, python3; sh for here-doc snippets
+}
+
+export interface UrlEncodedMetadata {
+  decoded_preview: string;
+}
+
+export interface NaturalLanguage {
+  code: string; // ISO 639-3, e.g. eng, cmn
+  confidence: number;
+}
+
+export interface HtmlEntitiesMetadata {
+  decoded_preview: string;
+  entity_count: number;
+}
+
+export interface MarkdownMetadata {
+  table: boolean;
+  columns: number;
+}
+
+export interface DiffMetadata {
+  files_changed: number;
+  additions: number;
+  deletions: number;
+}
+
+export interface EnvVarsMetadata {
+  keys: string[];
+}
+
+export interface SecretMetadata {
+  kind: string; // e.g. aws_access_key, github_token; high_entropy for unknown formats
+  entropy: number; // Bits per character
+}
+
+export interface ContentEntropy {
+  bits_per_byte: number; // 0 for a single repeated byte, up to 8
+  classification: 'low' | 'medium' | 'high';
+  byte_count: number;
+}
+
+export interface JsonValidation {
+  valid: boolean;
+  error: string | null;
+  line: number | null; // 1-based
+  column: number | null; // 1-based
+}
+
+export interface JsonMetadata {
+  minified: boolean;
+  key_count: number;
+  depth: number;
 }
 
 export interface ImageMetadata {
@@ -60,6 +152,14 @@ export interface TimestampFormats {
   unix_ms?: number;
   iso8601?: string;
   date_string?: string;
+  human_readable?: string;
+}
+
+export interface HistoryPage {
+  entries: ClipboardEntry[];
+  total: number; // Entries matching the same search and filters
+  offset: number;
+  limit: number;
 }
 
 export interface Statistics {
@@ -69,6 +169,17 @@ export interface Statistics {
   recent_apps: AppUsage[];
 }
 
+export interface SyncExportResult {
+  entries: number;
+  images: number;
+}
+
+export interface SyncImportResult {
+  imported: number; // New entries added
+  merged: number; // Entries whose content already existed locally
+  images: number;
+}
+
 export interface AppUsage {
   app_name: string;
   count: number;
@@ -0,0 +1,34 @@
+export type ErrorCode =
+  | 'db_error'
+  | 'not_found'
+  | 'invalid_input'
+  | 'io_error'
+  | 'network_error'
+  | 'unsupported'
+  | 'clipboard_busy' // Another application held the clipboard; retrying later may work
+  | 'internal';
+
+// Error payload returned by Tauri commands
+export interface CommandError {
+  code: ErrorCode;
+  message: string;
+}
+
+export function isCommandError(error: unknown): error is CommandError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as CommandError).code === 'string' &&
+    typeof (error as CommandError).message === 'string'
+  );
+}
+
+export function errorMessage(error: unknown): string {
+  if (isCommandError(error)) {
+    return error.message;
+  }
+  if (error instanceof Error) {
+    return error.message;
+  }
+  return String(error);
+}